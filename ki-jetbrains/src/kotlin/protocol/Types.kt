@@ -350,6 +350,27 @@ sealed class SelectionMode {
 	@Serializable
 	@SerialName("Paragraph")
 	object Paragraph: SelectionMode()
+	@Serializable
+	@SerialName("Textobject")
+	object Textobject: SelectionMode()
+	@Serializable
+	@SerialName("Block")
+	object Block: SelectionMode()
+	@Serializable
+	@SerialName("Sentence")
+	object Sentence: SelectionMode()
+	@Serializable
+	@SerialName("Markdown")
+	object Markdown: SelectionMode()
+	@Serializable
+	@SerialName("MergeConflict")
+	object MergeConflict: SelectionMode()
+	@Serializable
+	@SerialName("IndentationBlock")
+	object IndentationBlock: SelectionMode()
+	@Serializable
+	@SerialName("Pattern")
+	object Pattern: SelectionMode()
 }
 
 @Serializable
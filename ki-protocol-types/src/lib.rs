@@ -78,6 +78,13 @@ pub enum SelectionMode {
     LocalQuickfix,
     BigWord,
     Paragraph,
+    Textobject,
+    Block,
+    Sentence,
+    Markdown,
+    MergeConflict,
+    IndentationBlock,
+    Pattern,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -196,7 +196,14 @@ export type SelectionMode =
 	| { tag: "GitHunk", params?: undefined }
 	| { tag: "LocalQuickfix", params?: undefined }
 	| { tag: "BigWord", params?: undefined }
-	| { tag: "Paragraph", params?: undefined };
+	| { tag: "Paragraph", params?: undefined }
+	| { tag: "Textobject", params?: undefined }
+	| { tag: "Block", params?: undefined }
+	| { tag: "Sentence", params?: undefined }
+	| { tag: "Markdown", params?: undefined }
+	| { tag: "MergeConflict", params?: undefined }
+	| { tag: "IndentationBlock", params?: undefined }
+	| { tag: "Pattern", params?: undefined };
 
 export interface SelectionModeParams {
 	mode: SelectionMode;
@@ -0,0 +1,15 @@
+[
+  (argument_list)
+  (compound_statement)
+  (enumerator_list)
+  (field_declaration_list)
+  (initializer_list)
+  (parameter_list)
+] @indent
+
+(case_statement) @indent @extend
+
+[
+  "}"
+  ")"
+] @outdent
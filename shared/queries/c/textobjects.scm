@@ -0,0 +1,25 @@
+(function_definition
+  body: (compound_statement) @function.inside) @function.around
+
+(struct_specifier
+  body: (field_declaration_list) @class.inside) @class.around
+
+(union_specifier
+  body: (field_declaration_list) @class.inside) @class.around
+
+(enum_specifier
+  body: (enumerator_list) @class.inside) @class.around
+
+(parameter_list
+  (_) @parameter.inside @parameter.around)
+
+(parameter_list
+  (_) @parameter.around . "," @parameter.around)
+
+(argument_list
+  (_) @parameter.inside @parameter.around)
+
+(argument_list
+  (_) @parameter.around . "," @parameter.around)
+
+(comment) @comment.inside @comment.around
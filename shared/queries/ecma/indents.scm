@@ -0,0 +1,25 @@
+[
+  (arguments)
+  (array)
+  (array_pattern)
+  (class_body)
+  (export_clause)
+  (formal_parameters)
+  (named_imports)
+  (object)
+  (object_pattern)
+  (parenthesized_expression)
+  (statement_block)
+  (switch_body)
+] @indent
+
+[
+  (switch_case)
+  (switch_default)
+] @indent @extend
+
+[
+  "}"
+  ")"
+  "]"
+] @outdent
@@ -0,0 +1,47 @@
+(function_declaration
+  body: (statement_block) @function.inside) @function.around
+
+(function_expression
+  body: (statement_block) @function.inside) @function.around
+
+(generator_function_declaration
+  body: (statement_block) @function.inside) @function.around
+
+(arrow_function
+  body: (_) @function.inside) @function.around
+
+(method_definition
+  body: (statement_block) @function.inside) @function.around
+
+(class_declaration
+  body: (class_body) @class.inside) @class.around
+
+(class
+  body: (class_body) @class.inside) @class.around
+
+(formal_parameters
+  (_) @parameter.inside @parameter.around)
+
+(formal_parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(arguments
+  (_) @parameter.inside @parameter.around)
+
+(arguments
+  (_) @parameter.around . "," @parameter.around)
+
+(comment) @comment.inside @comment.around
+
+(
+  (call_expression
+    function: (identifier) @_test_function
+    arguments: (arguments
+      [
+        (arrow_function
+          body: (statement_block) @test.inside)
+        (function_expression
+          body: (statement_block) @test.inside)
+      ])) @test.around
+  (#any-of? @_test_function "describe" "it" "test")
+)
@@ -0,0 +1,23 @@
+[
+  (argument_list)
+  (block)
+  (const_declaration)
+  (field_declaration_list)
+  (import_spec_list)
+  (interface_type)
+  (literal_value)
+  (parameter_list)
+  (var_declaration)
+] @indent
+
+[
+  (communication_case)
+  (default_case)
+  (expression_case)
+  (type_case)
+] @indent @extend
+
+[
+  "}"
+  ")"
+] @outdent
@@ -0,0 +1,39 @@
+[
+  (class_definition)
+  (case_clause)
+  (elif_clause)
+  (else_clause)
+  (except_clause)
+  (finally_clause)
+  (for_statement)
+  (function_definition)
+  (if_statement)
+  (match_statement)
+  (try_statement)
+  (while_statement)
+  (with_statement)
+] @indent @extend
+
+[
+  (argument_list)
+  (dictionary)
+  (dictionary_comprehension)
+  (generator_expression)
+  (list)
+  (list_comprehension)
+  (parameters)
+  (parenthesized_expression)
+  (set)
+  (set_comprehension)
+  (tuple)
+] @indent
+
+[
+  "}"
+  ")"
+  "]"
+  "elif"
+  "else"
+  "except"
+  "finally"
+] @outdent
@@ -0,0 +1,42 @@
+(function_definition
+  body: (block) @function.inside) @function.around
+
+(lambda
+  body: (_) @function.inside) @function.around
+
+(class_definition
+  body: (block) @class.inside) @class.around
+
+(parameters
+  (_) @parameter.inside @parameter.around)
+
+(parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(lambda_parameters
+  (_) @parameter.inside @parameter.around)
+
+(lambda_parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(argument_list
+  (_) @parameter.inside @parameter.around)
+
+(argument_list
+  (_) @parameter.around . "," @parameter.around)
+
+(comment) @comment.inside @comment.around
+
+(
+  (function_definition
+    name: (identifier) @_test_function
+    body: (block) @test.inside) @test.around
+  (#match? @_test_function "^test")
+)
+
+(
+  (class_definition
+    name: (identifier) @_test_class
+    body: (block) @test.inside) @test.around
+  (#match? @_test_class "^Test")
+)
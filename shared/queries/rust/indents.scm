@@ -0,0 +1,26 @@
+[
+  (arguments)
+  (array_expression)
+  (block)
+  (declaration_list)
+  (enum_variant_list)
+  (field_declaration_list)
+  (field_initializer_list)
+  (match_block)
+  (ordered_field_declaration_list)
+  (parameters)
+  (token_tree)
+  (tuple_expression)
+  (tuple_pattern)
+  (tuple_type)
+  (type_arguments)
+  (type_parameters)
+  (use_list)
+  (where_clause)
+] @indent
+
+[
+  "}"
+  ")"
+  "]"
+] @outdent
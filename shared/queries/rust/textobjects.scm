@@ -0,0 +1,83 @@
+(function_item
+  body: (_) @function.inside) @function.around
+
+(function_signature_item) @function.around
+
+(closure_expression
+  body: (_) @function.inside) @function.around
+
+(struct_item
+  body: (_) @class.inside) @class.around
+
+(enum_item
+  body: (_) @class.inside) @class.around
+
+(union_item
+  body: (_) @class.inside) @class.around
+
+(trait_item
+  body: (_) @class.inside) @class.around
+
+(impl_item
+  body: (_) @class.inside) @class.around
+
+(parameters
+  (_) @parameter.inside @parameter.around)
+
+(parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(closure_parameters
+  (_) @parameter.inside @parameter.around)
+
+(closure_parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(type_parameters
+  (_) @parameter.inside @parameter.around)
+
+(type_parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(arguments
+  (_) @parameter.inside @parameter.around)
+
+(arguments
+  (_) @parameter.around . "," @parameter.around)
+
+(type_arguments
+  (_) @parameter.inside @parameter.around)
+
+(type_arguments
+  (_) @parameter.around . "," @parameter.around)
+
+(line_comment) @comment.inside @comment.around
+
+(block_comment) @comment.inside @comment.around
+
+(
+  (attribute_item
+    (attribute
+      (identifier) @_test_attribute)) @test.around
+  .
+  [(attribute_item) (line_comment)]* @test.around
+  .
+  (function_item
+    body: (_) @test.inside) @test.around
+  (#eq? @_test_attribute "test")
+)
+
+(
+  (attribute_item
+    (attribute
+      (identifier) @_cfg_attribute
+      arguments: (token_tree
+        .
+        (identifier) @_cfg_predicate
+        .))) @test.around
+  .
+  (mod_item
+    body: (_) @test.inside) @test.around
+  (#eq? @_cfg_attribute "cfg")
+  (#eq? @_cfg_predicate "test")
+)
@@ -0,0 +1,11 @@
+; inherits: ecma
+
+[
+  (enum_body)
+  (interface_body)
+  (object_type)
+  (type_arguments)
+  (type_parameters)
+] @indent
+
+">" @outdent
@@ -0,0 +1,24 @@
+; inherits: ecma
+
+(function_signature) @function.around
+
+(abstract_class_declaration
+  body: (class_body) @class.inside) @class.around
+
+(interface_declaration
+  body: (interface_body) @class.inside) @class.around
+
+(enum_declaration
+  body: (enum_body) @class.inside) @class.around
+
+(type_parameters
+  (_) @parameter.inside @parameter.around)
+
+(type_parameters
+  (_) @parameter.around . "," @parameter.around)
+
+(type_arguments
+  (_) @parameter.inside @parameter.around)
+
+(type_arguments
+  (_) @parameter.around . "," @parameter.around)
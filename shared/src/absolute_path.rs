@@ -123,6 +123,11 @@ impl AbsolutePath {
         Ok(relative.display().to_string())
     }
 
+    /// Get the home directory of the current user.
+    pub fn home_dir() -> anyhow::Result<AbsolutePath> {
+        etcetera::home_dir()?.try_into()
+    }
+
     /// If the path is relative to home, format it relative to the home directory with
     /// a leading ~ character. Otherwise it will be displayed as an absolute path.
     pub fn display_relative_to_home(&self) -> anyhow::Result<String> {
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+};
 
 use grammar::grammar::GrammarConfiguration;
 use schemars::JsonSchema;
@@ -7,7 +10,22 @@ use serde_json::Value;
 use tree_sitter::Query;
 
 pub(crate) use crate::process_command::ProcessCommand;
-use crate::{formatter::Formatter, ts_highlight_query::get_highlight_query};
+use crate::{
+    formatter::Formatter, ts_bundled_query::get_bundled_query,
+    ts_highlight_query::get_highlight_query,
+};
+
+type CompiledQueries = HashMap<
+    (
+        /* grammar id */ String,
+        /* query name */ &'static str,
+    ),
+    CompiledQuery,
+>;
+type CompiledQuery = Option<Result<Arc<Query>, String>>;
+
+/// Refer [`Language::compiled_query`].
+static COMPILED_QUERIES: LazyLock<Mutex<CompiledQueries>> = LazyLock::new(Default::default);
 
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
@@ -61,6 +79,11 @@ pub struct Language {
     pub(crate) line_comment_prefix: Option<String>,
     #[schemars(example = ("/*", "*/"))]
     pub(crate) block_comment_affixes: Option<(String, String)>,
+    /// Pairs of keywords that enclose a block, such as `begin` and `end` in Ruby.
+    /// A pair is only recognized when it delimits a node of the syntax tree.
+    #[serde(default)]
+    #[schemars(example = &[("begin", "end"), ("do", "end")])]
+    pub(crate) keyword_enclosures: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -254,6 +277,7 @@ impl Language {
             formatter: None,
             line_comment_prefix: None,
             block_comment_affixes: None,
+            keyword_enclosures: Vec::new(),
         }
     }
 
@@ -273,6 +297,10 @@ impl Language {
         self.block_comment_affixes.clone()
     }
 
+    pub fn keyword_enclosures(&self) -> &[(String, String)] {
+        &self.keyword_enclosures
+    }
+
     pub fn lsp_environment(&self) -> HashMap<String, String> {
         self.lsp_command
             .as_ref()
@@ -390,12 +418,78 @@ impl Language {
         }
     }
 
+    /// Grammars built from source may ship their own `textobjects.scm`,
+    /// which takes precedence over the textobject queries bundled with Ki.
+    pub fn textobjects_query(&self) -> Option<String> {
+        self.query_file("textobjects")
+    }
+
+    /// Like [`Language::textobjects_query`], but compiled, refer [`Language::compiled_query`].
+    pub fn compiled_textobjects_query(&self) -> anyhow::Result<Option<Arc<Query>>> {
+        self.compiled_query("textobjects")
+    }
+
+    /// Like [`Language::textobjects_query`], but for `indents.scm`.
+    pub fn indents_query(&self) -> Option<String> {
+        self.query_file("indents")
+    }
+
+    /// Like [`Language::indents_query`], but compiled, refer [`Language::compiled_query`].
+    pub fn compiled_indents_query(&self) -> anyhow::Result<Option<Arc<Query>>> {
+        self.compiled_query("indents")
+    }
+
+    /// Compiling a query is too slow to be done on every keystroke,
+    /// so the result is cached per grammar, including the compilation error.
+    /// Returns `Ok(None)` if this language has no such query.
+    fn compiled_query(&self, query_name: &'static str) -> anyhow::Result<Option<Arc<Query>>> {
+        let Some(grammar_id) = self.tree_sitter_grammar_id() else {
+            return Ok(None);
+        };
+        let mut compiled_queries = COMPILED_QUERIES
+            .lock()
+            .map_err(|error| anyhow::anyhow!("{error}"))?;
+        compiled_queries
+            .entry((grammar_id, query_name))
+            .or_insert_with(|| {
+                let query = self.query_file(query_name)?;
+                let language = self.tree_sitter_language()?;
+                Some(
+                    Query::new(&language, &query)
+                        .map(Arc::new)
+                        .map_err(|error| format!("{error:?}")),
+                )
+            })
+            .clone()
+            .transpose()
+            .map_err(|error| anyhow::anyhow!("Unable to compile {query_name}.scm: {error}"))
+    }
+
+    fn query_file(&self, query_name: &str) -> Option<String> {
+        let config = self.tree_sitter_grammar_config.as_ref()?;
+        match &config.kind {
+            GrammarConfigKind::CargoLinked(_) => get_bundled_query(&config.id, query_name),
+            GrammarConfigKind::FromSource { .. } => {
+                grammar::grammar::load_runtime_file(&config.id, &format!("{query_name}.scm"))
+                    .ok()
+                    .or_else(|| get_bundled_query(&config.id, query_name))
+            }
+        }
+    }
+
     pub fn locals_query(&self) -> Option<&'static str> {
         None
     }
 
-    pub fn injection_query(&self) -> Option<&'static str> {
-        None
+    /// Like [`Language::textobjects_query`], but for `injections.scm`,
+    /// which specifies the regions of the content that are written in another language.
+    pub fn injection_query(&self) -> Option<String> {
+        self.query_file("injections")
+    }
+
+    /// Like [`Language::injection_query`], but compiled, refer [`Language::compiled_query`].
+    pub fn compiled_injection_query(&self) -> anyhow::Result<Option<Arc<Query>>> {
+        self.compiled_query("injections")
     }
 
     pub fn lsp_process_command(&self) -> Option<ProcessCommand> {
@@ -10,6 +10,13 @@ fn to_vec(slice: &[&'static str]) -> Vec<String> {
     slice.iter().map(|s| s.to_string()).collect()
 }
 
+fn to_pairs(slice: &[(&'static str, &'static str)]) -> Vec<(String, String)> {
+    slice
+        .iter()
+        .map(|(open, close)| (open.to_string(), close.to_string()))
+        .collect()
+}
+
 pub fn languages() -> HashMap<String, Language> {
     [
         ("bash", bash()),
@@ -353,6 +360,7 @@ fn elixir() -> Language {
             kind: GrammarConfigKind::CargoLinked(CargoLinkedTreesitterLanguage::Elixir),
         }),
         line_comment_prefix: Some("#".to_string()),
+        keyword_enclosures: to_pairs(&[("do", "end"), ("fn", "end")]),
         ..Language::new()
     }
 }
@@ -967,6 +975,16 @@ fn ruby() -> Language {
             kind: GrammarConfigKind::CargoLinked(CargoLinkedTreesitterLanguage::Ruby),
         }),
         line_comment_prefix: Some("#".to_string()),
+        keyword_enclosures: to_pairs(&[
+            ("begin", "end"),
+            ("do", "end"),
+            ("def", "end"),
+            ("class", "end"),
+            ("module", "end"),
+            ("if", "end"),
+            ("unless", "end"),
+            ("case", "end"),
+        ]),
         ..Language::new()
     }
 }
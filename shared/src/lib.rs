@@ -5,4 +5,5 @@ pub mod icons;
 pub mod language;
 pub mod languages;
 pub mod process_command;
+pub(crate) mod ts_bundled_query;
 pub mod ts_highlight_query;
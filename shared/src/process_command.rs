@@ -80,12 +80,12 @@ impl ProcessCommand {
         }
     }
 
-    pub fn run_with_input(&self, input: &str) -> anyhow::Result<String> {
+    pub fn run_with_input(&self, input: impl AsRef<[u8]>) -> anyhow::Result<String> {
         let mut child = self.spawn()?;
 
         if let Some(mut stdin) = child.stdin.take() {
             stdin
-                .write_all(input.as_bytes())
+                .write_all(input.as_ref())
                 .context("Failed to write to stdin")?;
         } else {
             return Err(anyhow::anyhow!("Failed to open stdin"));
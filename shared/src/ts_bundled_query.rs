@@ -0,0 +1,85 @@
+use crate::ts_highlight_query::get_highlight_query_parents;
+
+/// Tree-sitter queries bundled with Ki, keyed by grammar ID and query name.
+///
+/// Parents are declared using the same `; inherits:` comment as nvim-treesitter.
+fn get_bundled_query_content(grammar_id: &str, query_name: &str) -> Option<&'static str> {
+    match (query_name, grammar_id) {
+        ("textobjects", "c") => Some(include_str!("../queries/c/textobjects.scm")),
+        ("textobjects", "ecma") => Some(include_str!("../queries/ecma/textobjects.scm")),
+        ("textobjects", "go") => Some(include_str!("../queries/go/textobjects.scm")),
+        ("textobjects", "python") => Some(include_str!("../queries/python/textobjects.scm")),
+        ("textobjects", "rust") => Some(include_str!("../queries/rust/textobjects.scm")),
+        ("textobjects", "typescript" | "tsx") => {
+            Some(include_str!("../queries/typescript/textobjects.scm"))
+        }
+        ("indents", "c") => Some(include_str!("../queries/c/indents.scm")),
+        ("indents", "ecma") => Some(include_str!("../queries/ecma/indents.scm")),
+        ("indents", "go") => Some(include_str!("../queries/go/indents.scm")),
+        ("indents", "json") => Some(include_str!("../queries/json/indents.scm")),
+        ("indents", "python") => Some(include_str!("../queries/python/indents.scm")),
+        ("indents", "rust") => Some(include_str!("../queries/rust/indents.scm")),
+        ("indents", "typescript" | "tsx") => {
+            Some(include_str!("../queries/typescript/indents.scm"))
+        }
+        ("injections", "html") => Some(include_str!("../queries/html/injections.scm")),
+        ("injections", "markdown") => Some(include_str!("../queries/markdown/injections.scm")),
+        ("injections", "svelte") => Some(include_str!("../queries/svelte/injections.scm")),
+        (_, "javascript" | "jsx") => Some("; inherits: ecma"),
+        _ => None,
+    }
+}
+
+/// Get the bundled query of the given grammar, including the queries of its parents.
+pub(crate) fn get_bundled_query(grammar_id: &str, query_name: &str) -> Option<String> {
+    let current = get_bundled_query_content(grammar_id, query_name)?;
+    let parent = get_highlight_query_parents(current)
+        .into_iter()
+        .map(|parent| get_bundled_query(&parent, query_name))
+        .collect::<Option<Vec<_>>>()?
+        .join("\n\n");
+    Some(format!("{parent}\n\n{current}"))
+}
+
+#[cfg(test)]
+mod test_ts_bundled_query {
+    use super::*;
+
+    #[test]
+    fn bundled_queries_are_valid() {
+        let languages: [(&str, tree_sitter::Language); 11] = [
+            ("c", tree_sitter_c::LANGUAGE.into()),
+            ("go", tree_sitter_go::LANGUAGE.into()),
+            ("html", tree_sitter_html::LANGUAGE.into()),
+            ("markdown", tree_sitter_md::LANGUAGE.into()),
+            ("svelte", tree_sitter_svelte_ng::LANGUAGE.into()),
+            ("javascript", tree_sitter_javascript::LANGUAGE.into()),
+            ("json", tree_sitter_json::LANGUAGE.into()),
+            ("python", tree_sitter_python::LANGUAGE.into()),
+            ("rust", tree_sitter_rust::LANGUAGE.into()),
+            (
+                "typescript",
+                tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            ),
+            ("tsx", tree_sitter_typescript::LANGUAGE_TSX.into()),
+        ];
+        for (grammar_id, language) in languages {
+            for query_name in ["textobjects", "indents", "injections"] {
+                let Some(query) = get_bundled_query(grammar_id, query_name) else {
+                    continue;
+                };
+                if let Err(error) = tree_sitter::Query::new(&language, &query) {
+                    panic!("Invalid {query_name} query for {grammar_id}: {error:?}")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inherits_parent_query() {
+        let query = get_bundled_query("tsx", "textobjects").unwrap();
+        assert!(query.contains("(interface_declaration"));
+        assert!(query.contains("(arrow_function"));
+        assert!(get_bundled_query("Not a Language", "textobjects").is_none());
+    }
+}
@@ -22,7 +22,7 @@ pub(crate) fn get_highlight_query(language_id: &str) -> Option<GetHighlightQuery
 ///
 /// Reference:
 ///   - https://github.com/nvim-treesitter/nvim-treesitter/blob/8f5513a1f2ec6ee5b378c2e32e53fc3c2a8f1e13/CONTRIBUTING.md#inheriting-languages
-fn get_highlight_query_parents(content: &str) -> Vec<String> {
+pub(crate) fn get_highlight_query_parents(content: &str) -> Vec<String> {
     regex::Regex::new(r"inherits:\s*([\w,]+)")
         .unwrap()
         .captures(content)
@@ -22,13 +22,13 @@ use crate::{
     },
     config::AppConfig,
     context::{
-        Context, FormatterCommand, GlobalMode, GlobalSearchConfig, LocalSearchConfigMode,
-        QuickfixListKind, QuickfixListSource, Search,
+        Context, FormatterCommand, GlobalMode, GlobalSearchConfig, GlobalSearchLimit,
+        GlobalSearchScope, LocalSearchConfigMode, QuickfixListKind, QuickfixListSource, Search,
     },
     edit::Edit,
     file_watcher::{FileWatcherEvent, FileWatcherInput},
     frontend::Frontend,
-    git::{self},
+    git::{self, GitOperation},
     grid::{Grid, StyleKey},
     integration_event::{IntegrationEvent, IntegrationEventEmitter},
     keymap_override::{
@@ -36,14 +36,15 @@ use crate::{
         EditorKeymapOverride, KeymapOverrideScope, KeymapOverrideTrait,
     },
     layout::Layout,
-    list::{self, Match, WalkBuilderConfig},
+    list::{self, replace_preview::ReplacePreview, Match, WalkBuilderConfig, WalkProgress},
     lsp::{
-        completion::CompletionItem,
+        code_action::CodeAction,
+        completion::{CompletionItem, PositionalEdit},
         goto_definition_response::GotoDefinitionResponse,
         manager::LspManager,
         process::{CallHierarchyDirection, FromEditor, LspNotification, ResponseContext},
         symbols::Symbols,
-        workspace_edit::WorkspaceEdit,
+        workspace_edit::{TextDocumentEdit, WorkspaceEdit},
     },
     multibuffer::Multibuffer,
     persistence::Persistence,
@@ -52,10 +53,11 @@ use crate::{
     render_flex_layout::{self, FlexLayoutComponent},
     screen::{Screen, Window},
     scripting::{custom_keymap, ScriptDispatch, ScriptInput},
-    search::parse_search_config,
+    search::{parse_search_config, parse_search_scope_update},
     selection::{CharIndex, SelectionMode},
     syntax_highlight::{HighlightedSpans, SyntaxHighlightRequest, SyntaxHighlightRequestBatchId},
-    thread::{debounce, Callback, SendResult},
+    thread::{debounce, Callback, Interval, SendResult},
+    transformation::{MyRegex, Reorder, SortKey, Transformation},
     ui_tree::{ComponentKind, KindedComponent},
 };
 use anyhow::ensure;
@@ -72,7 +74,7 @@ use shared::{absolute_path::AbsolutePath, language::Language};
 use std::{
     any::TypeId,
     cell::RefCell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     rc::Rc,
     sync::{mpsc::Sender, Mutex},
@@ -124,7 +126,20 @@ pub struct App<T: Frontend> {
     /// Used for debouncing LSP Completion request, so that we don't overwhelm
     /// the server with too many requests, and also Ki with too many incoming Completion responses
     debounce_lsp_request_completion: Callback<()>,
+    /// Used for auto-saving after the user stops interacting for a while
+    debounce_auto_save: Callback<()>,
+    /// Files whose save conflict has already been surfaced by auto-save,
+    /// so that the conflict prompt is not shown again on every idle period
+    auto_save_conflicts: HashSet<AbsolutePath>,
     pub multibuffer: Option<Multibuffer>,
+    /// The global search that is running in the background, if any.
+    global_search: Option<RunningGlobalSearch>,
+}
+
+struct RunningGlobalSearch {
+    progress: WalkProgress,
+    /// Re-renders the progress periodically.
+    interval: Interval,
 }
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema)]
@@ -160,6 +175,14 @@ pub enum StatusLineComponent {
     LspProgress,
     /// The detected language for the current file
     Language,
+    /// Shown only when the current file is opened in large-file mode
+    LargeFileMode,
+    /// Shown only when the current buffer is read-only
+    ReadOnly,
+    /// The indentation used by the current file, such as "Spaces:4" or "Tabs"
+    Indentation,
+    /// The number of files scanned and matches found, shown only while a global search is running
+    GlobalSearchProgress,
 }
 
 impl<T: Frontend> App<T> {
@@ -250,6 +273,20 @@ impl<T: Frontend> App<T> {
                     Duration::from_millis(300),
                 )
             },
+            debounce_auto_save: {
+                let sender = sender.clone();
+                debounce(
+                    Callback::new(Arc::new(move |_| {
+                        if let Err(err) =
+                            sender.send(AppMessage::ExternalDispatch(Box::new(Dispatch::AutoSave)))
+                        {
+                            log::error!("Failed to send AutoSave to App due to {err:?}");
+                        }
+                    })),
+                    Duration::from_millis(AppConfig::singleton().auto_save().idle_delay_ms),
+                )
+            },
+            auto_save_conflicts: HashSet::new(),
             sender,
             keymap_override: None,
             layout: Layout::new(
@@ -266,6 +303,7 @@ impl<T: Frontend> App<T> {
             queued_events: Vec::new(),
             file_watcher_input_sender,
             multibuffer: None,
+            global_search: None,
         };
 
         app.restore_session();
@@ -296,12 +334,22 @@ impl<T: Frontend> App<T> {
     }
 
     /// This is the main event loop.
-    pub fn run(mut self, entry_path: Option<AbsolutePath>) -> Result<(), anyhow::Error> {
+    /// `large_file_mode` overrides whether `entry_path` is opened in large-file mode.
+    pub fn run(
+        mut self,
+        entry_path: Option<AbsolutePath>,
+        large_file_mode: Option<bool>,
+    ) -> Result<(), anyhow::Error> {
         self.set_terminal_options()?;
 
         let first_dispatch = entry_path.map(|entry_path| {
             if entry_path.as_ref().is_dir() {
                 Dispatch::OpenFileExplorer
+            } else if let Some(large_file_mode) = large_file_mode {
+                Dispatch::OpenFileWithLargeFileMode {
+                    path: entry_path,
+                    large_file_mode,
+                }
             } else {
                 Dispatch::OpenFile {
                     path: entry_path,
@@ -375,11 +423,17 @@ impl<T: Frontend> App<T> {
                 Ok(false)
             }
             AppMessage::GlobalSearchFinished => {
-                // Does nothing because this is used for testing only at the moment
-                // In the future we can possibly update the UI to notify the user
-                // that the global search is completed
+                // The message of a cancelled search can arrive after a new search is started
+                if self
+                    .global_search
+                    .as_ref()
+                    .is_some_and(|global_search| global_search.progress.is_finished())
+                {
+                    self.cancel_global_search();
+                }
                 Ok(false)
             }
+            AppMessage::GlobalSearchProgressed => Ok(false),
         }
     }
 
@@ -444,7 +498,17 @@ impl<T: Frontend> App<T> {
                     width: columns as usize,
                 });
             }
+            Event::FocusLost => {
+                self.auto_save().map(|_| ()).unwrap_or_else(|e| {
+                    self.show_global_info(Info::new("ERROR".to_string(), e.to_string()));
+                });
+            }
             event => {
+                if self.context.auto_save().enabled
+                    && matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_))
+                {
+                    self.debounce_auto_save.call(());
+                }
                 let dispatches = match (event, &mut self.keymap_override) {
                     (Event::Key(key_event), Some(keymap_override)) => match key_event.kind {
                         KeyEventKind::Press => {
@@ -677,12 +741,35 @@ impl<T: Frontend> App<T> {
                         StatusLineComponent::LspProgress => {
                             Some(FlexLayoutComponent::Text(self.context.lsp_progress()))
                         }
+                        StatusLineComponent::GlobalSearchProgress => {
+                            self.global_search.as_ref().map(|global_search| {
+                                FlexLayoutComponent::Text(global_search.progress.display())
+                            })
+                        }
                         StatusLineComponent::Language => self
                             .current_component()
                             .borrow()
                             .editor()
                             .language()
                             .map(FlexLayoutComponent::Text),
+                        StatusLineComponent::LargeFileMode => self
+                            .current_component()
+                            .borrow()
+                            .editor()
+                            .display_large_file_mode()
+                            .map(FlexLayoutComponent::Text),
+                        StatusLineComponent::ReadOnly => self
+                            .current_component()
+                            .borrow()
+                            .editor()
+                            .display_read_only()
+                            .map(FlexLayoutComponent::Text),
+                        StatusLineComponent::Indentation => self
+                            .current_component()
+                            .borrow()
+                            .editor()
+                            .display_indentation(&self.context)
+                            .map(FlexLayoutComponent::Text),
                     })
                     .collect_vec(),
             )
@@ -794,13 +881,35 @@ impl<T: Frontend> App<T> {
                 prior_change,
             )?,
             Dispatch::OpenPipeToShellPrompt => self.open_pipe_to_shell_prompt()?,
+            Dispatch::OpenSortByRegexPrompt => self.open_sort_by_regex_prompt()?,
             Dispatch::OpenFile { path, owner, focus } => {
-                self.open_file(&path, owner, true, focus)?;
+                if !focus || self.auto_save_before_leaving_current_file(&path)? {
+                    self.open_file(&path, owner, true, focus)?;
+                }
             }
             Dispatch::OpenFileFromPathBuf { path, owner, focus } => {
                 let canonicalized_path = path.try_into()?;
                 self.open_file(&canonicalized_path, owner, true, focus)?;
             }
+            Dispatch::OpenFileWithLargeFileMode {
+                path,
+                large_file_mode,
+            } => {
+                if self.auto_save_before_leaving_current_file(&path)? {
+                    self.open_file_with_large_file_mode(
+                        &path,
+                        BufferOwner::User,
+                        true,
+                        true,
+                        Some(large_file_mode),
+                    )?;
+                }
+            }
+            Dispatch::OpenFileAtPosition { path, position } => {
+                if self.auto_save_before_leaving_current_file(&path)? {
+                    self.open_file_at_position(&path, position)?;
+                }
+            }
             Dispatch::OpenFilePicker(kind) => {
                 self.open_file_picker(kind)?;
             }
@@ -969,7 +1078,11 @@ impl<T: Frontend> App<T> {
                 }
             }
             Dispatch::RequestCodeAction { diagnostics } => {
-                if let Some(params) = self.get_request_params() {
+                let params = self.get_request_params().filter(|params| {
+                    // The code actions of an embedding editor are requested by the integration event
+                    self.is_running_as_embedded() || self.lsp_manager().has_server(&params.path)
+                });
+                if let Some(params) = params {
                     self.lsp_manager().send_message(
                         params.path.clone(),
                         FromEditor::TextDocumentCodeAction {
@@ -978,6 +1091,12 @@ impl<T: Frontend> App<T> {
                         },
                     )?;
                     self.send_integration_event(IntegrationEvent::RequestLspCodeAction);
+                } else {
+                    // Without a language server, only the fixes of the ast-grep rules are offered
+                    let code_actions = self.ast_grep_code_actions();
+                    if !code_actions.is_empty() {
+                        self.open_code_actions_picker(code_actions)?;
+                    }
                 }
             }
             Dispatch::RequestSignatureHelp => {
@@ -1034,8 +1153,14 @@ impl<T: Frontend> App<T> {
                 }
                 self.lsp_manager().send_message(
                     path.clone(),
-                    FromEditor::TextDocumentDidSave { file_path: path },
+                    FromEditor::TextDocumentDidSave {
+                        file_path: path.clone(),
+                    },
                 )?;
+                // An invalid rule file should not prevent the save from being completed
+                if let Err(error) = self.update_lints_on_save(&path) {
+                    self.show_global_info(Info::new("Error".to_string(), format!("{error:?}")));
+                }
             }
             Dispatch::SetQuickfixList(r#type) => {
                 self.set_quickfix_list_type(ResponseContext::default(), r#type)?;
@@ -1149,6 +1274,7 @@ impl<T: Frontend> App<T> {
                 self.handle_event(event)?;
             }
             Dispatch::GetRepoGitHunks(diff_mode) => self.get_repo_git_hunks(diff_mode)?,
+            Dispatch::GetRepoMergeConflicts => self.get_repo_merge_conflicts()?,
             Dispatch::SaveAll => self.save_all()?,
             #[cfg(test)]
             Dispatch::TerminalDimensionChanged(dimension) => self.resize(dimension),
@@ -1176,12 +1302,23 @@ impl<T: Frontend> App<T> {
             Dispatch::UpdateGlobalSearchConfig { update } => {
                 self.update_global_search_config(update)?;
             }
+            Dispatch::UpdateGlobalSearchScope(update) => {
+                self.context.update_global_search_scope(update)?;
+            }
+            Dispatch::OpenGlobalSearchScopePrompt => self.open_global_search_scope_prompt()?,
+            Dispatch::CancelGlobalSearch => self.cancel_global_search(),
             Dispatch::Replace { scope } => match scope {
                 Scope::Local => self.handle_dispatch_editor(ReplacePattern {
                     config: self.context.local_search_config(Scope::Local).clone(),
                 })?,
                 Scope::Global => self.global_replace()?,
             },
+            Dispatch::PreviewGlobalReplace => self.preview_global_replace()?,
+            Dispatch::PreviewAstGrepFixes => self.preview_ast_grep_fixes()?,
+            Dispatch::ExcludeFromReplacePreview { whole_file } => {
+                self.exclude_from_replace_preview(whole_file)?
+            }
+            Dispatch::ApplyReplacePreview => self.apply_replace_preview()?,
             #[cfg(test)]
             Dispatch::HandleLspNotification(notification) => {
                 self.handle_lsp_notification(notification)?;
@@ -1216,6 +1353,11 @@ impl<T: Frontend> App<T> {
             }
             Dispatch::ShowEditorInfo(info) => self.show_editor_info(info)?,
             Dispatch::ReceiveCodeActions(code_actions) => {
+                let code_actions = self
+                    .ast_grep_code_actions()
+                    .into_iter()
+                    .chain(code_actions)
+                    .collect();
                 self.open_code_actions_picker(code_actions)?;
             }
             Dispatch::OtherWindow => self.layout.cycle_window(),
@@ -1250,6 +1392,7 @@ impl<T: Frontend> App<T> {
                 self.keyboard_layout_changed();
             }
             Dispatch::OpenKeyboardLayoutPrompt => self.open_keyboard_layout_picker()?,
+            Dispatch::OpenIndentationPrompt => self.open_indentation_picker()?,
             Dispatch::MovementHistoryNavigation(movement) => {
                 self.movement_history_navigation(movement)?;
             }
@@ -1325,6 +1468,15 @@ impl<T: Frontend> App<T> {
             }
             Dispatch::ToggleRevealSelections => self.toggle_reveal_selections()?,
             Dispatch::SaveFile => self.save()?,
+            Dispatch::ToggleLargeFileMode => self.toggle_large_file_mode()?,
+            Dispatch::AutoSave => {
+                self.auto_save()?;
+            }
+            #[cfg(test)]
+            Dispatch::SetAutoSaveConfig(auto_save) => self.context.set_auto_save(auto_save),
+            Dispatch::ToggleReadOnly => self.toggle_read_only(),
+            Dispatch::OpenFileAtRevision(diff_mode) => self.open_file_at_revision(diff_mode)?,
+            Dispatch::SaveWithElevatedPrivileges => self.save_with_elevated_privileges()?,
         }
         Ok(())
     }
@@ -1464,7 +1616,13 @@ impl<T: Frontend> App<T> {
     ) -> anyhow::Result<()> {
         self.open_prompt({
             PromptConfig::new(
-                format!("{scope:?} search",),
+                match scope {
+                    Scope::Local => "Local search".to_string(),
+                    Scope::Global => match self.context.global_search_scope().display() {
+                        scope if scope.is_empty() => "Global search".to_string(),
+                        scope => format!("Global search ({scope})"),
+                    },
+                },
                 PromptOnEnter::ParseCurrentLine {
                     parser: DispatchParser::UpdateLocalSearchConfigSearch {
                         scope,
@@ -1643,6 +1801,19 @@ impl<T: Frontend> App<T> {
         owner: BufferOwner,
         store_history: bool,
         focus: bool,
+    ) -> anyhow::Result<Rc<RefCell<SuggestiveEditor>>> {
+        self.open_file_with_large_file_mode(path, owner, store_history, focus, None)
+    }
+
+    /// `large_file_mode` overrides whether the file is opened in large-file mode,
+    /// which is otherwise decided by `buffer::is_large_file`.
+    pub fn open_file_with_large_file_mode(
+        &mut self,
+        path: &AbsolutePath,
+        owner: BufferOwner,
+        store_history: bool,
+        focus: bool,
+        large_file_mode: Option<bool>,
     ) -> anyhow::Result<Rc<RefCell<SuggestiveEditor>>> {
         if !path.exists() {
             return Err(anyhow::anyhow!(
@@ -1664,13 +1835,18 @@ impl<T: Frontend> App<T> {
 
         // Check if the file is opened before so that we won't notify the LSP twice
         if let Some(matching_editor) = self.layout.open_file(path, focus) {
+            if let Some(large_file_mode) = large_file_mode {
+                self.set_large_file_mode(matching_editor.clone(), large_file_mode)?;
+            }
             return Ok(matching_editor);
         }
 
-        let mut buffer = Buffer::from_path(path, true)?;
+        let mut buffer = Buffer::from_path_with_large_file_mode(path, true, large_file_mode)?;
         buffer.set_owner(owner);
+        self.lint_buffer(&mut buffer);
 
         let language = buffer.language();
+        let large_file_mode = buffer.large_file_mode();
         let content = buffer.content();
         let batch_id = buffer.batch_id().clone();
         let buffer = Rc::new(RefCell::new(buffer));
@@ -1688,11 +1864,13 @@ impl<T: Frontend> App<T> {
             self.layout
                 .replace_and_focus_current_suggestive_editor(component.clone());
         }
-        if let Some(language) = language {
-            self.request_syntax_highlight(component_id, batch_id, language, content)?;
-        }
-        if self.enable_lsp {
-            self.lsp_manager().open_file(path.clone())?;
+        if !large_file_mode {
+            if let Some(language) = language {
+                self.request_syntax_highlight(component_id, batch_id, language, content)?;
+            }
+            if self.enable_lsp {
+                self.lsp_manager().open_file(path.clone())?;
+            }
         }
 
         self.send_file_watcher_input(FileWatcherInput::SyncOpenedPaths(
@@ -1934,6 +2112,26 @@ impl<T: Frontend> App<T> {
         Ok(())
     }
 
+    /// Unlike `go_to_location`, the position is resolved against the opened buffer,
+    /// which may have unsaved changes, and is clamped to its content.
+    fn open_file_at_position(
+        &mut self,
+        path: &AbsolutePath,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        let component = self.open_file(path, BufferOwner::User, true, true)?;
+        let char_index = component
+            .borrow()
+            .editor()
+            .buffer()
+            .position_to_char(position)?;
+        let dispatches = component
+            .borrow_mut()
+            .editor_mut()
+            .set_char_index_range((char_index..char_index + 1).into(), &self.context)?;
+        self.handle_dispatches(dispatches)
+    }
+
     fn set_quickfix_list_type(
         &mut self,
         context: ResponseContext,
@@ -1972,6 +2170,10 @@ impl<T: Frontend> App<T> {
             }
             QuickfixListType::Items(items) => (None, QuickfixListSource::Custom(items)),
             QuickfixListType::Mark => (Some(QuickfixListKind::Mark), QuickfixListSource::Mark),
+            QuickfixListType::ReplacePreview(items) => (
+                Some(QuickfixListKind::ReplacePreview),
+                QuickfixListSource::Custom(items),
+            ),
         };
 
         let items = self.layout.get_quickfix_list_items(&source, &self.context);
@@ -2047,39 +2249,283 @@ impl<T: Frontend> App<T> {
         self.layout.recalculate_layout(&self.context);
     }
 
-    fn global_replace(&mut self) -> anyhow::Result<()> {
-        let working_directory = self.working_directory().clone();
+    /// The files of the working directory that match the globs of the global search config
+    /// and the global search scope.
+    fn global_walk_builder_config(&self) -> anyhow::Result<WalkBuilderConfig> {
         let global_search_config = self.context.global_search_config();
-        let walk_builder_config = WalkBuilderConfig {
-            root: working_directory.clone().into(),
-            include: global_search_config.include_glob(),
-            exclude: global_search_config.exclude_glob(),
+        let scope = self.context.global_search_scope();
+        let paths = match scope.limit {
+            GlobalSearchLimit::WorkingDirectory => None,
+            GlobalSearchLimit::CurrentDirectory => self
+                .current_component()
+                .borrow()
+                .editor()
+                .path()
+                .and_then(|path| path.parent().ok().flatten())
+                .map(|directory| vec![directory.to_path_buf().clone()]),
+            GlobalSearchLimit::MarkedFiles => {
+                let marked_files = self.context.get_marked_files();
+                if marked_files.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "The search scope is limited to the marked files, but no files are marked."
+                    ));
+                }
+                Some(
+                    marked_files
+                        .into_iter()
+                        .map(|path| path.to_path_buf().clone())
+                        .collect(),
+                )
+            }
         };
-        let config = self.context.global_search_config().local_config();
-        let (dispatches, affected_paths) =
+        Ok(WalkBuilderConfig {
+            root: self.working_directory().clone().into(),
+            include: global_search_config.include_globs(),
+            exclude: global_search_config.exclude_globs(),
+            include_hidden: scope.include_hidden,
+            include_ignored: scope.include_ignored,
+            file_name_globs: scope.file_name_globs()?,
+            paths,
+            buffer_contents: Arc::new(self.layout.get_opened_buffer_contents()),
+            progress: WalkProgress::default(),
+        })
+    }
+
+    fn global_replace(&mut self) -> anyhow::Result<()> {
+        let walk_builder_config = self.global_walk_builder_config()?;
+        let config = self.context.global_search_config().local_config().clone();
+        let (dispatches, opened_paths) =
             list::grep::replace(&self.context, walk_builder_config, config.clone())?;
         self.handle_dispatches(dispatches)?;
-        let dispatches = self.layout.reload_buffers(&self.context, affected_paths)?;
-        self.handle_dispatches(dispatches)
+        for path in opened_paths {
+            let Some(editor) = self.layout.get_existing_editor(&path) else {
+                continue;
+            };
+            let buffer = editor.borrow().editor().buffer_rc();
+            let dirty = buffer.borrow().dirty(&self.context);
+            self.handle_dispatch_editor_custom(
+                DispatchEditor::ReplacePattern {
+                    config: config.clone(),
+                },
+                editor,
+            )?;
+            // Buffers with unsaved changes are left unsaved,
+            // so that the replacement does not save those changes without the user knowing
+            if !dirty {
+                let (dispatches, _) = buffer
+                    .borrow_mut()
+                    .save_without_formatting(&self.context, false)?;
+                self.handle_dispatches(dispatches)?;
+            }
+        }
+        Ok(())
     }
 
-    fn global_search(&mut self) -> anyhow::Result<()> {
-        let working_directory = self.working_directory().clone();
+    fn preview_global_replace(&mut self) -> anyhow::Result<()> {
+        let global_search_config = self.context.global_search_config();
+        let walk_builder_config = self.global_walk_builder_config()?;
+        let preview = list::grep::preview_replace(
+            walk_builder_config,
+            global_search_config.local_config().clone(),
+        )?;
+        self.show_replace_preview(preview, "Replace Preview")
+    }
+
+    fn preview_ast_grep_fixes(&mut self) -> anyhow::Result<()> {
+        let rules = self.context.ast_grep_rules().to_vec();
+        if rules.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No ast-grep rules are loaded. Rules are loaded from the directories listed by `ruleDirs` of `sgconfig.yml` in the working directory."
+            ));
+        }
+        let preview = list::ast_grep::preview_fixes(rules, self.global_walk_builder_config()?)?;
+        self.show_replace_preview(preview, "ast-grep Fixes")
+    }
+
+    fn show_replace_preview(
+        &mut self,
+        preview: ReplacePreview,
+        description: &str,
+    ) -> anyhow::Result<()> {
+        let items = preview.quickfix_list_items();
+        self.context.set_replace_preview(preview);
+        self.set_quickfix_list_type(
+            ResponseContext::default().set_description(description),
+            QuickfixListType::ReplacePreview(items),
+        )
+    }
+
+    /// Updates the matches of the ast-grep rules of the project in `buffer`.
+    fn lint_buffer(&self, buffer: &mut Buffer) {
+        if buffer.large_file_mode() {
+            return;
+        }
+        let Some(language) = buffer.language() else {
+            return;
+        };
+        let rule_matches = crate::ast_grep_rule::find_matches(
+            self.context.ast_grep_rules(),
+            &language,
+            &buffer.content(),
+        );
+        buffer.set_lints(rule_matches);
+    }
+
+    /// Saving a YAML file reloads the ast-grep rules, because it might be one of them,
+    /// and then lints every opened buffer again.
+    fn update_lints_on_save(&mut self, path: &AbsolutePath) -> anyhow::Result<()> {
+        let paths = if path
+            .extension()
+            .is_some_and(|extension| extension == "yml" || extension == "yaml")
+        {
+            self.context.reload_ast_grep_rules()?;
+            self.layout.get_opened_files()
+        } else {
+            vec![path.clone()]
+        };
+        for path in paths {
+            if let Some(editor) = self.layout.get_existing_editor(&path) {
+                let buffer = editor.borrow().editor().buffer_rc();
+                self.lint_buffer(&mut buffer.borrow_mut());
+            }
+        }
+        Ok(())
+    }
 
+    /// The fixes of the ast-grep rules matched at the cursor of the current editor.
+    fn ast_grep_code_actions(&self) -> Vec<CodeAction> {
+        let component = self.current_component();
+        let component = component.borrow();
+        let editor = component.editor();
+        let Some(path) = editor.path() else {
+            return Vec::new();
+        };
+        let cursor_char_index = editor.get_cursor_char_index();
+        let buffer = editor.buffer();
+        buffer
+            .lints()
+            .iter()
+            .filter(|lint| lint.diagnostic.range.contains(&cursor_char_index))
+            .filter_map(|lint| {
+                let range = buffer
+                    .char_index_range_to_position_range(lint.diagnostic.range)
+                    .ok()?;
+                Some(CodeAction {
+                    title: format!(
+                        "Fix: {}",
+                        lint.diagnostic.message.lines().next().unwrap_or_default()
+                    ),
+                    kind: Some("ast-grep".to_string()),
+                    edit: Some(WorkspaceEdit {
+                        edits: vec![TextDocumentEdit {
+                            path: path.clone(),
+                            edits: vec![PositionalEdit {
+                                range,
+                                new_text: lint.fix.clone()?,
+                            }],
+                        }],
+                        resource_operations: Vec::new(),
+                    }),
+                    command: None,
+                })
+            })
+            .collect()
+    }
+
+    fn exclude_from_replace_preview(&mut self, whole_file: bool) -> anyhow::Result<()> {
+        let Some(QuickfixListKind::ReplacePreview) = self.context.quickfix_list().kind() else {
+            return Err(anyhow::anyhow!(
+                "The quickfix list is not a replace preview."
+            ));
+        };
+        let Some(location) = self.context.quickfix_list().current_location() else {
+            return Ok(());
+        };
+        let Some(preview) = self.context.replace_preview_mut() else {
+            return Ok(());
+        };
+        preview.exclude(&location, whole_file);
+        let items = preview.quickfix_list_items();
+        self.update_quickfix_list_item(QuickfixListType::ReplacePreview(items));
+        self.render_quickfix_list()?;
+        self.goto_quickfix_list_item(Movement::Current(IfCurrentNotFound::LookForward))
+    }
+
+    /// Each file is changed in one transaction, so that it can be undone at once.
+    /// Files that were modified since the preview are skipped.
+    fn apply_replace_preview(&mut self) -> anyhow::Result<()> {
+        let Some(preview) = self.context.take_replace_preview() else {
+            return Err(anyhow::anyhow!("There is no replace preview to apply."));
+        };
+        let mut changes_count = 0;
+        let mut affected_paths = Vec::new();
+        let mut skipped_paths = Vec::new();
+        for file in preview.into_files() {
+            let editor = self.open_file(&file.path, BufferOwner::System, false, false)?;
+            let buffer = editor.borrow().editor().buffer_rc();
+            if buffer.borrow().content() != file.content {
+                skipped_paths.push(file.path);
+                continue;
+            }
+            let dirty = buffer.borrow().dirty(&self.context);
+            let dispatches = editor
+                .borrow_mut()
+                .editor_mut()
+                .apply_positional_edits(file.positional_edits(), &self.context)?;
+            self.handle_dispatches(dispatches)?;
+            // Like `Replace { scope: Global }`, buffers with unsaved changes are left unsaved
+            if !dirty {
+                let (dispatches, _) = buffer
+                    .borrow_mut()
+                    .save_without_formatting(&self.context, false)?;
+                self.handle_dispatches(dispatches)?;
+            }
+            changes_count += file.changes_count();
+            affected_paths.push(file.path);
+        }
+        let display_paths = |paths: Vec<AbsolutePath>| {
+            paths
+                .iter()
+                .map(|path| path.try_display_relative_to(self.context.current_working_directory()))
+                .join("\n")
+        };
+        let mut content = format!(
+            "Applied {changes_count} change(s) to {} file(s):\n{}",
+            affected_paths.len(),
+            display_paths(affected_paths)
+        );
+        if !skipped_paths.is_empty() {
+            content.push_str(&format!(
+                "\n\nSkipped {} file(s) that were modified after the preview:\n{}",
+                skipped_paths.len(),
+                display_paths(skipped_paths)
+            ));
+        }
+        self.show_global_info(Info::new("Replace Preview".to_string(), content));
+        Ok(())
+    }
+
+    fn global_search(&mut self) -> anyhow::Result<()> {
+        self.cancel_global_search();
         let global_search_config = self.context.global_search_config();
+        let progress = WalkProgress::default();
         let walk_builder_config = WalkBuilderConfig {
-            root: working_directory.clone().into(),
-            include: global_search_config.include_glob(),
-            exclude: global_search_config.exclude_glob(),
+            progress: progress.clone(),
+            ..self.global_walk_builder_config()?
         };
         let config = global_search_config.local_config();
         if config.search().is_empty() {
             return Ok(());
         }
-        let limit = 10000;
+        let limit = AppConfig::singleton().global_search_result_limit();
         let send_matches = {
             let sender = self.sender.clone();
+            let progress = progress.clone();
             Arc::new(move |result: crate::thread::BatchResult<Match>| {
+                // The matches of a cancelled search should not be mixed with those of the new search
+                if progress.is_cancelled() {
+                    return SendResult::ReceiverDisconnected;
+                }
                 SendResult::from(
                 sender.send(AppMessage::ExternalDispatch(Box::new(match result {
                     crate::thread::BatchResult::Items(matches) => {
@@ -2088,7 +2534,7 @@ impl<T: Frontend> App<T> {
                     crate::thread::BatchResult::LimitReached => {
                         Dispatch::ShowGlobalInfo(Info::new(
                             "Search Halted".to_string(),
-                            format!("The search has more than {limit} matches and is thus halted to avoid performance issues."),
+                            format!("The search has more than {limit} matches and is thus halted to avoid performance issues. The limit can be changed with `global_search_result_limit` of the config."),
                         ))
                     }
                 }))),
@@ -2097,15 +2543,17 @@ impl<T: Frontend> App<T> {
         };
         let on_finish = {
             let sender = self.sender.clone();
+            let progress = progress.clone();
             Callback::new(Arc::new(move |_| {
+                progress.finish();
+                // The walk can still be running if the limit is reached
+                progress.cancel();
                 let _ = sender.send(AppMessage::GlobalSearchFinished);
             }))
         };
         let send_match =
             crate::thread::batch(send_matches, on_finish, Duration::from_millis(100), limit); // Around 10 ticks per second
 
-        // TODO: we need to create a new sender for each global search, so that it can be cancelled, but when?
-        // Is it when the quickfix list is closed?
         match config.mode {
             LocalSearchConfigMode::Regex(regex) => {
                 list::grep::run(&config.search(), walk_builder_config, regex, send_match)?;
@@ -2120,6 +2568,9 @@ impl<T: Frontend> App<T> {
                     send_match,
                 )?;
             }
+            LocalSearchConfigMode::Fuzzy(candidate) => {
+                list::fuzzy::run(config.search(), candidate, walk_builder_config, send_match)?;
+            }
         };
         self.set_quickfix_list_type(
             ResponseContext::default().set_description("Global search"),
@@ -2127,9 +2578,26 @@ impl<T: Frontend> App<T> {
             // due to the asynchronity
             QuickfixListType::Items(Vec::new()),
         )?;
+        let interval = crate::thread::set_interval(
+            Callback::new({
+                let sender = self.sender.clone();
+                Arc::new(move |_| {
+                    let _ = sender.send(AppMessage::GlobalSearchProgressed);
+                })
+            }),
+            Duration::from_millis(200),
+        );
+        self.global_search = Some(RunningGlobalSearch { progress, interval });
         Ok(())
     }
 
+    fn cancel_global_search(&mut self) {
+        if let Some(global_search) = self.global_search.take() {
+            global_search.progress.cancel();
+            global_search.interval.cancel();
+        }
+    }
+
     pub fn quit_all(&self) -> anyhow::Result<()> {
         Ok(self.sender.send(AppMessage::Quit)?)
     }
@@ -2385,6 +2853,51 @@ impl<T: Frontend> App<T> {
         )
     }
 
+    fn get_repo_merge_conflicts(&mut self) -> anyhow::Result<()> {
+        let working_directory = self.working_directory().clone();
+        let repo = git::GitRepo::try_from(&working_directory)?;
+        let buffer_contents = self.layout.get_opened_buffer_contents();
+        let items = repo
+            .merge_conflicted_files()?
+            .into_iter()
+            .flat_map(|path| {
+                // Opened files may have unsaved edits, so their buffer content is used instead
+                let buffer = match buffer_contents.get(&path) {
+                    Some(content) => Buffer::from_content(&path, content.clone(), false),
+                    None => match Buffer::from_path(&path, false) {
+                        Ok(buffer) => buffer,
+                        Err(_) => return Vec::new(),
+                    },
+                };
+                let content = buffer.content();
+                let conflicts = git::merge_conflict::merge_conflicts(&content);
+                let ranges = if conflicts.is_empty() {
+                    // The markers may have been removed without the file being staged
+                    [0..0].to_vec()
+                } else {
+                    conflicts
+                        .iter()
+                        .map(|conflict| conflict.range().clone())
+                        .collect_vec()
+                };
+                ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        let location = Location {
+                            path: path.clone(),
+                            range: buffer.byte_range_to_char_index_range(&range).ok()?,
+                        };
+                        Some(QuickfixListItem::new(location, None, None))
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        self.set_quickfix_list_type(
+            ResponseContext::default().set_description("Merge Conflicts"),
+            QuickfixListType::Items(items),
+        )
+    }
+
     #[cfg(test)]
     fn set_global_title(&mut self, title: String) {
         self.global_title = Some(title);
@@ -2503,6 +3016,9 @@ impl<T: Frontend> App<T> {
                 .cloned()
         } {
             let next_file_path = next_file_path.clone();
+            if !self.auto_save_before_leaving_current_file(&next_file_path)? {
+                return Ok(());
+            }
             if let Err(err) = self.open_file(&next_file_path.clone(), BufferOwner::User, true, true)
             {
                 // If the file failed to open, show the error.
@@ -2966,6 +3482,30 @@ impl<T: Frontend> App<T> {
         ))
     }
 
+    fn open_indentation_picker(&mut self) -> anyhow::Result<()> {
+        let item = |name: &str, indentation: Option<(char, usize)>| {
+            DropdownItem::new(name.to_string()).set_dispatches(Dispatches::one(Dispatch::ToEditor(
+                DispatchEditor::SetIndentation(indentation),
+            )))
+        };
+        self.open_prompt(PromptConfig::new(
+            "Indentation".to_string(),
+            PromptOnEnter::SelectsFirstMatchingItem {
+                items: PromptItems::Precomputed(
+                    [
+                        item("Tabs", Some(('\t', 1))),
+                        item("2 Spaces", Some((' ', 2))),
+                        item("4 Spaces", Some((' ', 4))),
+                        item("8 Spaces", Some((' ', 8))),
+                        item("Automatic", None),
+                    ]
+                    .into_iter()
+                    .collect_vec(),
+                ),
+            },
+        ))
+    }
+
     fn update_current_completion_item(
         &mut self,
         completion_item: CompletionItem,
@@ -2992,6 +3532,18 @@ impl<T: Frontend> App<T> {
         ))
     }
 
+    fn open_sort_by_regex_prompt(&mut self) -> anyhow::Result<()> {
+        self.open_prompt(PromptConfig::new(
+            "Sort by regex (first capture group)".to_string(),
+            PromptOnEnter::ParseCurrentLine {
+                parser: DispatchParser::SortByRegex,
+                history_key: PromptHistoryKey::SortByRegex,
+                current_line: None,
+                suggested_items: Vec::default(),
+            },
+        ))
+    }
+
     fn use_last_non_contiguous_selection_mode(
         &mut self,
         if_current_not_found: IfCurrentNotFound,
@@ -3654,6 +4206,7 @@ Conflict markers will be injected in areas that cannot be merged gracefully."
 
     fn change_working_directory(&mut self, path: AbsolutePath) -> anyhow::Result<()> {
         self.context.change_working_directory(path)?;
+        self.context.reload_ast_grep_rules()?;
         self.layout.refresh_file_explorer(&self.context)?;
 
         Ok(())
@@ -3676,6 +4229,31 @@ Conflict markers will be injected in areas that cannot be merged gracefully."
         )
     }
 
+    fn open_global_search_scope_prompt(&mut self) -> anyhow::Result<()> {
+        let suggested_items = self
+            .context
+            .global_search_scope_presets()
+            .iter()
+            .sorted_by_key(|(name, _)| *name)
+            .map(|(name, scope)| {
+                DropdownItem::new(
+                    format!("@{name} {}", scope.display())
+                        .trim_end()
+                        .to_string(),
+                )
+            })
+            .collect_vec();
+        self.open_prompt(PromptConfig::new(
+            "Global search scope".to_string(),
+            PromptOnEnter::ParseCurrentLine {
+                parser: DispatchParser::UpdateGlobalSearchScope,
+                history_key: PromptHistoryKey::GlobalSearchScope,
+                current_line: Some(self.context.global_search_scope().display()),
+                suggested_items,
+            },
+        ))
+    }
+
     fn working_directory(&self) -> &AbsolutePath {
         self.context.current_working_directory()
     }
@@ -3745,6 +4323,150 @@ Please consider installing it.\n\
         self.open_file(&path, BufferOwner::User, true, true)?;
         Ok(())
     }
+
+    fn toggle_large_file_mode(&mut self) -> anyhow::Result<()> {
+        let component = self.current_component();
+        let large_file_mode = !component.borrow().editor().buffer().large_file_mode();
+        self.set_large_file_mode(component, large_file_mode)
+    }
+
+    /// The LSP server is only aware of buffers that are not in large-file mode,
+    /// so the document is closed when the mode is turned on, and reopened when it is turned off.
+    fn set_large_file_mode(
+        &mut self,
+        component: Rc<RefCell<dyn Component>>,
+        large_file_mode: bool,
+    ) -> anyhow::Result<()> {
+        if component.borrow().editor().buffer().large_file_mode() == large_file_mode {
+            return Ok(());
+        }
+        let dispatches = component
+            .borrow_mut()
+            .editor_mut()
+            .set_large_file_mode(large_file_mode);
+        if let Some(path) = component.borrow().path().filter(|_| self.enable_lsp) {
+            if large_file_mode {
+                self.lsp_manager().send_message(
+                    path.clone(),
+                    FromEditor::TextDocumentDidClose { file_path: path },
+                )?;
+            } else {
+                self.lsp_manager().open_file(path)?;
+            }
+        }
+        self.handle_dispatches(dispatches)
+    }
+
+    fn toggle_read_only(&mut self) {
+        let component = self.current_component();
+        let read_only = component.borrow().editor().buffer().read_only();
+        component
+            .borrow_mut()
+            .editor_mut()
+            .buffer_mut()
+            .set_read_only(!read_only);
+    }
+
+    /// The buffer has no path, so that it is never saved over the file,
+    /// and it is read-only because edits to a past revision would be lost.
+    fn open_file_at_revision(&mut self, diff_mode: git::DiffMode) -> anyhow::Result<()> {
+        let Some(path) = self.current_component().borrow().path() else {
+            return Ok(());
+        };
+        let repo = git::GitRepo::try_from(self.context.current_working_directory())?;
+        let content = path.content_at_last_commit(&diff_mode, &repo)?;
+        let language = crate::config::from_path(&path);
+        let mut buffer = Buffer::new(
+            language
+                .as_ref()
+                .and_then(|language| language.tree_sitter_language()),
+            &content,
+        );
+        if let Some(language) = language.clone() {
+            buffer.set_language(language)?;
+        }
+        buffer.set_read_only(true);
+        let batch_id = buffer.batch_id().clone();
+        let mut editor = SuggestiveEditor::from_buffer(
+            Rc::new(RefCell::new(buffer)),
+            SuggestiveEditorFilter::CurrentWord,
+            self.on_nucleo_notify(NucleoSource::SuggestiveEditor),
+        );
+        editor.set_title(format!(
+            "{} {}",
+            path.try_display_relative_to(self.context.current_working_directory()),
+            diff_mode.display()
+        ));
+        let component_id = editor.id();
+        self.layout
+            .replace_and_focus_current_suggestive_editor(Rc::new(RefCell::new(editor)));
+        if let Some(language) = language {
+            self.request_syntax_highlight(component_id, batch_id, language, content)?;
+        }
+        Ok(())
+    }
+
+    fn save_with_elevated_privileges(&mut self) -> anyhow::Result<()> {
+        // `sudo` might ask for the password in the terminal,
+        // so the terminal has to be restored to its normal state meanwhile.
+        self.prepare_to_suspend_or_quit()?;
+        let result = self
+            .current_component()
+            .borrow_mut()
+            .editor_mut()
+            .save_with_elevated_privileges(&self.context);
+        self.set_terminal_options()?;
+        self.frontend.lock().unwrap().previous_screen();
+        self.handle_dispatches(result?)
+    }
+
+    /// Saves the dirty buffers if auto-save is enabled.
+    ///
+    /// Returns true if a save-conflict prompt is shown for the current file.
+    fn auto_save(&mut self) -> anyhow::Result<bool> {
+        let auto_save = self.context.auto_save().clone();
+        // Do not stack prompts on top of the one that the user is interacting with
+        if !auto_save.enabled
+            || self.layout.get_current_component_kind() == Some(ComponentKind::Prompt)
+        {
+            return Ok(false);
+        }
+        let (dispatches, conflicting_paths) = self
+            .layout
+            .auto_save_all(auto_save.run_formatter, &self.context)?;
+        self.auto_save_conflicts
+            .retain(|path| conflicting_paths.contains(path));
+        self.handle_dispatches(dispatches)?;
+
+        // The actions of the conflict prompt apply to the current editor,
+        // so conflicts of other files are left until they are saved manually
+        let Some(path) = self
+            .get_current_file_path()
+            .filter(|path| conflicting_paths.contains(path))
+        else {
+            return Ok(false);
+        };
+        if !self.auto_save_conflicts.insert(path.clone()) {
+            return Ok(false);
+        }
+        let component = self.current_component();
+        let content_editor = component.borrow().content();
+        let content_filesystem = component.borrow().editor().buffer().read_file(&path)?;
+        self.show_buffer_save_conflict_prompt(&path, content_editor, content_filesystem)?;
+        Ok(true)
+    }
+
+    /// Returns false if the current file should not be left yet,
+    /// because auto-saving it surfaced a save conflict.
+    fn auto_save_before_leaving_current_file(
+        &mut self,
+        next_path: &AbsolutePath,
+    ) -> anyhow::Result<bool> {
+        if self.get_current_file_path().as_ref() == Some(next_path) {
+            return Ok(true);
+        }
+        Ok(!self.auto_save()?)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -3844,6 +4566,16 @@ pub enum Dispatch {
         owner: BufferOwner,
         focus: bool,
     },
+    /// Opens a file with large-file mode forced on or off, refer `Buffer::from_path_with_large_file_mode`.
+    OpenFileWithLargeFileMode {
+        path: AbsolutePath,
+        large_file_mode: bool,
+    },
+    /// Opens a file and moves the cursor to `position`, refer `App::open_file_at_position`.
+    OpenFileAtPosition {
+        path: AbsolutePath,
+        position: Position,
+    },
     RequestCompletion,
     RequestSignatureHelp,
     RequestHover,
@@ -3933,6 +4665,8 @@ pub enum Dispatch {
     #[cfg(test)]
     HandleKeyEvents(Vec<event::KeyEvent>),
     GetRepoGitHunks(git::DiffMode),
+    /// Shows the merge conflicts of every conflicted file of the repository in the quickfix list.
+    GetRepoMergeConflicts,
     SaveAll,
     #[cfg(test)]
     TerminalDimensionChanged(Dimension),
@@ -3950,9 +4684,24 @@ pub enum Dispatch {
     UpdateGlobalSearchConfig {
         update: GlobalSearchConfigUpdate,
     },
+    UpdateGlobalSearchScope(GlobalSearchScopeUpdate),
+    OpenGlobalSearchScopePrompt,
+    /// Stops the running global search, the matches found so far are kept.
+    CancelGlobalSearch,
     Replace {
         scope: Scope,
     },
+    /// Lists the changes of `Replace { scope: Global }` in the quickfix list without applying them.
+    PreviewGlobalReplace,
+    /// Lists the fixes of the ast-grep rules of the project in the quickfix list
+    /// as a replace preview, so that they can be reviewed before being applied.
+    PreviewAstGrepFixes,
+    /// Excludes the current change of the replace preview,
+    /// or every change of its file if `whole_file` is true.
+    ExcludeFromReplacePreview {
+        whole_file: bool,
+    },
+    ApplyReplacePreview,
     #[cfg(test)]
     HandleLspNotification(LspNotification),
     CloseDropdown,
@@ -3982,6 +4731,7 @@ pub enum Dispatch {
     GitCheckout(String),
     ResolveCompletionItem(lsp_types::CompletionItem),
     OpenPipeToShellPrompt,
+    OpenSortByRegexPrompt,
     SetLastNonContiguousSelectionMode(Either<SelectionMode, GlobalMode>),
     UseLastNonContiguousSelectionMode(IfCurrentNotFound),
     SetLastActionDescription {
@@ -3995,6 +4745,7 @@ pub enum Dispatch {
     SelectCompletionItem,
     SetKeyboardLayout(KeyboardLayout),
     OpenKeyboardLayoutPrompt,
+    OpenIndentationPrompt,
     MovementHistoryNavigation(Movement),
     ToggleSelectionMark,
     ToggleFileMark,
@@ -4065,6 +4816,19 @@ pub enum Dispatch {
     },
     ToggleRevealSelections,
     SaveFile,
+    /// Overrides the large-file mode of the current buffer,
+    /// which is otherwise determined when the file is opened.
+    ToggleLargeFileMode,
+    /// Saves the dirty buffers, if auto-save is enabled.
+    AutoSave,
+    #[cfg(test)]
+    SetAutoSaveConfig(crate::config::AutoSaveConfig),
+    /// Overrides the read-only flag of the current buffer,
+    /// which is otherwise set for files that the user cannot write to.
+    ToggleReadOnly,
+    /// Opens the current file as of the last commit of the branch of the diff mode.
+    OpenFileAtRevision(git::DiffMode),
+    SaveWithElevatedPrivileges,
 }
 
 /// Used to send notify host app about changes
@@ -4102,6 +4866,17 @@ pub enum GlobalSearchConfigUpdate {
     Config(GlobalSearchConfig),
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GlobalSearchScopeUpdate {
+    Scope(GlobalSearchScope),
+    LoadPreset(String),
+    /// Saves `scope` as the preset named `name`, and uses it.
+    SaveAsPreset {
+        name: String,
+        scope: GlobalSearchScope,
+    },
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum LocalSearchConfigUpdate {
     #[cfg(test)]
@@ -4185,6 +4960,8 @@ pub enum AppMessage {
     HandleNucleoNotify(NucleoSource),
     FileWatcherEvent(FileWatcherEvent),
     GlobalSearchFinished,
+    /// Sent periodically while a global search is running, so that its progress is rendered.
+    GlobalSearchProgressed,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -4212,6 +4989,7 @@ pub enum DispatchParser {
     #[cfg(test)]
     SetContent,
     PipeToShell,
+    SortByRegex,
     FilterSelectionMatchingSearch {
         maintain: bool,
     },
@@ -4221,6 +4999,7 @@ pub enum DispatchParser {
     Null,
     ChangeWorkingDirectory,
     SaveAs,
+    UpdateGlobalSearchScope,
 }
 
 impl DispatchParser {
@@ -4293,6 +5072,11 @@ impl DispatchParser {
                     command: text.to_string(),
                 },
             ))),
+            DispatchParser::SortByRegex => Ok(Dispatches::one(Dispatch::ToEditor(Transform(
+                Transformation::Reorder(Reorder::Sort(SortKey::Regex(MyRegex(
+                    fancy_regex::Regex::new(text)?,
+                )))),
+            )))),
             DispatchParser::FilterSelectionMatchingSearch { maintain } => {
                 Ok(Dispatches::one(Dispatch::FilterCursorsMatchingSearch {
                     maintain,
@@ -4307,6 +5091,9 @@ impl DispatchParser {
             DispatchParser::ChangeWorkingDirectory => Ok(Dispatches::one(
                 Dispatch::ChangeWorkingDirectory(text.try_into()?),
             )),
+            DispatchParser::UpdateGlobalSearchScope => Ok(Dispatches::one(
+                Dispatch::UpdateGlobalSearchScope(parse_search_scope_update(text)?),
+            )),
             DispatchParser::SaveAs => {
                 let path = AbsolutePath::try_from(text)?;
                 if path.exists() {
@@ -0,0 +1,454 @@
+use std::cmp::Ordering;
+
+use itertools::Itertools;
+
+/// The value of an arithmetic expression.
+///
+/// Operations on integers stay integers, except divisions with a remainder,
+/// so that `7 / 2` is `3.5`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Integer(integer) => write!(f, "{integer}"),
+            Number::Float(float) => write!(f, "{float}"),
+        }
+    }
+}
+
+impl Number {
+    fn to_f64(self) -> f64 {
+        match self {
+            Number::Integer(integer) => integer as f64,
+            Number::Float(float) => float,
+        }
+    }
+
+    fn from_f64_rounded(float: f64) -> Number {
+        if float.is_finite() && float.abs() < i64::MAX as f64 {
+            Number::Integer(float as i64)
+        } else {
+            Number::Float(float)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(Number),
+    Identifier(String),
+    Operator(&'static str),
+}
+
+const OPERATORS: [&str; 15] = [
+    "**", "<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~", "(", ")", ",",
+];
+
+/// Evaluates an expression of:
+/// - decimal integers and floats (`1_000`, `1.5e3`), and hexadecimal (`0x`),
+///   binary (`0b`) and octal (`0o`) integers
+/// - the arithmetic operators `+ - * / % **`
+/// - the bit operators `& | ^ ~ << >>`, which only accept integers
+/// - parentheses
+/// - the constants `pi`, `tau` and `e`
+/// - the functions `abs sqrt cbrt exp ln log2 log10 log sin cos tan asin acos atan
+///   floor ceil round trunc min max pow`
+///
+/// The operators have the precedence of Python, so `-2 ** 2` is `-4` and `1 | 2 + 3` is `5`.
+pub(crate) fn evaluate(expression: &str) -> anyhow::Result<Number> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        position: 0,
+    };
+    let result = parser.parse_binary(0)?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(result),
+        Some(token) => Err(anyhow::anyhow!("Unexpected {token:?}")),
+    }
+}
+
+/// Summarizes the count, sum, average, minimum and maximum of `numbers`.
+pub(crate) fn summarize(numbers: &[Number]) -> anyhow::Result<String> {
+    let Some(first) = numbers.first() else {
+        return Err(anyhow::anyhow!("There is no number to summarize."));
+    };
+    // The sum of integers is approximated if it does not fit in `i64`
+    let sum = numbers.iter().skip(1).fold(*first, |sum, number| {
+        apply_binary("+", sum, *number)
+            .unwrap_or_else(|_| Number::Float(sum.to_f64() + number.to_f64()))
+    });
+    let compare = |a: &&Number, b: &&Number| a.to_f64().total_cmp(&b.to_f64());
+    let min = numbers.iter().min_by(compare).unwrap_or(first);
+    let max = numbers.iter().max_by(compare).unwrap_or(first);
+    Ok([
+        format!("Count: {}", numbers.len()),
+        format!("Sum: {sum}"),
+        format!(
+            "Average: {}",
+            Number::Float(sum.to_f64() / numbers.len() as f64)
+        ),
+        format!("Min: {min}"),
+        format!("Max: {max}"),
+    ]
+    .join("\n"))
+}
+
+fn tokenize(expression: &str) -> anyhow::Result<Vec<Token>> {
+    let number = lazy_regex::regex!(
+        r"^(?:0[xX][0-9a-fA-F_]+|0[bB][01_]+|0[oO][0-7_]+|(?:[0-9][0-9_]*(?:\.[0-9_]*)?|\.[0-9][0-9_]*)(?:[eE][+-]?[0-9]+)?)"
+    );
+    let identifier = lazy_regex::regex!(r"^[a-zA-Z_][a-zA-Z0-9_]*");
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim_start();
+    while !rest.is_empty() {
+        let (token, length) = if let Some(m) = number.find(rest) {
+            (Token::Number(parse_number(m.as_str())?), m.len())
+        } else if let Some(m) = identifier.find(rest) {
+            (Token::Identifier(m.as_str().to_lowercase()), m.len())
+        } else if let Some(operator) = OPERATORS
+            .into_iter()
+            .find(|operator| rest.starts_with(operator))
+        {
+            (Token::Operator(operator), operator.len())
+        } else {
+            return Err(anyhow::anyhow!(
+                "Unexpected character {:?}",
+                rest.chars().next().unwrap_or_default()
+            ));
+        };
+        tokens.push(token);
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+fn parse_number(string: &str) -> anyhow::Result<Number> {
+    let digits = string.replace('_', "");
+    let radix = match digits.get(..2) {
+        Some("0x" | "0X") => Some(16),
+        Some("0b" | "0B") => Some(2),
+        Some("0o" | "0O") => Some(8),
+        _ => None,
+    };
+    Ok(match radix {
+        Some(radix) => Number::Integer(i64::from_str_radix(&digits[2..], radix)?),
+        None if digits.contains(['.', 'e', 'E']) => Number::Float(digits.parse()?),
+        // Integers too large for `i64` are approximated
+        None => digits
+            .parse()
+            .map(Number::Integer)
+            .or_else(|_| digits.parse().map(Number::Float))?,
+    })
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+/// The precedence of the left-associative binary operators, from the loosest.
+fn binary_precedence(operator: &str) -> Option<u8> {
+    match operator {
+        "|" => Some(1),
+        "^" => Some(2),
+        "&" => Some(3),
+        "<<" | ">>" => Some(4),
+        "+" | "-" => Some(5),
+        "*" | "/" | "%" => Some(6),
+        _ => None,
+    }
+}
+
+impl Parser {
+    fn peek_operator(&self) -> Option<&'static str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Operator(operator)) => Some(*operator),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, operator: &'static str) -> anyhow::Result<()> {
+        if self.peek_operator() == Some(operator) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Expected `{operator}`"))
+        }
+    }
+
+    fn parse_binary(&mut self, min_precedence: u8) -> anyhow::Result<Number> {
+        let mut left = self.parse_unary()?;
+        while let Some((operator, precedence)) = self.peek_operator().and_then(|operator| {
+            binary_precedence(operator)
+                .filter(|precedence| *precedence >= min_precedence)
+                .map(|precedence| (operator, precedence))
+        }) {
+            self.position += 1;
+            let right = self.parse_binary(precedence + 1)?;
+            left = apply_binary(operator, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> anyhow::Result<Number> {
+        match self.peek_operator() {
+            Some(operator @ ("-" | "+" | "~")) => {
+                self.position += 1;
+                let operand = self.parse_unary()?;
+                match (operator, operand) {
+                    ("-", Number::Integer(integer)) => integer
+                        .checked_neg()
+                        .map(Number::Integer)
+                        .ok_or_else(|| anyhow::anyhow!("Integer overflow")),
+                    ("-", Number::Float(float)) => Ok(Number::Float(-float)),
+                    ("~", Number::Integer(integer)) => Ok(Number::Integer(!integer)),
+                    ("~", Number::Float(_)) => {
+                        Err(anyhow::anyhow!("`~` can only be applied to integers"))
+                    }
+                    _ => Ok(operand),
+                }
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    /// `**` is right-associative, and binds tighter than a unary operator on its left,
+    /// but looser than one on its right, so `2 ** -1` is `0.5`.
+    fn parse_power(&mut self) -> anyhow::Result<Number> {
+        let base = self.parse_primary()?;
+        if self.peek_operator() == Some("**") {
+            self.position += 1;
+            let exponent = self.parse_unary()?;
+            return apply_binary("**", base, exponent);
+        }
+        Ok(base)
+    }
+
+    fn parse_primary(&mut self) -> anyhow::Result<Number> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unexpected end of expression"))?;
+        self.position += 1;
+        match token {
+            Token::Number(number) => Ok(number),
+            Token::Operator("(") => {
+                let result = self.parse_binary(0)?;
+                self.expect(")")?;
+                Ok(result)
+            }
+            Token::Identifier(name) if self.peek_operator() == Some("(") => {
+                self.position += 1;
+                let mut arguments = Vec::new();
+                if self.peek_operator() != Some(")") {
+                    arguments.push(self.parse_binary(0)?);
+                    while self.peek_operator() == Some(",") {
+                        self.position += 1;
+                        arguments.push(self.parse_binary(0)?);
+                    }
+                }
+                self.expect(")")?;
+                call(&name, &arguments)
+            }
+            Token::Identifier(name) => match name.as_str() {
+                "pi" => Ok(Number::Float(std::f64::consts::PI)),
+                "tau" => Ok(Number::Float(std::f64::consts::TAU)),
+                "e" => Ok(Number::Float(std::f64::consts::E)),
+                _ => Err(anyhow::anyhow!("Unknown constant `{name}`")),
+            },
+            token => Err(anyhow::anyhow!("Unexpected {token:?}")),
+        }
+    }
+}
+
+fn apply_binary(operator: &str, left: Number, right: Number) -> anyhow::Result<Number> {
+    let overflow = || anyhow::anyhow!("Integer overflow");
+    match (left, right) {
+        (Number::Integer(left), Number::Integer(right)) => match operator {
+            "+" => left
+                .checked_add(right)
+                .map(Number::Integer)
+                .ok_or_else(overflow),
+            "-" => left
+                .checked_sub(right)
+                .map(Number::Integer)
+                .ok_or_else(overflow),
+            "*" => left
+                .checked_mul(right)
+                .map(Number::Integer)
+                .ok_or_else(overflow),
+            "/" | "%" if right == 0 => Err(anyhow::anyhow!("Division by zero")),
+            "/" => Ok(match left.checked_rem(right) {
+                Some(0) => Number::Integer(left / right),
+                _ => Number::Float(left as f64 / right as f64),
+            }),
+            "%" => left
+                .checked_rem(right)
+                .map(Number::Integer)
+                .ok_or_else(overflow),
+            "**" => match u32::try_from(right) {
+                Ok(exponent) => left
+                    .checked_pow(exponent)
+                    .map(Number::Integer)
+                    .ok_or_else(overflow),
+                Err(_) => Ok(Number::Float((left as f64).powf(right as f64))),
+            },
+            "&" => Ok(Number::Integer(left & right)),
+            "|" => Ok(Number::Integer(left | right)),
+            "^" => Ok(Number::Integer(left ^ right)),
+            "<<" | ">>" => {
+                let shift = u32::try_from(right)
+                    .ok()
+                    .filter(|shift| *shift < i64::BITS)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid shift amount {right}"))?;
+                Ok(Number::Integer(if operator == "<<" {
+                    left << shift
+                } else {
+                    left >> shift
+                }))
+            }
+            _ => Err(anyhow::anyhow!("Unknown operator `{operator}`")),
+        },
+        (left, right) => {
+            let (left, right) = (left.to_f64(), right.to_f64());
+            match operator {
+                "+" => Ok(Number::Float(left + right)),
+                "-" => Ok(Number::Float(left - right)),
+                "*" => Ok(Number::Float(left * right)),
+                "/" => Ok(Number::Float(left / right)),
+                "%" => Ok(Number::Float(left % right)),
+                "**" => Ok(Number::Float(left.powf(right))),
+                "&" | "|" | "^" | "<<" | ">>" => Err(anyhow::anyhow!(
+                    "`{operator}` can only be applied to integers"
+                )),
+                _ => Err(anyhow::anyhow!("Unknown operator `{operator}`")),
+            }
+        }
+    }
+}
+
+fn call(name: &str, arguments: &[Number]) -> anyhow::Result<Number> {
+    let float = |function: fn(f64) -> f64| match arguments {
+        [argument] => Ok(Number::Float(function(argument.to_f64()))),
+        _ => Err(anyhow::anyhow!(
+            "`{name}` expects 1 argument, but got {}",
+            arguments.len()
+        )),
+    };
+    let compare = |a: &&Number, b: &&Number| -> Ordering { a.to_f64().total_cmp(&b.to_f64()) };
+    match name {
+        "abs" => match arguments {
+            [Number::Integer(integer)] => integer
+                .checked_abs()
+                .map(Number::Integer)
+                .ok_or_else(|| anyhow::anyhow!("Integer overflow")),
+            _ => float(f64::abs),
+        },
+        "sqrt" => float(f64::sqrt),
+        "cbrt" => float(f64::cbrt),
+        "exp" => float(f64::exp),
+        "ln" => float(f64::ln),
+        "log2" => float(f64::log2),
+        "log10" => float(f64::log10),
+        "sin" => float(f64::sin),
+        "cos" => float(f64::cos),
+        "tan" => float(f64::tan),
+        "asin" => float(f64::asin),
+        "acos" => float(f64::acos),
+        "atan" => float(f64::atan),
+        "floor" | "ceil" | "round" | "trunc" => {
+            let function: fn(f64) -> f64 = match name {
+                "floor" => f64::floor,
+                "ceil" => f64::ceil,
+                "round" => f64::round,
+                _ => f64::trunc,
+            };
+            match float(function)? {
+                Number::Float(float) => Ok(Number::from_f64_rounded(float)),
+                integer => Ok(integer),
+            }
+        }
+        "log" | "pow" => match arguments {
+            [left, right] if name == "log" => Ok(Number::Float(left.to_f64().log(right.to_f64()))),
+            [left, right] => apply_binary("**", *left, *right),
+            _ => Err(anyhow::anyhow!(
+                "`{name}` expects 2 arguments, but got {}",
+                arguments.len()
+            )),
+        },
+        "min" | "max" => {
+            let result = if name == "min" {
+                arguments.iter().min_by(compare)
+            } else {
+                arguments.iter().max_by(compare)
+            };
+            result
+                .copied()
+                .ok_or_else(|| anyhow::anyhow!("`{name}` expects at least 1 argument"))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unknown function `{name}`, the known functions are: {}",
+            [
+                "abs", "sqrt", "cbrt", "exp", "ln", "log2", "log10", "log", "sin", "cos", "tan",
+                "asin", "acos", "atan", "floor", "ceil", "round", "trunc", "min", "max", "pow"
+            ]
+            .into_iter()
+            .join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test_arithmetic {
+    use super::*;
+
+    #[test]
+    fn evaluate_expressions() {
+        let run_test = |expression: &str, expected: &str| {
+            assert_eq!(evaluate(expression).unwrap().to_string(), expected);
+        };
+        run_test("1 + 2 * 3", "7");
+        run_test("(1 + 2) * 3", "9");
+        run_test("7 / 2", "3.5");
+        run_test("8 / 2", "4");
+        run_test("7 % 4", "3");
+        run_test("-2 ** 2", "-4");
+        run_test("2 ** -1", "0.5");
+        run_test("2 ** 3 ** 2", "512");
+        run_test("0xff & 0b1010 | 1 << 4", "26");
+        run_test("~0 ^ 0o7", "-8");
+        run_test("1_000 + 1.5e3", "2500");
+        run_test("0.1 + 0.2", "0.30000000000000004");
+        run_test("max(1, 5.5, 3) - min(4, 2)", "3.5");
+        run_test("floor(2.7) + abs(-3)", "5");
+        run_test("sqrt(16) + pow(2, 10)", "1028");
+        run_test("round(PI * 100)", "314");
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expression in [
+            "1 +", "(1", "1 / 0", "1.5 & 1", "foo(1)", "2 ** 64", "1 2", "$",
+        ] {
+            assert!(evaluate(expression).is_err(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn summarize_numbers() {
+        assert_eq!(
+            summarize(&[Number::Integer(1), Number::Float(2.5), Number::Integer(-3)]).unwrap(),
+            "Count: 3\nSum: 0.5\nAverage: 0.16666666666666666\nMin: -3\nMax: 2.5"
+        );
+        assert_eq!(
+            summarize(&[Number::Integer(i64::MAX), Number::Integer(1)]).unwrap(),
+            "Count: 2\nSum: 9223372036854776000\nAverage: 4611686018427388000\nMin: 1\nMax: 9223372036854775807"
+        );
+    }
+}
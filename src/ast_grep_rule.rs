@@ -0,0 +1,398 @@
+//! Lint rules written in the YAML format of [ast-grep](https://ast-grep.github.io/guide/rule-config.html),
+//! which are loaded from the directories listed by `ruleDirs` of the `sgconfig.yml`
+//! in the working directory.
+//!
+//! Only a subset of the format is supported, namely the atomic rules `pattern`, `kind` and `regex`,
+//! and the composite rules `all`, `any` and `not`. Rules using other keys, including top-level
+//! ones such as `constraints`, fail to load, instead of silently matching more than they should.
+
+use std::{ops::Range, path::PathBuf};
+
+use ast_grep_core::{language::TSLanguage, matcher::Pattern, Node, StrDoc};
+use figment::providers::{self, Format};
+use itertools::Itertools;
+use lsp_types::DiagnosticSeverity;
+use serde::{de::IgnoredAny, Deserialize};
+use shared::{absolute_path::AbsolutePath, language::Language};
+
+use crate::{buffer::Buffer, lsp::diagnostic::Diagnostic};
+
+const PROJECT_CONFIG_FILE_NAME: &str = "sgconfig.yml";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectConfig {
+    #[serde(default)]
+    rule_dirs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+    Info,
+    #[default]
+    Hint,
+    /// Disables the rule.
+    Off,
+}
+
+impl Severity {
+    fn to_diagnostic_severity(self) -> Option<DiagnosticSeverity> {
+        match self {
+            Severity::Error => Some(DiagnosticSeverity::ERROR),
+            Severity::Warning => Some(DiagnosticSeverity::WARNING),
+            Severity::Info => Some(DiagnosticSeverity::INFORMATION),
+            Severity::Hint => Some(DiagnosticSeverity::HINT),
+            Severity::Off => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AstGrepRule {
+    id: String,
+    /// The name of a language, such as `rust`, or one of its extensions, such as `rs`.
+    language: String,
+    #[serde(default)]
+    severity: Severity,
+    #[serde(default)]
+    message: String,
+    note: Option<String>,
+    rule: Rule,
+    /// The replacement of the matched node, which can refer to the
+    /// meta variables of the `pattern` of the rule, such as `$A`.
+    fix: Option<String>,
+    // The keys below do not affect the matches, so they are accepted but unused.
+    #[allow(dead_code)]
+    url: Option<IgnoredAny>,
+    #[allow(dead_code)]
+    metadata: Option<IgnoredAny>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    pattern: Option<String>,
+    kind: Option<String>,
+    regex: Option<String>,
+    #[serde(default)]
+    all: Vec<Rule>,
+    #[serde(default)]
+    any: Vec<Rule>,
+    not: Option<Box<Rule>>,
+}
+
+/// A `Rule` whose pattern and regex are compiled for the language of a file.
+/// A node is matched if it satisfies every field that is set.
+struct Matcher {
+    pattern: Option<Pattern<TSLanguage>>,
+    kind: Option<String>,
+    regex: Option<regex::Regex>,
+    all: Vec<Matcher>,
+    any: Vec<Matcher>,
+    not: Option<Box<Matcher>>,
+}
+
+/// A match of a rule in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    pub range: Range<usize>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    /// The replacement of `range`.
+    pub fix: Option<String>,
+}
+
+/// A `RuleMatch` of a buffer, whose range is kept up to date as the buffer is edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub diagnostic: Diagnostic,
+    pub fix: Option<String>,
+}
+
+impl Lint {
+    pub fn try_from(buffer: &Buffer, rule_match: RuleMatch) -> anyhow::Result<Self> {
+        Ok(Self {
+            diagnostic: Diagnostic {
+                range: buffer.byte_range_to_char_index_range(&rule_match.range)?,
+                message: rule_match.message,
+                severity: Some(rule_match.severity),
+                related_information: None,
+                code_description: None,
+                original_value: None,
+            },
+            fix: rule_match.fix,
+        })
+    }
+}
+
+/// Returns no rules if the working directory has no `sgconfig.yml`.
+pub fn load(working_directory: &AbsolutePath) -> anyhow::Result<Vec<AstGrepRule>> {
+    let project_config_path = working_directory
+        .to_path_buf()
+        .join(PROJECT_CONFIG_FILE_NAME);
+    if !project_config_path.is_file() {
+        return Ok(Vec::new());
+    }
+    let project_config: ProjectConfig =
+        figment::Figment::from(providers::Yaml::file(&project_config_path)).extract()?;
+    let mut rules = Vec::new();
+    for rule_dir in project_config.rule_dirs {
+        let rule_files = ignore::WalkBuilder::new(working_directory.to_path_buf().join(rule_dir))
+            .build()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "yml" || extension == "yaml")
+            })
+            .sorted();
+        for path in rule_files {
+            rules.extend(parse(&std::fs::read_to_string(&path)?).map_err(|error| {
+                anyhow::anyhow!("Failed to load the ast-grep rules of {path:?}: {error}")
+            })?);
+        }
+    }
+    Ok(rules)
+}
+
+/// A rule file can contain several rules, separated by `---`.
+fn parse(content: &str) -> anyhow::Result<Vec<AstGrepRule>> {
+    lazy_regex::regex!(r"(?m)^---[ \t]*\r?$")
+        .split(content)
+        .filter(|document| !document.trim().is_empty())
+        .map(|document| {
+            let rule: AstGrepRule =
+                figment::Figment::from(providers::Yaml::string(document)).extract()?;
+            Ok(rule)
+        })
+        .collect()
+}
+
+impl AstGrepRule {
+    fn applies_to(&self, language: &Language) -> bool {
+        crate::config::from_injection_name(&self.language).as_ref() == Some(language)
+    }
+
+    fn message(&self) -> String {
+        let message = if self.message.is_empty() {
+            self.id.clone()
+        } else {
+            format!("{} [{}]", self.message, self.id)
+        };
+        match &self.note {
+            Some(note) => format!("{message}\n\n{}", note.trim()),
+            None => message,
+        }
+    }
+
+    fn find(&self, language: &TSLanguage, content: &str) -> anyhow::Result<Vec<RuleMatch>> {
+        let Some(severity) = self.severity.to_diagnostic_severity() else {
+            return Ok(Vec::new());
+        };
+        let matcher = self.rule.compile(language)?;
+        let grep = ast_grep_core::AstGrep::new(content.to_string(), language.clone());
+        let root = grep.root();
+        Ok(root
+            .dfs()
+            .filter(|node| matcher.matches(node))
+            .map(|node| RuleMatch {
+                range: node.range(),
+                severity,
+                message: self.message(),
+                fix: self.fix.as_ref().map(|fix| match matcher.fix_pattern() {
+                    Some(pattern) => node
+                        .replace(pattern.clone(), fix.as_str())
+                        .and_then(|edit| String::from_utf8(edit.inserted_text).ok())
+                        .unwrap_or_else(|| fix.clone()),
+                    None => fix.clone(),
+                }),
+            })
+            .collect())
+    }
+}
+
+impl Rule {
+    fn compile(&self, language: &TSLanguage) -> anyhow::Result<Matcher> {
+        let compile_all = |rules: &[Rule]| -> anyhow::Result<Vec<Matcher>> {
+            rules.iter().map(|rule| rule.compile(language)).collect()
+        };
+        Ok(Matcher {
+            pattern: self
+                .pattern
+                .as_ref()
+                .map(|pattern| Pattern::try_new(pattern, language.clone()))
+                .transpose()?,
+            kind: self.kind.clone(),
+            regex: self
+                .regex
+                .as_ref()
+                .map(|regex| regex::Regex::new(regex))
+                .transpose()?,
+            all: compile_all(&self.all)?,
+            any: compile_all(&self.any)?,
+            not: self
+                .not
+                .as_ref()
+                .map(|not| Ok::<_, anyhow::Error>(Box::new(not.compile(language)?)))
+                .transpose()?,
+        })
+    }
+}
+
+impl Matcher {
+    fn matches(&self, node: &Node<StrDoc<TSLanguage>>) -> bool {
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| node.matches(pattern.clone()))
+            && self.kind.as_ref().is_none_or(|kind| node.kind() == *kind)
+            && self
+                .regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(&node.text()))
+            && self.all.iter().all(|matcher| matcher.matches(node))
+            && (self.any.is_empty() || self.any.iter().any(|matcher| matcher.matches(node)))
+            && self.not.as_ref().is_none_or(|not| !not.matches(node))
+    }
+
+    /// The pattern whose meta variables are substituted into the fix.
+    fn fix_pattern(&self) -> Option<&Pattern<TSLanguage>> {
+        self.pattern
+            .as_ref()
+            .or_else(|| self.all.iter().find_map(|matcher| matcher.fix_pattern()))
+    }
+}
+
+/// Returns the matches of the `rules` that apply to `language`, in the order of their start.
+/// Rules that fail to compile for the language are skipped.
+pub fn find_matches(rules: &[AstGrepRule], language: &Language, content: &str) -> Vec<RuleMatch> {
+    let rules = rules
+        .iter()
+        .filter(|rule| rule.applies_to(language))
+        .collect_vec();
+    if rules.is_empty() {
+        return Vec::new();
+    }
+    let Some(tree_sitter_language) = language.tree_sitter_language() else {
+        return Vec::new();
+    };
+    let tree_sitter_language: TSLanguage = tree_sitter_language.into();
+    rules
+        .into_iter()
+        .flat_map(|rule| {
+            rule.find(&tree_sitter_language, content)
+                .unwrap_or_else(|error| {
+                    log::error!("Failed to run the ast-grep rule {:?}: {error}", rule.id);
+                    Vec::new()
+                })
+        })
+        .sorted_by_key(|rule_match| (rule_match.range.start, rule_match.range.end))
+        .collect()
+}
+
+/// Returns the range and fix of every match that has a fix, except those overlapping a preceding one.
+pub fn fixes(matches: &[RuleMatch]) -> Vec<(Range<usize>, String)> {
+    let mut result = Vec::new();
+    let mut end = 0;
+    for rule_match in matches {
+        let Some(fix) = &rule_match.fix else {
+            continue;
+        };
+        if rule_match.range.start < end {
+            continue;
+        }
+        result.push((rule_match.range.clone(), fix.clone()));
+        end = rule_match.range.end;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test_ast_grep_rule {
+    use super::*;
+
+    fn rust() -> Language {
+        crate::config::from_extension("rs").unwrap()
+    }
+
+    #[test]
+    fn find_and_fix() -> anyhow::Result<()> {
+        let rules = parse(
+            "
+id: no-unwrap
+language: rust
+severity: warning
+message: Avoid unwrap
+rule:
+  pattern: $A.unwrap()
+fix: $A.expect(\"TODO\")
+---
+id: no-todo
+language: Rust
+rule:
+  all:
+    - kind: macro_invocation
+    - regex: ^todo!
+    - not:
+        regex: unreachable
+",
+        )?;
+        let content = "fn f() { x.unwrap(); y.unwrap(); todo!() }";
+        let matches = find_matches(&rules, &rust(), content);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|rule_match| (
+                    &content[rule_match.range.clone()],
+                    rule_match.severity,
+                    rule_match.fix.as_deref()
+                ))
+                .collect_vec(),
+            [
+                (
+                    "x.unwrap()",
+                    DiagnosticSeverity::WARNING,
+                    Some("x.expect(\"TODO\")")
+                ),
+                (
+                    "y.unwrap()",
+                    DiagnosticSeverity::WARNING,
+                    Some("y.expect(\"TODO\")")
+                ),
+                ("todo!()", DiagnosticSeverity::HINT, None),
+            ]
+        );
+        assert_eq!(matches[0].message, "Avoid unwrap [no-unwrap]");
+        assert_eq!(
+            fixes(&matches)
+                .into_iter()
+                .map(|(range, fix)| (&content[range], fix))
+                .collect_vec(),
+            [
+                ("x.unwrap()", "x.expect(\"TODO\")".to_string()),
+                ("y.unwrap()", "y.expect(\"TODO\")".to_string())
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_rules_fail_to_load() {
+        assert!(parse("id: a\nlanguage: rust\nrule:\n  inside:\n    kind: block\n").is_err());
+        assert!(parse(
+            "id: a\nlanguage: rust\nrule:\n  pattern: $A\nconstraints:\n  A:\n    kind: block\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn metadata_keys_are_accepted() {
+        assert!(parse(
+            "id: a\nlanguage: rust\nrule:\n  kind: block\nurl: https://example.com\nmetadata:\n  tag: a\n"
+        )
+        .is_ok());
+    }
+}
@@ -1,13 +1,17 @@
 use crate::app::{Dispatch, Dispatches};
+use crate::ast_grep_rule::{Lint, RuleMatch};
 use crate::components::suggestive_editor::Info;
 use crate::context::{Context, FormatterCommand};
+use crate::editorconfig::EditorConfig;
 use crate::git::hunk::SimpleHunk;
+use crate::git::merge_conflict::{self, MergeConflict};
 use crate::git::{DiffMode, GitOperation};
 use crate::history::History;
 use crate::lsp::diagnostic::Diagnostic;
 use crate::selection::Selection;
 use crate::selection_mode::naming_convention_agnostic::NamingConventionAgnostic;
 use crate::syntax_highlight::SyntaxHighlightRequestBatchId;
+use crate::syntax_injection::{self, Injection};
 use crate::{
     char_index_range::CharIndexRange,
     components::suggestive_editor::Decoration,
@@ -23,8 +27,9 @@ use anyhow::Context as _;
 use itertools::Itertools;
 use regex::Regex;
 use ropey::Rope;
-use shared::process_command::SpawnCommandError;
+use shared::process_command::{ProcessCommand, SpawnCommandError};
 use shared::{absolute_path::AbsolutePath, language::Language};
+use std::cell::OnceCell;
 use std::ops::Range;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::SystemTime;
@@ -46,11 +51,20 @@ pub enum BufferOwner {
 pub struct Buffer {
     rope: Rope,
     tree: Option<Tree>,
+    /// When true, `tree` does not reflect the latest edits yet,
+    /// because the tree is not reparsed in insert mode, refer `Buffer::update_tree`.
+    tree_outdated: bool,
+    /// The regions written in other languages, refer `syntax_injection`.
+    /// These are recomputed whenever `tree` is reparsed.
+    injections: Vec<Injection>,
     treesitter_language: Option<tree_sitter::Language>,
     language: Option<Language>,
     path: Option<AbsolutePath>,
     highlighted_spans: HighlightedSpans,
     diagnostics: Vec<Diagnostic>,
+    /// The matches of the ast-grep rules of the project, refer `ast_grep_rule`.
+    /// They are kept apart from `diagnostics`, which are replaced whenever the LSP server publishes.
+    lints: Vec<Lint>,
     decorations: Vec<Decoration>,
     selection_set_history: History<SelectionSet>,
 
@@ -62,9 +76,29 @@ pub struct Buffer {
     /// We need to cache this because its computation is expensive.
     cached_hunks: Option<CachedHunks>,
 
+    /// Computed on demand and reset whenever the content changes, refer `Buffer::merge_conflicts`.
+    cached_merge_conflicts: OnceCell<Vec<MergeConflict>>,
+
     /// Timestamp of the file when we last read/wrote it
     last_synced_time: Option<SystemTime>,
 
+    /// When true, tree-sitter parsing, LSP, git hunks and the word index
+    /// are disabled for this buffer, refer `is_large_file`.
+    large_file_mode: bool,
+
+    /// When true, the editor refuses to modify this buffer.
+    /// This is set for files that the current user cannot write to.
+    read_only: bool,
+
+    /// The `.editorconfig` properties that apply to this file
+    editorconfig: EditorConfig,
+
+    /// Inferred from the content when the file is loaded, refer `detect_indentation`
+    detected_indentation: Option<(char, usize)>,
+
+    /// Set explicitly by the user, this takes precedence over everything else
+    indentation_override: Option<(char, usize)>,
+
     #[cfg(test)]
     pub tree_reparsed_count: usize,
 }
@@ -103,10 +137,13 @@ impl Buffer {
                         .and_then(|_| parser.parse(text, None))
                 })
             },
+            tree_outdated: false,
+            injections: Vec::new(),
             path: None,
             highlighted_spans: HighlightedSpans::default(),
             decorations: Vec::new(),
             diagnostics: Vec::new(),
+            lints: Vec::new(),
             selection_set_history: History::new(),
 
             owner: BufferOwner::System,
@@ -114,7 +151,13 @@ impl Buffer {
             redo_stack: Vec::default(),
             batch_id: SyntaxHighlightRequestBatchId::default(),
             cached_hunks: None,
+            cached_merge_conflicts: OnceCell::new(),
             last_synced_time: None,
+            large_file_mode: false,
+            read_only: false,
+            editorconfig: EditorConfig::default(),
+            detected_indentation: None,
+            indentation_override: None,
             #[cfg(test)]
             tree_reparsed_count: 0,
         }
@@ -146,7 +189,7 @@ impl Buffer {
                 return Ok(Dispatches::default());
             }
 
-            let updated_content = path.read()?;
+            let updated_content = self.read_file(&path)?;
             let dispatches = self
                 .update_content(&updated_content, SelectionSet::default(), 0)?
                 .append(Dispatch::SetFileDirtyStatus {
@@ -183,6 +226,9 @@ impl Buffer {
     }
 
     pub fn simple_hunks(&mut self, context: &Context) -> anyhow::Result<Vec<SimpleHunk>> {
+        if self.large_file_mode {
+            return Ok(Vec::default());
+        }
         Ok(match &self.cached_hunks {
             Some(cached_hunks) if cached_hunks.file_content == self.rope => {
                 cached_hunks.hunks.clone()
@@ -215,10 +261,28 @@ impl Buffer {
     }
 
     pub fn diagnostics(&self) -> Vec<Diagnostic> {
-        self.diagnostics.clone()
+        self.diagnostics
+            .iter()
+            .chain(self.lints.iter().map(|lint| &lint.diagnostic))
+            .cloned()
+            .collect()
+    }
+
+    pub fn set_lints(&mut self, rule_matches: Vec<RuleMatch>) {
+        self.lints = rule_matches
+            .into_iter()
+            .filter_map(|rule_match| Lint::try_from(self, rule_match).ok())
+            .collect();
+    }
+
+    pub fn lints(&self) -> &[Lint] {
+        &self.lints
     }
 
     pub fn words(&self) -> Vec<String> {
+        if self.large_file_mode {
+            return Vec::new();
+        }
         let regex = lazy_regex::regex!(r"\b(\w|-)+");
         let str = self.rope.to_string();
         regex
@@ -257,7 +321,13 @@ impl Buffer {
                 .collect_vec();
             get_parent_lines(buffer, node.parent(), lines)
         }
-        let parent_lines = get_parent_lines(self, node, Vec::new())?;
+        let parent_lines = match node {
+            Some(node) => get_parent_lines(self, Some(node), Vec::new())?,
+            // Without a syntax tree, the parent lines are determined by indentation,
+            // except in large-file mode, where the scan could be arbitrarily long
+            None if self.large_file_mode() => Vec::new(),
+            None => self.get_parent_lines_by_indentation(line_index, indentation)?,
+        };
 
         Ok(parent_lines
             .into_iter()
@@ -280,6 +350,39 @@ impl Buffer {
             .collect_vec())
     }
 
+    /// Returns the nearest less indented non-blank line above `line_index`,
+    /// followed by the nearest line that is less indented than that, and so on.
+    fn get_parent_lines_by_indentation(
+        &self,
+        line_index: usize,
+        indentation: usize,
+    ) -> anyhow::Result<Vec<Line>> {
+        let mut indentation = indentation;
+        let mut lines = Vec::new();
+        for line_index in (0..line_index).rev() {
+            if indentation == 0 {
+                break;
+            }
+            let content = self.get_line_by_line_index(line_index)?.to_string();
+            if content.trim().is_empty() {
+                continue;
+            }
+            let column = content.chars().take_while(|c| c.is_whitespace()).count();
+            if column < indentation {
+                indentation = column;
+                lines.push(Line {
+                    origin_position: Position {
+                        line: line_index,
+                        column,
+                    },
+                    line: line_index,
+                    content,
+                });
+            }
+        }
+        Ok(lines)
+    }
+
     fn get_rope_and_tree(
         language: Option<tree_sitter::Language>,
         text: &str,
@@ -331,8 +434,7 @@ impl Buffer {
             return false;
         };
         let byte_range = start..end;
-        self.tree
-            .as_ref()
+        self.tree_at(start)
             .map(|tree| {
                 tree.root_node()
                     .descendant_for_byte_range(byte_range.start, byte_range.end)
@@ -357,6 +459,9 @@ impl Buffer {
 
     pub fn update(&mut self, text: &str) -> Dispatches {
         (self.rope, self.tree) = Self::get_rope_and_tree(self.treesitter_language.clone(), text);
+        self.tree_outdated = false;
+        self.cached_merge_conflicts = OnceCell::new();
+        self.update_injections();
         self.flag_as_modified()
     }
 
@@ -455,7 +560,7 @@ impl Buffer {
     }
 
     pub fn position_to_char(&self, position: Position) -> anyhow::Result<CharIndex> {
-        let line = position.line.clamp(0, self.len_lines());
+        let line = position.line.min(self.len_lines().saturating_sub(1));
         let column = position.column.clamp(
             0,
             self.get_line_by_line_index(line)
@@ -495,11 +600,11 @@ impl Buffer {
         selection: &Selection,
         get_largest_end: bool,
     ) -> anyhow::Result<Option<Node<'a>>> {
-        let Some(tree) = self.tree.as_ref() else {
-            return Ok(None);
-        };
         let range = selection.range();
         let start = self.char_to_byte(range.start)?;
+        let Some(tree) = self.tree_at(start) else {
+            return Ok(None);
+        };
         let (start, end) = if get_largest_end {
             (start, start + 1)
         } else {
@@ -662,6 +767,8 @@ impl Buffer {
         self.rope.try_remove(edit.range.start.0..edit.end().0)?;
         self.rope
             .try_insert(edit.range.start.0, edit.new.to_string().as_str())?;
+        self.tree_outdated = true;
+        self.cached_merge_conflicts = OnceCell::new();
 
         let dispatches = self.flag_as_modified();
 
@@ -674,6 +781,14 @@ impl Buffer {
                 false
             }
         });
+        self.lints.retain_mut(|lint| {
+            if let Some(range) = lint.diagnostic.range.apply_edit(edit) {
+                lint.diagnostic.range = range;
+                true
+            } else {
+                false
+            }
+        });
         let max_char_index = CharIndex(self.len_chars());
         self.selection_set_history = std::mem::take(&mut self.selection_set_history)
             .apply(|selection_set| selection_set.apply_edit(edit, max_char_index));
@@ -700,7 +815,52 @@ impl Buffer {
     }
 
     pub fn from_path(path: &AbsolutePath, enable_tree_sitter: bool) -> anyhow::Result<Buffer> {
-        let content = path.read()?;
+        Self::from_path_with_large_file_mode(path, enable_tree_sitter, None)
+    }
+
+    /// `large_file_mode` overrides the result of `is_large_file` for this open of the file.
+    pub fn from_path_with_large_file_mode(
+        path: &AbsolutePath,
+        enable_tree_sitter: bool,
+        large_file_mode: Option<bool>,
+    ) -> anyhow::Result<Buffer> {
+        let editorconfig = EditorConfig::from_path(path);
+        let content = editorconfig.decode(std::fs::read(path.to_path_buf())?)?;
+        Ok(Self::from_path_and_content(
+            path,
+            editorconfig,
+            content,
+            enable_tree_sitter,
+            large_file_mode,
+        ))
+    }
+
+    /// Used for a file whose content is already in memory, such as that of an opened buffer.
+    pub fn from_content(path: &AbsolutePath, content: String, enable_tree_sitter: bool) -> Buffer {
+        Self::from_path_and_content(
+            path,
+            EditorConfig::from_path(path),
+            content,
+            enable_tree_sitter,
+            None,
+        )
+    }
+
+    fn from_path_and_content(
+        path: &AbsolutePath,
+        editorconfig: EditorConfig,
+        content: String,
+        enable_tree_sitter: bool,
+        large_file_mode: Option<bool>,
+    ) -> Buffer {
+        let large_file_mode = large_file_mode.unwrap_or_else(|| {
+            let config = crate::config::AppConfig::singleton();
+            is_large_file(
+                &content,
+                config.large_file_size_threshold(),
+                config.large_file_line_length_threshold(),
+            )
+        });
         let language = if enable_tree_sitter {
             crate::config::from_path(path)
                 .or_else(|| crate::config::from_content_directive(&content))
@@ -711,16 +871,22 @@ impl Buffer {
         let mut buffer = Buffer::new(
             language
                 .as_ref()
+                .filter(|_| !large_file_mode)
                 .and_then(|language| language.tree_sitter_language()),
             &content,
         );
 
         buffer.path = Some(path.clone());
         buffer.language = language;
+        buffer.large_file_mode = large_file_mode;
+        buffer.read_only = !is_writable(path);
+        buffer.editorconfig = editorconfig;
+        buffer.detected_indentation = detect_indentation(&content);
+        buffer.update_injections();
 
         buffer.last_synced_time = path.last_modified_time().ok();
 
-        Ok(buffer)
+        buffer
     }
 
     pub fn reparse_tree(&mut self) -> anyhow::Result<()> {
@@ -728,6 +894,8 @@ impl Buffer {
         if let Some(tree) = self.tree.as_ref() {
             parser.set_language(&tree.language())?;
             self.tree = parser.parse(self.rope.to_string(), None);
+            self.tree_outdated = false;
+            self.update_injections();
 
             #[cfg(test)]
             {
@@ -737,6 +905,68 @@ impl Buffer {
         Ok(())
     }
 
+    /// The merge conflicts of the content, which are needed on every render,
+    /// so they are only computed again after the content changes.
+    pub fn merge_conflicts(&self) -> &[MergeConflict] {
+        self.cached_merge_conflicts
+            .get_or_init(|| merge_conflict::merge_conflicts(&self.rope.to_string()))
+    }
+
+    /// Reparses the tree only if it does not reflect the latest edits,
+    /// for features that need an up-to-date tree in insert mode, such as syntax-aware indentation.
+    pub fn update_tree(&mut self) -> anyhow::Result<()> {
+        if self.tree_outdated {
+            self.reparse_tree()?;
+        }
+        Ok(())
+    }
+
+    /// Returns a buffer with the content after `edit_transaction`, which is not applied to this buffer.
+    /// The returned buffer only carries the content, the languages and the syntax tree.
+    pub(crate) fn preview(&self, edit_transaction: &EditTransaction) -> anyhow::Result<Buffer> {
+        let mut rope = self.rope.clone();
+        for edit in edit_transaction.edits() {
+            rope.try_remove(edit.range.start.0..edit.end().0)?;
+            rope.try_insert(edit.range.start.0, edit.new.to_string().as_str())?;
+        }
+        let mut buffer = Buffer::new(self.treesitter_language.clone(), &rope.to_string());
+        buffer.language = self.language.clone();
+        Ok(buffer)
+    }
+
+    fn update_injections(&mut self) {
+        let previous = std::mem::take(&mut self.injections);
+        self.injections = match (self.language.as_ref(), self.tree.as_ref()) {
+            (Some(language), Some(tree)) => {
+                syntax_injection::injections(language, tree, &self.rope.to_string(), &previous)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The innermost injection that contains `byte`.
+    pub fn injection_at(&self, byte: usize) -> Option<&Injection> {
+        self.injections
+            .iter()
+            .filter(|injection| injection.byte_range().contains(&byte))
+            .min_by_key(|injection| injection.byte_range().len())
+    }
+
+    /// The syntax tree of the innermost language at `byte`,
+    /// which is the tree of the buffer unless `byte` is within an injection.
+    pub fn tree_at(&self, byte: usize) -> Option<&Tree> {
+        self.injection_at(byte)
+            .map(|injection| injection.tree())
+            .or(self.tree.as_ref())
+    }
+
+    /// Like `tree_at`, but for the language.
+    pub fn language_at(&self, byte: usize) -> Option<Language> {
+        self.injection_at(byte)
+            .map(|injection| injection.language().clone())
+            .or_else(|| self.language())
+    }
+
     pub fn get_formatted_content(&self) -> Option<anyhow::Result<String>> {
         let formatter = self
             .language
@@ -756,6 +986,36 @@ impl Buffer {
         &mut self,
         context: &Context,
         force: bool,
+    ) -> anyhow::Result<(Dispatches, Option<AbsolutePath>)> {
+        self.save_with(context, force, |path, content| {
+            std::fs::write(path.to_path_buf(), content).map_err(|error| match error.kind() {
+                std::io::ErrorKind::PermissionDenied => anyhow::anyhow!(
+                    "Permission denied to write {}, try \"Sudo Save\" instead.",
+                    path.display_absolute()
+                ),
+                _ => error.into(),
+            })
+        })
+    }
+
+    /// Writes the content through `sudo dd`,
+    /// for files that the current user has no permission to write to.
+    pub fn save_with_elevated_privileges(
+        &mut self,
+        context: &Context,
+    ) -> anyhow::Result<(Dispatches, Option<AbsolutePath>)> {
+        self.save_with(context, false, |path, content| {
+            ProcessCommand::new("sudo", &write_file_args(path))
+                .run_with_input(content)
+                .map(|_| ())
+        })
+    }
+
+    fn save_with(
+        &mut self,
+        context: &Context,
+        force: bool,
+        write: impl FnOnce(&AbsolutePath, &[u8]) -> anyhow::Result<()>,
     ) -> anyhow::Result<(Dispatches, Option<AbsolutePath>)> {
         if !force && !self.dirty(context) {
             return Ok((Dispatches::default(), self.path()));
@@ -766,7 +1026,7 @@ impl Buffer {
                 return Ok((dispatches, Some(path.clone())));
             }
 
-            path.write(&self.content())?;
+            write(path, &self.editorconfig.encode(&self.content())?)?;
 
             self.last_synced_time = path.last_modified_time().ok();
 
@@ -783,6 +1043,19 @@ impl Buffer {
         }
     }
 
+    /// Returns true if this buffer has unsaved changes
+    /// while its file was modified on disk since it was last synced.
+    pub fn has_save_conflict(&self, context: &Context) -> bool {
+        self.path
+            .as_ref()
+            .is_some_and(|path| matches!(self.check_conflict(context, false, path), Ok(Some(_))))
+    }
+
+    /// Reads the file with the charset of this buffer, refer `EditorConfig::decode`.
+    pub(crate) fn read_file(&self, path: &AbsolutePath) -> anyhow::Result<String> {
+        self.editorconfig.decode(std::fs::read(path.to_path_buf())?)
+    }
+
     /// Check if the content of this file conflicts with that of the system.
     /// Return None if no conflict.
     fn check_conflict(
@@ -803,7 +1076,7 @@ impl Buffer {
             Some(Dispatches::one(Dispatch::ShowBufferSaveConflictPrompt {
                 path: path.clone(),
                 content_editor: self.content(),
-                content_filesystem: path.read()?,
+                content_filesystem: self.read_file(path)?,
             }))
         } else {
             None
@@ -820,6 +1093,7 @@ impl Buffer {
         let extra_dispatches = if force || self.dirty(context) {
             match self.get_formatted_content() {
                 Some(Ok(formatted_content)) => {
+                    let formatted_content = self.editorconfig.normalize(&formatted_content);
                     let dispatches = self.update_content(
                         &formatted_content,
                         current_selection_set,
@@ -837,8 +1111,9 @@ impl Buffer {
                         "Failed formatter execution".to_string(),
                         format!("{error}"),
                     ))),
-                },
-                None => Dispatches::empty(),
+                }
+                .chain(self.apply_editorconfig(current_selection_set, last_visible_line)?),
+                None => self.apply_editorconfig(current_selection_set, last_visible_line)?,
             }
         } else {
             Dispatches::empty()
@@ -848,6 +1123,25 @@ impl Buffer {
             .map(|(dispatches, path)| (dispatches.chain(extra_dispatches), path))
     }
 
+    /// Applies the `.editorconfig` properties that modify the content before saving,
+    /// namely `end_of_line`, `trim_trailing_whitespace` and `insert_final_newline`.
+    pub fn apply_editorconfig(
+        &mut self,
+        current_selection_set: SelectionSet,
+        last_visible_line: usize,
+    ) -> anyhow::Result<Dispatches> {
+        let content = self.content();
+        let normalized_content = self.editorconfig.normalize(&content);
+        if normalized_content == content {
+            return Ok(Dispatches::default());
+        }
+        self.update_content(
+            &normalized_content,
+            current_selection_set,
+            last_visible_line,
+        )
+    }
+
     pub fn update_content(
         &mut self,
         new_content: &str,
@@ -885,6 +1179,72 @@ impl Buffer {
         self.treesitter_language.clone()
     }
 
+    pub fn large_file_mode(&self) -> bool {
+        self.large_file_mode
+    }
+
+    /// Enabling large-file mode drops the syntax tree and highlights,
+    /// while disabling it parses the content with the buffer's language again.
+    pub fn set_large_file_mode(&mut self, large_file_mode: bool) {
+        self.large_file_mode = large_file_mode;
+        self.cached_hunks = None;
+        if large_file_mode {
+            self.tree = None;
+            self.injections = Vec::new();
+            self.treesitter_language = None;
+            self.highlighted_spans = HighlightedSpans::default();
+        } else {
+            self.treesitter_language = self
+                .language
+                .as_ref()
+                .and_then(|language| language.tree_sitter_language());
+            (_, self.tree) =
+                Self::get_rope_and_tree(self.treesitter_language.clone(), &self.content());
+            self.tree_outdated = false;
+            self.update_injections();
+        }
+    }
+
+    /// Returns the indentation character and its count per indentation level.
+    ///
+    /// The precedence is: the user override, `.editorconfig`,
+    /// the indentation detected from the content, then the global config.
+    pub fn indentation(&self, context: &Context) -> (char, usize) {
+        if let Some(indentation) = self.indentation_override {
+            return indentation;
+        }
+        self.editorconfig.indentation(
+            self.detected_indentation,
+            (context.indent_char(), context.indent_width()),
+        )
+    }
+
+    pub fn set_indentation_override(&mut self, indentation: Option<(char, usize)>) {
+        self.indentation_override = indentation
+    }
+
+    /// The line terminator for new lines, which is `\n` unless specified by `.editorconfig`.
+    pub fn end_of_line(&self) -> &'static str {
+        self.editorconfig
+            .end_of_line
+            .map(|end_of_line| end_of_line.as_str())
+            .unwrap_or("\n")
+    }
+
+    /// The width that lines are hard-wrapped to,
+    /// which is 80 unless specified by `.editorconfig`.
+    pub fn max_line_length(&self) -> usize {
+        self.editorconfig.max_line_length.unwrap_or(80)
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only
+    }
+
     pub fn get_char_at_position(&self, position: Position) -> Option<char> {
         let char_index = position.to_char_index(self).ok()?.0;
         self.rope.get_char(char_index)
@@ -1046,6 +1406,79 @@ impl Buffer {
         ))
     }
 
+    /// Returns the byte range of every match of `config`, with its replacement,
+    /// in order and without overlaps, and without modifying this buffer.
+    pub fn replacements(
+        &self,
+        config: &LocalSearchConfig,
+    ) -> anyhow::Result<Vec<(Range<usize>, String)>> {
+        let content = self.rope.to_string();
+        let replacements = match &config.mode {
+            LocalSearchConfigMode::NamingConventionAgnostic => {
+                let (search, replacement) = (config.search(), config.replacement());
+                NamingConventionAgnostic::new(search.clone())
+                    .find_all(&content)
+                    .into_iter()
+                    .filter_map(|(range, matched)| {
+                        let new =
+                            NamingConventionAgnostic::replace(&matched, &search, &replacement)
+                                .ok()?;
+                        Some((range.range().clone(), new))
+                    })
+                    .sorted_by_key(|(range, _)| (range.start, range.end))
+                    .collect_vec()
+            }
+            LocalSearchConfigMode::Regex(regex_config) => {
+                let regex = regex_config.to_regex(&config.search())?;
+                let replacement = config.replacement();
+                regex
+                    .captures_iter(&content)
+                    .map(|captures| -> anyhow::Result<_> {
+                        let captures = captures?;
+                        let range = captures
+                            .get(0)
+                            .map(|matched| matched.range())
+                            .unwrap_or_default();
+                        let new = fancy_regex::Expander::default().expand(&captures, &replacement);
+                        Ok((range, new))
+                    })
+                    .try_collect()?
+            }
+            LocalSearchConfigMode::AstGrep => match self.treesitter_language() {
+                Some(language) => {
+                    AstGrep::replace(language, &content, &config.search(), &config.replacement())?
+                        .into_iter()
+                        .map(|edit| -> anyhow::Result<_> {
+                            Ok((
+                                edit.position..edit.position + edit.deleted_length,
+                                String::from_utf8(edit.inserted_text)?,
+                            ))
+                        })
+                        .try_collect()?
+                }
+                None => Vec::new(),
+            },
+            LocalSearchConfigMode::Fuzzy(_) => {
+                return Err(anyhow::anyhow!(
+                    "Replacement is not supported in the fuzzy search mode."
+                ))
+            }
+        };
+        // Matches of different casings may overlap, in which case only the first is replaced
+        Ok(replacements.into_iter().fold(
+            Vec::new(),
+            |mut result: Vec<(Range<usize>, String)>, (range, new)| {
+                if result
+                    .last()
+                    .is_none_or(|(previous, _)| previous.end <= range.start)
+                {
+                    result.push((range, new))
+                }
+                result
+            },
+        ))
+    }
+
     /// The boolean returned indicates whether the replacement causes any modification
     pub fn replace(
         &mut self,
@@ -1100,6 +1533,11 @@ impl Buffer {
                         .try_collect()?,
                 )
             }
+            LocalSearchConfigMode::Fuzzy(_) => {
+                return Err(anyhow::anyhow!(
+                    "Replacement is not supported in the fuzzy search mode."
+                ))
+            }
         };
         let (selection_set, dispatches, diff_edits) = self.apply_edit_transaction(
             &edit_transaction,
@@ -1288,6 +1726,94 @@ impl Buffer {
     }
 }
 
+/// A file is considered large if its size exceeds `size_threshold` bytes,
+/// or if any of its lines is longer than `line_length_threshold` characters.
+pub fn is_large_file(content: &str, size_threshold: usize, line_length_threshold: usize) -> bool {
+    content.len() > size_threshold
+        || content.lines().any(|line| {
+            // The byte length is checked first because counting chars is more expensive
+            line.len() > line_length_threshold && line.chars().count() > line_length_threshold
+        })
+}
+
+/// Infers the indentation from the leading whitespace of the lines.
+///
+/// Tabs win if more lines are indented by tabs than by spaces,
+/// otherwise the width is the most common increase of leading spaces between
+/// consecutive non-blank lines.
+/// Returns `None` if no line is indented.
+pub fn detect_indentation(content: &str) -> Option<(char, usize)> {
+    const MAX_WIDTH: usize = 8;
+    let mut tab_indented_lines = 0;
+    let mut space_indented_lines = 0;
+    let mut width_counts = [0usize; MAX_WIDTH + 1];
+    let mut previous_space_count = 0;
+    // Sampling the beginning is enough, and keeps this cheap for huge files
+    for line in content.lines().take(10_000) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('\t') {
+            tab_indented_lines += 1;
+            continue;
+        }
+        let space_count = line.chars().take_while(|c| *c == ' ').count();
+        if space_count > 0 {
+            space_indented_lines += 1;
+        }
+        if let Some(increase) = space_count.checked_sub(previous_space_count) {
+            if (1..=MAX_WIDTH).contains(&increase) {
+                width_counts[increase] += 1;
+            }
+        }
+        previous_space_count = space_count;
+    }
+
+    if tab_indented_lines == 0 && space_indented_lines == 0 {
+        return None;
+    }
+    if tab_indented_lines > space_indented_lines {
+        return Some(('\t', 1));
+    }
+    // A width of 1 is usually caused by alignment, such as the ` *` of block comments,
+    // so it is only chosen when there is no other candidate
+    (2..=MAX_WIDTH)
+        .rev()
+        .max_by_key(|width| width_counts[*width])
+        .filter(|width| width_counts[*width] > 0)
+        .or((width_counts[1] > 0).then_some(1))
+        .map(|width| (' ', width))
+}
+
+#[cfg(unix)]
+fn is_writable(path: &AbsolutePath) -> bool {
+    let Ok(path) = std::ffi::CString::new(path.display_absolute()) else {
+        return true;
+    };
+    // Unlike the permission bits, `access` takes the current user into account
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_writable(path: &AbsolutePath) -> bool {
+    std::fs::metadata(path.to_path_buf())
+        .map(|metadata| !metadata.permissions().readonly())
+        .unwrap_or(true)
+}
+
+/// The arguments of a `dd` command that writes its stdin to `path`.
+///
+/// Unlike `tee`, `dd` prints nothing to stdout, which is not read until stdin is fully written,
+/// so large contents cannot fill the pipe and block, and non-UTF-8 contents are not echoed back.
+fn write_file_args(path: &AbsolutePath) -> Vec<String> {
+    [
+        "dd".to_string(),
+        format!("of={}", path.display_absolute()),
+        "status=none".to_string(),
+    ]
+    .to_vec()
+}
+
 #[cfg(test)]
 mod test_buffer {
     use std::{fs::File, ops::Range};
@@ -1304,6 +1830,20 @@ mod test_buffer {
 
     use super::Buffer;
 
+    #[test]
+    fn write_file_args_handle_large_non_utf8_content() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let path: AbsolutePath = dir.path().to_path_buf().try_into()?;
+        let path = path.join("large.txt")?;
+        // Larger than the pipe buffers, and invalid UTF-8
+        let content = [b'a', 0xe9, b'\n'].repeat(100_000);
+        let args = super::write_file_args(&path);
+        shared::process_command::ProcessCommand::new(&args[0], &args[1..])
+            .run_with_input(&content)?;
+        assert_eq!(std::fs::read(path.to_path_buf())?, content);
+        Ok(())
+    }
+
     #[test]
     fn get_parent_lines_1() {
         let buffer = Buffer::new(
@@ -1367,6 +1907,93 @@ fn f(
         pretty_assertions::assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn get_parent_lines_without_syntax_tree() {
+        let buffer = Buffer::new(
+            None,
+            "
+outline
+  first
+    detail
+
+    more detail
+  second
+",
+        );
+        let actual = buffer
+            .get_parent_lines(5)
+            .unwrap()
+            .into_iter()
+            .map(|line| line.content)
+            .collect_vec()
+            .join("\n");
+        pretty_assertions::assert_eq!(actual, "outline\n  first");
+    }
+
+    #[test]
+    fn is_large_file() {
+        assert!(!super::is_large_file("hello\nworld", 100, 10));
+        assert!(super::is_large_file("hello\nworld", 5, 10));
+        assert!(super::is_large_file("hello\nworld", 100, 4));
+        // Multi-byte characters should be measured by chars instead of bytes
+        assert!(!super::is_large_file("ひらがな", 100, 4));
+    }
+
+    #[test]
+    fn detect_indentation() {
+        use super::detect_indentation;
+        assert_eq!(detect_indentation("a\nb"), None);
+        assert_eq!(
+            detect_indentation("fn a() {\n\tb();\n\tif c {\n\t\td();\n\t}\n}"),
+            Some(('\t', 1))
+        );
+        assert_eq!(
+            detect_indentation("a:\n  b:\n    c: 1\n  d: 2\ne:\n  f: 3"),
+            Some((' ', 2))
+        );
+        assert_eq!(
+            detect_indentation(
+                "/**\n * Doc\n */\nfn a() {\n    b();\n    if c {\n        d();\n    }\n}"
+            ),
+            Some((' ', 4))
+        );
+    }
+
+    #[test]
+    fn read_file_decodes_with_charset() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join(".editorconfig"), "[*]\ncharset = latin1\n").unwrap();
+        let file_path = dir.path().join("latin1.txt");
+        std::fs::write(&file_path, b"caf\xe9").unwrap();
+        let path = AbsolutePath::try_from(file_path).unwrap();
+
+        let buffer = Buffer::from_path(&path, false).unwrap();
+        assert_eq!(buffer.content(), "caf\u{e9}");
+
+        std::fs::write(path.to_path_buf(), b"\xe0 la carte").unwrap();
+        assert_eq!(buffer.read_file(&path).unwrap(), "\u{e0} la carte");
+    }
+
+    #[test]
+    fn set_large_file_mode() {
+        let mut buffer = Buffer::new(
+            crate::config::from_extension("rs")
+                .unwrap()
+                .tree_sitter_language(),
+            "fn main() {}",
+        );
+        buffer.language = crate::config::from_extension("rs");
+        assert!(buffer.tree().is_some());
+
+        buffer.set_large_file_mode(true);
+        assert!(buffer.tree().is_none());
+        assert!(buffer.words().is_empty());
+
+        buffer.set_large_file_mode(false);
+        assert!(buffer.tree().is_some());
+        assert_eq!(buffer.words(), ["fn", "main"]);
+    }
+
     mod replace {
 
         use crate::{
@@ -1398,6 +2025,7 @@ fn f(
                     escaped: true,
                     case_sensitive: false,
                     match_whole_word: false,
+                    multi_line: false,
                 }))
                 .set_search("hel.".to_string())
                 .set_replacment("wow".to_string())
@@ -1414,6 +2042,7 @@ fn f(
                     escaped: false,
                     case_sensitive: false,
                     match_whole_word: false,
+                    multi_line: false,
                 }))
                 .set_search(r"(\d+)".to_string())
                 .set_replacment(r"($1)".to_string())
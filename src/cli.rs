@@ -120,6 +120,11 @@ struct EditArgs {
     /// Path to file to edit. If not provided and stdin is not connected to a terminal,
     /// content will be read from stdin and saved to a timestamp-based file
     path: Option<String>,
+
+    /// Open the file in large-file mode (`true`) or not (`false`),
+    /// instead of deciding by its size and line lengths
+    #[arg(long)]
+    large_file_mode: Option<bool>,
 }
 
 #[derive(Args)]
@@ -181,6 +186,7 @@ fn process_edit_args(args: EditArgs) -> anyhow::Result<RunConfig> {
             Ok(crate::RunConfig {
                 entry_path: path,
                 working_directory,
+                large_file_mode: args.large_file_mode,
             })
         }
         None => {
@@ -193,6 +199,7 @@ fn process_edit_args(args: EditArgs) -> anyhow::Result<RunConfig> {
                 Ok(crate::RunConfig {
                     entry_path: canonicalized_path,
                     working_directory: None,
+                    large_file_mode: args.large_file_mode,
                 })
             } else {
                 Ok(RunConfig::default())
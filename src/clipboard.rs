@@ -14,10 +14,31 @@ pub struct Clipboard {
 /// and so forth.
 pub struct Texts {
     texts: NonEmpty<String>,
+    /// The texts are the lines of a block selection,
+    /// so they should be pasted as a rectangle.
+    is_block: bool,
 }
 impl Texts {
     pub fn new(texts: NonEmpty<String>) -> Self {
-        Self { texts }
+        Self {
+            texts,
+            is_block: false,
+        }
+    }
+
+    pub fn block(texts: NonEmpty<String>) -> Self {
+        Self {
+            texts,
+            is_block: true,
+        }
+    }
+
+    pub fn is_block(&self) -> bool {
+        self.is_block
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.texts.iter()
     }
 
     fn join(&self, separator: &str) -> String {
@@ -8,13 +8,17 @@ use super::{
 };
 use crate::{
     app::{Dimension, Dispatch, Dispatches, RequestParams, Scope, ToHostApp},
+    arithmetic,
     buffer::{Buffer, EditHistoryKind, InsertSession, Line},
     char_index_range::{range_intersects, CharIndexRange},
     clipboard::Texts,
     components::component::{Component, RenderTitleMode},
     context::{Context, GlobalMode, LocalSearchConfig, LocalSearchConfigMode},
     edit::{Action, ActionGroup, Edit, EditTransaction},
-    git::{hunk::SimpleHunkKind, DiffMode, GitOperation as _, GitRepo},
+    git::{
+        hunk::SimpleHunkKind, merge_conflict::MergeConflictResolution, DiffMode, GitOperation as _,
+        GitRepo,
+    },
     grid::LINE_NUMBER_VERTICAL_BORDER,
     keymap::{
         insert_mode_keymap_legend_config, normal_mode_keymap_legend_config,
@@ -39,7 +43,8 @@ use crate::{
         PositionBasedSelectionMode,
     },
     surround::EnclosureKind,
-    transformation::{MyRegex, Transformation},
+    syntax_indent::{SyntaxIndent, SyntaxIndentation},
+    transformation::{MyRegex, Reorder, Transformation},
 };
 use crossterm::event::{MouseButton, MouseEventKind};
 use event::{KeyEvent, KeyEventKind};
@@ -356,6 +361,10 @@ impl Component for Editor {
             PipeToShell { command } => return self.pipe_to_shell(command, context),
             ShowCurrentTreeSitterNodeSexp => return self.show_current_tree_sitter_node_sexp(),
             Indent => return self.indent(context),
+            Reindent => return self.reindent(context),
+            GoToMatchingPair => return self.go_to_matching_pair(context),
+            SelectBlock => return self.select_block(context),
+            SetIndentation(indentation) => self.buffer_mut().set_indentation_override(indentation),
             Dedent => return self.dedent(context),
             CyclePrimarySelection(direction) => self.cycle_primary_selection(direction),
             SwapExtensionAnchor => self.selection_set.swap_anchor(),
@@ -369,7 +378,8 @@ impl Component for Editor {
             HandleEsc => {
                 self.disable_selection_extension();
                 self.mode = Mode::Normal;
-                return Ok(Dispatches::one(Dispatch::RemainOnlyCurrentComponent));
+                return Ok(Dispatches::one(Dispatch::RemainOnlyCurrentComponent)
+                    .append(Dispatch::CancelGlobalSearch));
             }
             ToggleReveal(reveal) => self.toggle_reveal(reveal),
             SearchCurrentSelection(if_current_not_found, scope) => {
@@ -380,10 +390,28 @@ impl Component for Editor {
             }
             ToggleLineComment => return self.toggle_line_comment(context),
             ToggleBlockComment => return self.toggle_block_comment(context),
+            HardWrap => return self.hard_wrap(context),
+            SummarizeSelections => return self.summarize_selections(),
+            Increment {
+                amount,
+                progressive,
+            } => {
+                return self.transform_selection(
+                    Transformation::Increment(crate::transformation::Increment {
+                        amount,
+                        progressive,
+                        word_rings: context.word_rings().clone(),
+                    }),
+                    context,
+                )
+            }
             RepeatSearch(scope, if_current_not_found, prior_change) => {
                 return self.repeat_search(context, scope, if_current_not_found, prior_change)
             }
             RevertHunk(diff_mode) => return self.revert_hunk(context, diff_mode),
+            ResolveMergeConflict { resolution, all } => {
+                return self.resolve_merge_conflict(context, resolution, all)
+            }
             GitBlame => return self.git_blame(context),
             ReloadFile { force } => return self.reload(context, force),
             MergeContent {
@@ -392,7 +420,7 @@ impl Component for Editor {
                 path,
             } => return self.merge_content(context, path, content_editor, content_filesystem),
             ClearIncrementalSearchMatches => self.clear_incremental_search_matches(),
-            GoToFile => return self.go_to_file(),
+            GoToFile => return self.go_to_file(context),
             SearchClipboardContent(scope) => {
                 return Ok(self.search_clipboard_content(scope, context))
             }
@@ -757,6 +785,7 @@ impl Editor {
             range: first_line_range,
             initial_range: None,
             info: None,
+            virtual_space: 0,
         };
 
         let selection_set = SelectionSet::default().set_selections(NonEmpty::new(selection));
@@ -1228,6 +1257,8 @@ impl Editor {
     }
 
     fn enter_newline(&mut self, context: &Context) -> anyhow::Result<Dispatches> {
+        self.buffer_mut().update_tree()?;
+        let unit = self.indentation_unit(context);
         let edit_transaction = EditTransaction::from_action_groups({
             let buffer = self.buffer();
             self.selection_set
@@ -1237,16 +1268,38 @@ impl Editor {
 
                     let current_line = buffer.get_line_by_line_index(current_line_index);
 
-                    let indent = "\n".to_string()
-                        + current_line
-                            .map(|line| {
-                                line.to_string()
-                                    .chars()
-                                    .take_while(|c| c.is_whitespace() && c != &'\n')
-                                    .join("")
+                    let end_of_line = buffer.end_of_line();
+                    let byte = buffer.char_to_byte(cursor)?;
+                    let (indent, closing) =
+                        match SyntaxIndent::new(&buffer, byte..byte).and_then(|syntax_indent| {
+                            syntax_indent.indentation(byte, &unit, |line_index| {
+                                syntax_indent.leading_whitespaces(line_index)
                             })
-                            .unwrap_or_default()
-                            .as_str();
+                        }) {
+                            // Put the closing token on its own line, below the new line
+                            Some(SyntaxIndentation::Closing { indentation, inner }) => (
+                                format!("{end_of_line}{inner}"),
+                                format!("{end_of_line}{indentation}"),
+                            ),
+                            Some(SyntaxIndentation::Inside(indentation)) => {
+                                (format!("{end_of_line}{indentation}"), String::new())
+                            }
+                            None => (
+                                end_of_line.to_string()
+                                    + current_line
+                                        .map(|line| {
+                                            line.to_string()
+                                                .chars()
+                                                .take_while(|c| {
+                                                    c.is_whitespace() && !matches!(c, '\n' | '\r')
+                                                })
+                                                .join("")
+                                        })
+                                        .unwrap_or_default()
+                                        .as_str(),
+                                String::new(),
+                            ),
+                        };
 
                     let range_start = cursor + indent.chars().count();
                     Ok(ActionGroup::new(
@@ -1254,7 +1307,7 @@ impl Editor {
                             Action::Edit(Edit::new(
                                 self.buffer().rope(),
                                 (cursor..cursor).into(),
-                                indent.into(),
+                                format!("{indent}{closing}").into(),
                             )),
                             Action::Select(
                                 selection
@@ -1282,13 +1335,18 @@ impl Editor {
     }
 
     fn get_current_texts(&self) -> Texts {
-        Texts::new(self.selection_set.map(|selection| {
+        let texts = self.selection_set.map(|selection| {
             self.buffer()
                 .slice(&selection.extended_range())
                 .ok()
                 .map(|s| s.to_string())
                 .unwrap_or_default()
-        }))
+        });
+        if self.selection_set.mode() == &SelectionMode::Block {
+            Texts::block(texts)
+        } else {
+            Texts::new(texts)
+        }
     }
 
     fn replace_current_selection_with<F>(
@@ -1376,6 +1434,9 @@ impl Editor {
         copied_texts: Texts,
         context: &Context,
     ) -> anyhow::Result<Dispatches> {
+        if copied_texts.is_block() && self.selection_set.len() == 1 {
+            return self.paste_block(get_gap_movement.to_direction(), copied_texts, context);
+        }
         let edit_transaction = EditTransaction::from_action_groups({
             self.get_selection_set_with_gap(&get_gap_movement, context)?
                 .into_iter()
@@ -1410,6 +1471,9 @@ impl Editor {
                         let paste_text = copied_text;
                         (selection_range, paste_text)
                     };
+                    let padding = " ".repeat(selection.virtual_space());
+                    let selection_range = selection_range.shift_right(padding.len());
+                    let paste_text: Rope = format!("{padding}{paste_text}").into();
                     ActionGroup::new(
                         [
                             Action::Edit(Edit::new(
@@ -1429,6 +1493,77 @@ impl Editor {
         self.apply_edit_transaction(edit_transaction, context)
     }
 
+    /// Pastes the lines of a copied block as a rectangle whose top-left corner is the cursor.
+    /// Lines that are too short are padded, and lines are appended if the buffer is too short.
+    fn paste_block(
+        &mut self,
+        direction: Direction,
+        copied_texts: Texts,
+        context: &Context,
+    ) -> anyhow::Result<Dispatches> {
+        let action_groups = {
+            let buffer = self.buffer();
+            let range = self.selection_set.primary_selection().extended_range();
+            let position = buffer.char_to_position(match direction {
+                Direction::Start => range.start,
+                Direction::End => range.end,
+            })?;
+            let mut action_groups = Vec::new();
+            let mut appended_text = String::new();
+            let mut appended_ranges = Vec::new();
+            for (index, text) in copied_texts.iter().enumerate() {
+                let line = position.line + index;
+                let text_len = text.chars().count();
+                if line < buffer.len_lines() {
+                    let line_start = buffer.line_to_char(line)?;
+                    let line_length = line_length(&buffer.get_line_by_line_index(line)?);
+                    let insertion = line_start + position.column.min(line_length);
+                    let padding = if text.is_empty() {
+                        0
+                    } else {
+                        position.column.saturating_sub(line_length)
+                    };
+                    let start = insertion + padding;
+                    action_groups.push(ActionGroup::new(
+                        [
+                            Action::Edit(Edit::new(
+                                buffer.rope(),
+                                (insertion..insertion).into(),
+                                format!("{}{text}", " ".repeat(padding)).into(),
+                            )),
+                            Action::Select(Selection::new((start..start + text_len).into())),
+                        ]
+                        .to_vec(),
+                    ))
+                } else {
+                    appended_text.push('\n');
+                    appended_text.push_str(&" ".repeat(position.column));
+                    let start = appended_text.chars().count();
+                    appended_text.push_str(text);
+                    appended_ranges.push(start..start + text_len);
+                }
+            }
+            if !appended_text.is_empty() {
+                let end = CharIndex(buffer.len_chars());
+                action_groups.push(ActionGroup::new(
+                    Some(Action::Edit(Edit::new(
+                        buffer.rope(),
+                        (end..end).into(),
+                        appended_text.into(),
+                    )))
+                    .into_iter()
+                    .chain(appended_ranges.into_iter().map(|range| {
+                        Action::Select(Selection::new((end + range.start..end + range.end).into()))
+                    }))
+                    .collect_vec(),
+                ))
+            }
+            action_groups
+        };
+        let edit_transaction = EditTransaction::from_action_groups(action_groups);
+        self.apply_edit_transaction(edit_transaction, context)
+    }
+
     pub fn paste_with_movement(
         &mut self,
         context: &Context,
@@ -1577,6 +1712,10 @@ impl Editor {
         context: &Context,
         kind: EditHistoryKind,
     ) -> anyhow::Result<Dispatches> {
+        if !edit_transaction.edits().is_empty() {
+            self.ensure_writable()?;
+        }
+
         // Apply the transaction to the buffer
         let last_visible_line = self.last_visible_line(context);
         let (new_selection_set, dispatches, diff_edits) =
@@ -1639,6 +1778,10 @@ impl Editor {
     }
 
     pub fn get_document_did_change_dispatch(&mut self) -> Dispatches {
+        // Large files are neither highlighted nor synced to the LSP
+        if self.buffer().large_file_mode() {
+            return Dispatches::default();
+        }
         [Dispatch::DocumentDidChange {
             component_id: self.id(),
             batch_id: self.buffer().batch_id().clone(),
@@ -1846,10 +1989,16 @@ impl Editor {
         context: &Context,
         kind: EditHistoryKind,
     ) -> anyhow::Result<Dispatches> {
-        let edit_transaction = EditTransaction::from_action_groups(
+        let edit_transaction = self.get_insert_edit_transaction(s);
+        self.apply_edit_transaction_with_edit_history_kind(edit_transaction, context, kind)
+    }
+
+    fn get_insert_edit_transaction(&self, s: &str) -> EditTransaction {
+        EditTransaction::from_action_groups(
             self.selection_set
                 .map(|selection| {
                     let range = selection.extended_range();
+                    let s = format!("{}{s}", " ".repeat(selection.virtual_space()));
                     let new_char_index = range.start + s.chars().count();
                     ActionGroup::new(
                         [
@@ -1859,7 +2008,7 @@ impl Editor {
                                     let start = selection.to_char_index(&Direction::End);
                                     (start..start).into()
                                 },
-                                Rope::from_str(s),
+                                Rope::from_str(&s),
                             )),
                             Action::Select(
                                 selection
@@ -1871,9 +2020,7 @@ impl Editor {
                     )
                 })
                 .into(),
-        );
-
-        self.apply_edit_transaction_with_edit_history_kind(edit_transaction, context, kind)
+        )
     }
 
     pub fn get_request_params(&self) -> Option<RequestParams> {
@@ -2029,6 +2176,7 @@ impl Editor {
         direction: Direction,
         context: &Context,
     ) -> anyhow::Result<Dispatches> {
+        self.ensure_writable()?;
         self.set_selection_set(
             self.selection_set
                 .apply(self.selection_set.mode.clone(), |selection| {
@@ -2799,10 +2947,17 @@ impl Editor {
         get_gap_movement: GetGapMovement,
     ) -> Result<Dispatches, anyhow::Error> {
         let direction = get_gap_movement.to_direction();
+        let unit = self.indentation_unit(context);
         let edit_transaction = EditTransaction::from_action_groups(
             self.get_selection_set_with_gap(&get_gap_movement, context)?
                 .into_iter()
                 .map(|(selection, gap)| {
+                    let gap = match direction {
+                        Direction::Start => gap,
+                        Direction::End => {
+                            syntax_indent_gap(&self.buffer(), selection.range().end, gap, &unit)
+                        }
+                    };
                     let gap_len = gap.len_chars();
                     ActionGroup::new(
                         [
@@ -2905,6 +3060,52 @@ impl Editor {
             }))
     }
 
+    /// Unlike `save`, this does not change the mode, the cursors or the layout,
+    /// so that it can run without interrupting the user.
+    pub(crate) fn auto_save(
+        &mut self,
+        run_formatter: bool,
+        context: &Context,
+    ) -> anyhow::Result<Dispatches> {
+        if context.is_running_as_embedded()
+            || self.buffer().path().is_none()
+            || self.buffer().read_only()
+            || !self.buffer().dirty(context)
+        {
+            return Ok(Dispatches::default());
+        }
+
+        let (dispatches, path) = if run_formatter {
+            let last_visible_line = self.last_visible_line(context);
+            self.buffer.borrow_mut().save(
+                context,
+                self.selection_set.clone(),
+                false,
+                last_visible_line,
+            )?
+        } else {
+            let last_visible_line = self.last_visible_line(context);
+            let dispatches = self
+                .buffer
+                .borrow_mut()
+                .apply_editorconfig(self.selection_set.clone(), last_visible_line)?;
+            let (other_dispatches, path) = self
+                .buffer
+                .borrow_mut()
+                .save_without_formatting(context, false)?;
+            (dispatches.chain(other_dispatches), path)
+        };
+
+        let Some(path) = path else {
+            return Ok(dispatches);
+        };
+
+        self.clamp(context)?;
+        Ok(Dispatches::one(Dispatch::DocumentDidSave { path })
+            .chain(self.get_document_did_change_dispatch())
+            .chain(dispatches))
+    }
+
     /// Clamp everything that might be out of bound after the buffer content is modified elsewhere
     fn clamp(&mut self, context: &Context) -> anyhow::Result<()> {
         let len_chars = self.buffer().len_chars();
@@ -2960,38 +3161,98 @@ impl Editor {
         transformation: Transformation,
         context: &Context,
     ) -> anyhow::Result<Dispatches> {
-        let edit_transaction = EditTransaction::from_action_groups(
-            self.selection_set
-                .map_with_index(|index, selection| -> anyhow::Result<_> {
-                    let new: Rope = transformation
-                        .apply(
-                            index,
-                            self.buffer()
-                                .slice(&selection.extended_range())?
-                                .to_string(),
-                        )?
-                        .into();
-                    let new_char_count = new.chars().count();
-                    let range = selection.extended_range();
-                    Ok(ActionGroup::new(
-                        [
-                            Action::Edit(Edit::new(self.buffer().rope(), range, new)),
-                            Action::Select(
-                                selection
-                                    .clone()
-                                    .set_range((range.start..range.start + new_char_count).into())
-                                    .set_initial_range(None),
-                            ),
-                        ]
-                        .to_vec(),
-                    ))
+        if let Transformation::Reorder(reorder) = &transformation {
+            if self.selection_set.len() > 1 {
+                return self.reorder_selections(reorder, context);
+            }
+        }
+        let (action_groups, errors): (Vec<_>, Vec<_>) = self
+            .selection_set
+            .map_with_index(|index, selection| -> anyhow::Result<_> {
+                let content = self
+                    .buffer()
+                    .slice(&selection.extended_range())?
+                    .to_string();
+                // A failed selection is left unchanged, so that the others are still transformed
+                Ok(match transformation.apply(index, content) {
+                    Ok(new) => (self.replace_selection_content(selection, new.into()), None),
+                    Err(error) => (
+                        ActionGroup::new([Action::Select(selection.clone())].to_vec()),
+                        Some(format!("Selection {}: {error}", index + 1)),
+                    ),
                 })
+            })
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        let dispatches = self
+            .apply_edit_transaction(EditTransaction::from_action_groups(action_groups), context)?;
+        let errors = errors.into_iter().flatten().collect_vec();
+        if errors.is_empty() {
+            return Ok(dispatches);
+        }
+        Ok(dispatches.append(Dispatch::ShowGlobalInfo(Info::new(
+            format!("{transformation} failed"),
+            errors.join("\n"),
+        ))))
+    }
+
+    /// Permutes the contents of the selections among their positions,
+    /// for example to sort the arguments of a function.
+    /// If duplicates are removed, the last selections are emptied.
+    fn reorder_selections(
+        &mut self,
+        reorder: &Reorder,
+        context: &Context,
+    ) -> anyhow::Result<Dispatches> {
+        let selections = self
+            .selection_set
+            .selections()
+            .iter()
+            .sorted_by_key(|selection| selection.extended_range().start)
+            .collect_vec();
+        let contents: Vec<String> = selections
+            .iter()
+            .map(|selection| -> anyhow::Result<_> {
+                Ok(self
+                    .buffer()
+                    .slice(&selection.extended_range())?
+                    .to_string())
+            })
+            .try_collect()?;
+        let mut contents = reorder.apply(contents).into_iter();
+        let edit_transaction = EditTransaction::from_action_groups(
+            selections
                 .into_iter()
-                .try_collect()?,
+                .map(|selection| {
+                    self.replace_selection_content(
+                        selection,
+                        contents.next().unwrap_or_default().into(),
+                    )
+                })
+                .collect_vec(),
         );
         self.apply_edit_transaction(edit_transaction, context)
     }
 
+    fn replace_selection_content(&self, selection: &Selection, new: Rope) -> ActionGroup {
+        let new_char_count = new.chars().count();
+        let range = selection.extended_range();
+        ActionGroup::new(
+            [
+                Action::Edit(Edit::new(self.buffer().rope(), range, new)),
+                Action::Select(
+                    selection
+                        .clone()
+                        .set_range((range.start..range.start + new_char_count).into())
+                        .set_initial_range(None),
+                ),
+            ]
+            .to_vec(),
+        )
+    }
+
     pub fn display_mode(&self) -> String {
         match &self.keymap_override {
             Some(EditorKeymapOverride::Jumps(_)) => "JUMP".to_string(),
@@ -3124,6 +3385,7 @@ impl Editor {
                         escaped: true,
                         case_sensitive: false,
                         match_whole_word: false,
+                        multi_line: false,
                     }),
                     search: search.to_string(),
                 },
@@ -3230,6 +3492,7 @@ impl Editor {
         context: &Context,
         reparse_tree: bool,
     ) -> Result<Option<(Dispatches, EditHistoryKind)>, anyhow::Error> {
+        self.ensure_writable()?;
         let last_visible_line = self.last_visible_line(context);
 
         // Call the appropriate buffer method to perform undo/redo
@@ -3411,34 +3674,96 @@ impl Editor {
         kind: SurroundKind,
         context: &Context,
     ) -> anyhow::Result<Dispatches> {
+        self.disable_selection_extension();
+        let edit_transaction =
+            EditTransaction::from_action_groups(
+                self.selection_set
+                    .map(|selection| -> anyhow::Result<_> {
+                        let buffer = self.buffer();
+                        let cursor_char_index = selection.get_anchor(&self.cursor_direction);
+                        if let Some((open_range, close_range)) =
+                            crate::surround::get_surrounding_ranges(
+                                &buffer,
+                                enclosure,
+                                cursor_char_index,
+                                false,
+                            )
+                        {
+                            let range = match kind {
+                                SurroundKind::Inside => open_range.end..close_range.start,
+                                SurroundKind::Around => open_range.start..close_range.end,
+                            }
+                            .into();
+                            Ok(ActionGroup::new(
+                                [Action::Select(selection.clone().set_range(range))].to_vec(),
+                            ))
+                        } else {
+                            Ok(ActionGroup::new(Vec::default()))
+                        }
+                    })
+                    .into_iter()
+                    .flatten()
+                    .collect_vec(),
+            );
+        let _ = self.set_selection_mode(
+            IfCurrentNotFound::LookForward,
+            SelectionMode::Custom,
+            context,
+            None,
+        );
+        self.disable_selection_extension();
+        self.apply_edit_transaction(edit_transaction, context)
+    }
+
+    /// Replaces the selections with one selection per line of the rectangle whose
+    /// opposite corners are the first and the last character of the primary selection,
+    /// which are the anchor and the cursor when the selection is extended.
+    ///
+    /// Lines that end before the rectangle get an empty selection at their end,
+    /// with virtual space up to the first column of the rectangle.
+    fn select_block(&mut self, context: &Context) -> anyhow::Result<Dispatches> {
+        let selections = {
+            let buffer = self.buffer();
+            let range = self.selection_set.primary_selection().extended_range();
+            let first = buffer.char_to_position(range.start)?;
+            let last = buffer.char_to_position((range.end - 1).max(range.start))?;
+            let columns = first.column.min(last.column)..first.column.max(last.column) + 1;
+            (first.line..=last.line)
+                .map(|line| -> anyhow::Result<_> {
+                    let line_start = buffer.line_to_char(line)?;
+                    let line_length = line_length(&buffer.get_line_by_line_index(line)?);
+                    let start = columns.start.min(line_length);
+                    let end = columns.end.min(line_length);
+                    Ok(
+                        Selection::new((line_start + start..line_start + end).into())
+                            .set_virtual_space(columns.start - start),
+                    )
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
+        let Some(selections) = NonEmpty::from_vec(selections) else {
+            return Ok(Dispatches::default());
+        };
+        let selection_set = self
+            .selection_set
+            .clone()
+            .set_selections(selections)
+            .set_mode(SelectionMode::Block);
+        Ok(self.update_selection_set(selection_set, true, context))
+    }
+
+    fn go_to_matching_pair(&mut self, context: &Context) -> anyhow::Result<Dispatches> {
         self.disable_selection_extension();
         let edit_transaction = EditTransaction::from_action_groups(
             self.selection_set
-                .map(|selection| -> anyhow::Result<_> {
-                    let buffer = self.buffer();
+                .map(|selection| {
                     let cursor_char_index = selection.get_anchor(&self.cursor_direction);
-                    if let Some((open_index, close_index)) =
-                        crate::surround::get_surrounding_indices(
-                            &buffer.content(),
-                            enclosure,
-                            cursor_char_index,
-                            false,
-                        )
-                    {
-                        let offset = match kind {
-                            SurroundKind::Inside => 1,
-                            SurroundKind::Around => 0,
-                        };
-                        let range = ((open_index + offset)..(close_index + 1 - offset)).into();
-                        Ok(ActionGroup::new(
-                            [Action::Select(selection.clone().set_range(range))].to_vec(),
-                        ))
-                    } else {
-                        Ok(ActionGroup::new(Vec::default()))
-                    }
+                    let range =
+                        crate::surround::get_matching_pair_range(&self.buffer(), cursor_char_index)
+                            .unwrap_or_else(|| selection.extended_range());
+                    ActionGroup::new([Action::Select(selection.clone().set_range(range))].to_vec())
                 })
                 .into_iter()
-                .flatten()
                 .collect_vec(),
         );
         let _ = self.set_selection_mode(
@@ -3447,7 +3772,6 @@ impl Editor {
             context,
             None,
         );
-        self.disable_selection_extension();
         self.apply_edit_transaction(edit_transaction, context)
     }
 
@@ -3466,60 +3790,64 @@ impl Editor {
         context: &Context,
     ) -> Result<Dispatches, anyhow::Error> {
         self.disable_selection_extension();
-        let edit_transaction = EditTransaction::from_action_groups(
-            self.selection_set
-                .map(|selection| -> anyhow::Result<_> {
-                    let buffer = self.buffer();
-                    let cursor_char_index = selection.get_anchor(&self.cursor_direction);
-                    if let Some((open_index, close_index)) =
-                        crate::surround::get_surrounding_indices(
-                            &buffer.content(),
-                            from,
-                            cursor_char_index,
-                            true,
-                        )
-                    {
-                        let open_range = (open_index..open_index + 1).into();
-                        let close_range = (close_index..close_index + 1).into();
-                        let (new_open, new_close) = to
-                            .as_ref()
-                            .map(|to| to.open_close_symbols_str())
-                            .unwrap_or(("", ""));
-                        let select_range = (open_index + 1 - new_open.chars().count()
-                            ..(close_index + new_close.chars().count()))
-                            .into();
-                        Ok([
-                            ActionGroup::new(
-                                [Action::Edit(Edit::new(
-                                    self.buffer().rope(),
-                                    open_range,
-                                    new_open.into(),
-                                ))]
-                                .to_vec(),
-                            ),
-                            ActionGroup::new(
-                                [Action::Edit(Edit::new(
-                                    self.buffer().rope(),
-                                    close_range,
-                                    new_close.into(),
-                                ))]
-                                .to_vec(),
-                            ),
-                            ActionGroup::new(
-                                [Action::Select(selection.clone().set_range(select_range))]
+        let edit_transaction =
+            EditTransaction::from_action_groups(
+                self.selection_set
+                    .map(|selection| -> anyhow::Result<_> {
+                        let buffer = self.buffer();
+                        let cursor_char_index = selection.get_anchor(&self.cursor_direction);
+                        if let Some((open_range, close_range)) =
+                            crate::surround::get_surrounding_ranges(
+                                &buffer,
+                                from,
+                                cursor_char_index,
+                                true,
+                            )
+                        {
+                            let (new_open, new_close) = to
+                                .as_ref()
+                                .and_then(|to| to.open_close_symbols_str())
+                                .unwrap_or(("", ""));
+                            // The selection will be offset by the edit of the open symbol,
+                            // because it is in the same action group as the edit of the close symbol
+                            let select_range = (open_range.end - new_open.chars().count()
+                                ..if new_close.is_empty() {
+                                    close_range.start
+                                } else {
+                                    close_range.start + new_close.chars().count()
+                                })
+                                .into();
+                            Ok([
+                                ActionGroup::new(
+                                    [Action::Edit(Edit::new(
+                                        self.buffer().rope(),
+                                        open_range,
+                                        new_open.into(),
+                                    ))]
                                     .to_vec(),
-                            ),
-                        ]
-                        .to_vec())
-                    } else {
-                        Ok(Vec::default())
-                    }
-                })
-                .into_iter()
-                .flatten()
-                .flatten()
-                .collect_vec(),
-        );
+                                ),
+                                ActionGroup::new(
+                                    [
+                                        Action::Edit(Edit::new(
+                                            self.buffer().rope(),
+                                            close_range,
+                                            new_close.into(),
+                                        )),
+                                        Action::Select(selection.clone().set_range(select_range)),
+                                    ]
+                                    .to_vec(),
+                                ),
+                            ]
+                            .to_vec())
+                        } else {
+                            Ok(Vec::default())
+                        }
+                    })
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .collect_vec(),
+            );
         let _ = self.set_selection_mode(
             IfCurrentNotFound::LookForward,
             SelectionMode::Custom,
@@ -3594,6 +3922,9 @@ impl Editor {
                 },
                 context,
             ),
+            LocalSearchConfigMode::Fuzzy(_) => Err(anyhow::anyhow!(
+                "Replacement is not supported in the fuzzy search mode."
+            )),
         }
     }
 
@@ -3642,8 +3973,116 @@ impl Editor {
         ))))
     }
 
+    fn indentation_unit(&self, context: &Context) -> String {
+        let (indent_char, indent_width) = self.buffer().indentation(context);
+        std::iter::repeat_n(indent_char, indent_width).collect()
+    }
+
+    /// Returns `None` if the language of this buffer has no indents query.
+    ///
+    /// Each action group is paired with whether it indents
+    /// the first non-blank line of its selection deeper than it currently is.
+    fn get_reindent_action_groups(
+        &self,
+        context: &Context,
+    ) -> anyhow::Result<Option<Vec<(ActionGroup, bool)>>> {
+        let unit = self.indentation_unit(context);
+        let buffer = self.buffer();
+        Ok(self
+            .selection_set
+            .map(|selection| -> anyhow::Result<_> {
+                let original_range = selection.extended_range();
+                let line_range = buffer.char_index_range_to_line_range(original_range)?;
+                let linewise_range =
+                    buffer.line_range_to_full_char_index_range(line_range.clone())?;
+                let byte_range = buffer.char_to_byte(linewise_range.start)?
+                    ..buffer.char_to_byte(linewise_range.end)?;
+                let Some(syntax_indent) = SyntaxIndent::new(&buffer, byte_range) else {
+                    return Ok(None);
+                };
+                let indentations = syntax_indent.reindent(line_range.clone(), &unit);
+                let mut deeper = None;
+                let mut first_line_offset: isize = 0;
+                let mut length_change: isize = 0;
+                let new: Rope = buffer
+                    .slice(&linewise_range)?
+                    .lines()
+                    .zip(line_range.clone())
+                    .map(|(line, line_index)| {
+                        let line = line.to_string();
+                        let Some((_, indentation)) =
+                            indentations.iter().find(|(index, _)| index == &line_index)
+                        else {
+                            return line;
+                        };
+                        let content = line.trim_start_matches([' ', '\t']);
+                        let old_len = line.len() - content.len();
+                        if deeper.is_none() && !content.trim().is_empty() {
+                            deeper = Some(indentation.len() > old_len)
+                        }
+                        let offset = indentation.len() as isize - old_len as isize;
+                        if line_index == line_range.start {
+                            first_line_offset = offset
+                        }
+                        length_change += offset;
+                        format!("{indentation}{content}")
+                    })
+                    .join("")
+                    .into();
+                let select_range = original_range
+                    .start
+                    .apply_offset(first_line_offset)
+                    .max(linewise_range.start)
+                    ..original_range.end.apply_offset(length_change);
+                Ok(Some((
+                    ActionGroup::new(
+                        [
+                            Action::Edit(Edit::new(buffer.rope(), linewise_range, new)),
+                            Action::Select(selection.clone().set_range(select_range.into())),
+                        ]
+                        .to_vec(),
+                    ),
+                    deeper.unwrap_or(false),
+                )))
+            })
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .collect())
+    }
+
+    fn reindent(&mut self, context: &Context) -> anyhow::Result<Dispatches> {
+        self.buffer_mut().update_tree()?;
+        let Some(action_groups) = self.get_reindent_action_groups(context)? else {
+            return Err(anyhow::anyhow!(
+                "Unable to reindent because no indents query is found for this language."
+            ));
+        };
+        let edit_transaction = EditTransaction::from_action_groups(
+            action_groups
+                .into_iter()
+                .map(|(action_group, _)| action_group)
+                .collect(),
+        );
+        self.apply_edit_transaction(edit_transaction, context)
+    }
+
     fn indent(&mut self, context: &Context) -> Result<Dispatches, anyhow::Error> {
-        let indentation: Rope = std::iter::repeat_n(context.indent_char(), context.indent_width())
+        // Lines that are less indented than the syntax tree suggests are reindented instead,
+        // so that indenting a new or pasted line puts it at the right level immediately
+        if let Some(action_groups) = self.get_reindent_action_groups(context)? {
+            if action_groups.iter().all(|(_, deeper)| *deeper) {
+                let edit_transaction = EditTransaction::from_action_groups(
+                    action_groups
+                        .into_iter()
+                        .map(|(action_group, _)| action_group)
+                        .collect(),
+                );
+                return self.apply_edit_transaction(edit_transaction, context);
+            }
+        }
+        let (indent_char, indent_width) = self.buffer().indentation(context);
+        let indentation: Rope = std::iter::repeat_n(indent_char, indent_width)
             .collect::<String>()
             .into();
         let edit_transaction = EditTransaction::from_action_groups(
@@ -3678,7 +4117,7 @@ impl Editor {
                         .join("")
                         .into();
                     let select_range = {
-                        let offset: isize = context.indent_width() as isize;
+                        let offset: isize = indent_width as isize;
                         let start = original_range.start.apply_offset(offset);
                         let original_len = original_range.len();
                         let end =
@@ -3702,6 +4141,7 @@ impl Editor {
     }
 
     fn dedent(&mut self, context: &Context) -> Result<Dispatches, anyhow::Error> {
+        let (indent_char, indent_width) = self.buffer().indentation(context);
         let edit_transaction = EditTransaction::from_action_groups(
             self.selection_set
                 .map(|selection| -> anyhow::Result<_> {
@@ -3714,11 +4154,9 @@ impl Editor {
                         .line_range_to_full_char_index_range(line_range.clone())?;
                     let content = self.buffer().slice(&linewise_range)?;
                     let get_remove_leading_char_count = |line: &str| {
-                        let leading_indent_count = line
-                            .chars()
-                            .take_while(|c| c == &context.indent_char())
-                            .count();
-                        leading_indent_count.min(context.indent_width())
+                        let leading_indent_count =
+                            line.chars().take_while(|c| c == &indent_char).count();
+                        leading_indent_count.min(indent_width)
                     };
                     let modified_lines = content
                         .lines()
@@ -3847,6 +4285,9 @@ impl Editor {
                             .is_empty()
                             .not()
                     }
+                    LocalSearchConfigMode::Fuzzy(_) => {
+                        selection_mode::fuzzy::is_match(&search, &haystack)
+                    }
                 };
                 if maintain && is_match || !maintain && !is_match {
                     Some(selection.clone())
@@ -4153,17 +4594,34 @@ impl Editor {
             .update_content(new_content, current_selection_set, last_visible_line)
     }
 
+    /// The line comment prefix of the innermost language at `char_index`,
+    /// such as that of the code in a Markdown code fence.
+    fn line_comment_prefix_at(&self, char_index: CharIndex) -> Option<String> {
+        let buffer = self.buffer();
+        buffer
+            .language_at(buffer.char_to_byte(char_index).ok()?)
+            .and_then(|language| language.line_comment_prefix())
+    }
+
     fn toggle_line_comment(&mut self, context: &Context) -> anyhow::Result<Dispatches> {
-        let Some(prefix) = self
-            .buffer()
-            .language()
-            .and_then(|langugae| langugae.line_comment_prefix())
-        else {
+        if self
+            .selection_set
+            .map(|selection| self.line_comment_prefix_at(selection.extended_range().start))
+            .into_iter()
+            .all(|prefix| prefix.is_none())
+        {
             return Ok(Dispatches::default());
-        };
+        }
         let edit_transaction = EditTransaction::from_action_groups(
             self.selection_set
                 .map(|selection| -> anyhow::Result<_> {
+                    let Some(prefix) =
+                        self.line_comment_prefix_at(selection.extended_range().start)
+                    else {
+                        return Ok(ActionGroup::new(
+                            [Action::Select(selection.clone())].to_vec(),
+                        ));
+                    };
                     let buffer = self.buffer();
                     let line_range =
                         buffer.char_index_range_to_line_range(selection.extended_range())?;
@@ -4239,6 +4697,49 @@ impl Editor {
         self.transform_selection(Transformation::ToggleBlockComment { open, close }, context)
     }
 
+    fn hard_wrap(&mut self, context: &Context) -> anyhow::Result<Dispatches> {
+        let transformation = Transformation::HardWrap(crate::transformation::HardWrap {
+            width: self.buffer().max_line_length(),
+            // The prefix of the code in a Markdown code fence is that of the fenced language
+            line_comment_prefix: self.line_comment_prefix_at(
+                self.selection_set
+                    .primary_selection()
+                    .extended_range()
+                    .start,
+            ),
+        });
+        self.transform_selection(transformation, context)
+    }
+
+    /// Selections that are not arithmetic expressions are skipped.
+    fn summarize_selections(&self) -> anyhow::Result<Dispatches> {
+        let (numbers, skipped): (Vec<_>, Vec<_>) = self
+            .selection_set
+            .selections()
+            .iter()
+            .map(|selection| -> anyhow::Result<_> {
+                Ok(arithmetic::evaluate(
+                    &self
+                        .buffer()
+                        .slice(&selection.extended_range())?
+                        .to_string(),
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .partition_result();
+        let summary = arithmetic::summarize(&numbers)?;
+        let info = if skipped.is_empty() {
+            summary
+        } else {
+            format!("{summary}\nSkipped: {} (not a number)", skipped.len())
+        };
+        Ok(Dispatches::one(Dispatch::ShowEditorInfo(Info::new(
+            "Summary of selections".to_string(),
+            info,
+        ))))
+    }
+
     fn handle_movement_with_prior_change(
         &mut self,
         context: &Context,
@@ -4343,6 +4844,60 @@ impl Editor {
         self.apply_edit_transaction(edit_transaction, context)
     }
 
+    fn resolve_merge_conflict(
+        &mut self,
+        context: &Context,
+        resolution: MergeConflictResolution,
+        all: bool,
+    ) -> anyhow::Result<Dispatches> {
+        let action_groups = {
+            let buffer = self.buffer();
+            let content = buffer.content();
+            let selected_byte_ranges = self
+                .selection_set
+                .map(|selection| buffer.char_index_range_to_byte_range(selection.extended_range()))
+                .into_iter()
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let conflicts = buffer
+                .merge_conflicts()
+                .iter()
+                .filter(|conflict| {
+                    all || selected_byte_ranges.iter().any(|range| {
+                        range_intersects(conflict.range(), range)
+                            || conflict.range().contains(&range.start)
+                    })
+                })
+                .collect_vec();
+            if conflicts.is_empty() {
+                return Err(anyhow::anyhow!("No merge conflict is selected."));
+            }
+            let template = self.selection_set.primary_selection().clone();
+            conflicts
+                .iter()
+                .filter_map(|conflict| {
+                    let replacement: Rope = conflict.resolve(&content, resolution)?.into();
+                    let range = buffer
+                        .byte_range_to_char_index_range(conflict.range())
+                        .ok()?;
+                    let select_range = (range.start..range.start + replacement.len_chars()).into();
+                    Some(ActionGroup::new(
+                        [
+                            Action::Edit(Edit::new(buffer.rope(), range, replacement)),
+                            Action::Select(template.clone().set_range(select_range)),
+                        ]
+                        .to_vec(),
+                    ))
+                })
+                .collect_vec()
+        };
+        if action_groups.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The selected merge conflicts have no base, see `merge.conflictStyle = diff3`."
+            ));
+        }
+        self.apply_edit_transaction(EditTransaction::from_action_groups(action_groups), context)
+    }
+
     fn git_blame(&self, context: &Context) -> Result<Dispatches, anyhow::Error> {
         let Some(file_path) = self.buffer().path() else {
             return Ok(Dispatches::default());
@@ -4434,6 +4989,11 @@ impl Editor {
                     .map(|(range, _)| range.range().clone())
                     .collect_vec()
             }
+            LocalSearchConfigMode::Fuzzy(candidate) => {
+                selection_mode::Fuzzy::new(&self.buffer(), &search, candidate)
+                    .map(|fuzzy| fuzzy.find_all())
+                    .unwrap_or_default()
+            }
         };
         self.incremental_search_matches = Some(matches);
     }
@@ -4442,18 +5002,40 @@ impl Editor {
         self.incremental_search_matches = Some(Vec::new());
     }
 
-    fn go_to_file(&self) -> Result<Dispatches, anyhow::Error> {
-        let paths: Vec<AbsolutePath> = self
+    fn go_to_file(&self, context: &Context) -> Result<Dispatches, anyhow::Error> {
+        let paths_and_positions = self
             .selection_set
             .selections
             .iter()
-            .map(|selection| {
-                self.buffer()
+            .map(|selection| -> anyhow::Result<_> {
+                let text = self
+                    .buffer()
                     .slice(&selection.extended_range())?
-                    .to_string()
-                    .try_into()
+                    .to_string();
+                let (path, position) = parse_path_with_position(text.trim());
+                // Relative paths, such as those in the output of a compiler,
+                // are relative to the working directory rather than the process
+                let path = match PathBuf::from(path) {
+                    path if path.starts_with("~") => AbsolutePath::home_dir()?
+                        .join(&path.strip_prefix("~")?.to_string_lossy())?,
+                    path if path.is_relative() => context
+                        .current_working_directory()
+                        .join(&path.to_string_lossy())?,
+                    path => path.try_into()?,
+                };
+                Ok((path, position))
             })
-            .collect::<anyhow::Result<Vec<AbsolutePath>>>()?;
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if let [(path, Some(position))] = paths_and_positions.as_slice() {
+            return Ok(Dispatches::one(Dispatch::OpenFileAtPosition {
+                path: path.clone(),
+                position: *position,
+            }));
+        }
+        let paths = paths_and_positions
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect_vec();
 
         // When we have only one file to open, we do not mark it as we would most of the time want
         // a sneak-and-return usage. But, for multiple selections, since non-marked files don't
@@ -4664,6 +5246,56 @@ impl Editor {
             .and_then(|language| language.tree_sitter_grammar_id())
     }
 
+    pub(crate) fn display_large_file_mode(&self) -> Option<String> {
+        self.buffer().large_file_mode().then(|| "LARGE".to_string())
+    }
+
+    /// Returns the dispatches required to re-highlight the buffer
+    /// when large-file mode is turned off.
+    pub(crate) fn set_large_file_mode(&mut self, large_file_mode: bool) -> Dispatches {
+        self.buffer_mut().set_large_file_mode(large_file_mode);
+        self.get_document_did_change_dispatch()
+    }
+
+    pub(crate) fn display_indentation(&self, context: &Context) -> Option<String> {
+        self.buffer().path()?;
+        Some(match self.buffer().indentation(context) {
+            ('\t', 1) => "Tabs".to_string(),
+            ('\t', width) => format!("Tabs:{width}"),
+            (_, width) => format!("Spaces:{width}"),
+        })
+    }
+
+    pub(crate) fn display_read_only(&self) -> Option<String> {
+        self.buffer().read_only().then(|| "RO".to_string())
+    }
+
+    fn ensure_writable(&self) -> anyhow::Result<()> {
+        if self.buffer().read_only() {
+            Err(anyhow::anyhow!(
+                "This buffer is read-only, use \"Toggle Read-only\" to make it editable."
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Like `save`, but writes through `sudo dd` without running the formatter.
+    pub(crate) fn save_with_elevated_privileges(
+        &mut self,
+        context: &Context,
+    ) -> anyhow::Result<Dispatches> {
+        let (dispatches, path) = self
+            .buffer
+            .borrow_mut()
+            .save_with_elevated_privileges(context)?;
+        let Some(path) = path else {
+            return Ok(Dispatches::one(Dispatch::OpenSaveAsPrompt));
+        };
+        self.enter_normal_mode(context)?;
+        Ok(Dispatches::one(Dispatch::DocumentDidSave { path }).chain(dispatches))
+    }
+
     fn duplicate_with_movement(
         &mut self,
         context: &Context,
@@ -4773,8 +5405,11 @@ impl Editor {
     }
 
     fn insert_char(&mut self, context: &Context, c: char) -> Result<Dispatches, anyhow::Error> {
-        self.insert(
-            &c.to_string(),
+        let edit_transaction = self
+            .get_closing_token_edit_transaction(context, c)
+            .unwrap_or_else(|| self.get_insert_edit_transaction(&c.to_string()));
+        self.apply_edit_transaction_with_edit_history_kind(
+            edit_transaction,
             context,
             EditHistoryKind::Fine {
                 insert_session: self.insert_session.clone(),
@@ -4782,6 +5417,84 @@ impl Editor {
         )
     }
 
+    /// Typing a token that closes its enclosing node, such as `}`, on a line of its own dedents the line.
+    /// The dedent is part of the same edit as the insertion, so that one undo reverts both.
+    ///
+    /// Returns `None` unless every cursor types such a token.
+    fn get_closing_token_edit_transaction(
+        &self,
+        context: &Context,
+        c: char,
+    ) -> Option<EditTransaction> {
+        let buffer = self.buffer();
+        let treesitter_language = buffer.treesitter_language()?;
+        let candidates = self
+            .selection_set
+            .map(|selection| -> Option<_> {
+                if selection.virtual_space() > 0 {
+                    return None;
+                }
+                let cursor = selection.extended_range().start;
+                let line_index = buffer.char_to_line(cursor).ok()?;
+                let line_start = buffer.line_to_char(line_index).ok()?;
+                let before_cursor = buffer.slice(&(line_start..cursor).into()).ok()?.to_string();
+                let token = format!("{}{c}", before_cursor.trim_start());
+                // Only tokens of the grammar can close a node, which spares parsing the preview
+                // for most keystrokes
+                (!token.contains(char::is_whitespace)
+                    && treesitter_language.id_for_node_kind(&token, false) != 0)
+                    .then(|| (selection.clone(), line_index, line_start, cursor, token))
+            })
+            .into_iter()
+            .collect::<Option<Vec<_>>>()?;
+        if !candidates
+            .iter()
+            .map(|(_, line_index, ..)| line_index)
+            .all_unique()
+        {
+            return None;
+        }
+        let unit = self.indentation_unit(context);
+        let preview = buffer
+            .preview(&self.get_insert_edit_transaction(&c.to_string()))
+            .ok()?;
+        let action_groups = candidates
+            .iter()
+            .map(|(selection, _, line_start, cursor, token)| -> Option<_> {
+                // Every character inserted before this cursor shifts the token in the preview
+                let shift = candidates
+                    .iter()
+                    .filter(|(_, _, _, other, _)| other < cursor)
+                    .count();
+                let token_start = *cursor + shift + 1 - token.chars().count();
+                let byte = preview.char_to_byte(token_start).ok()?;
+                let syntax_indent = SyntaxIndent::new(&preview, byte..byte)?;
+                let Some(SyntaxIndentation::Closing { indentation, .. }) = syntax_indent
+                    .indentation(byte, &unit, |line_index| {
+                        syntax_indent.leading_whitespaces(line_index)
+                    })
+                else {
+                    return None;
+                };
+                let new_cursor = *line_start + indentation.chars().count() + token.chars().count();
+                Some(ActionGroup::new(
+                    [
+                        Action::Edit(Edit::new(
+                            buffer.rope(),
+                            (*line_start..*cursor).into(),
+                            format!("{indentation}{token}").into(),
+                        )),
+                        Action::Select(
+                            selection.clone().set_range((new_cursor..new_cursor).into()),
+                        ),
+                    ]
+                    .to_vec(),
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(EditTransaction::from_action_groups(action_groups))
+    }
+
     fn fine_undo(&mut self, context: &Context) -> Result<Dispatches, anyhow::Error> {
         Ok(self
             .undo(context, true)?
@@ -4797,6 +5510,62 @@ impl Editor {
     }
 }
 
+/// The number of characters of the given line, excluding its line break.
+fn line_length(line: &ropey::RopeSlice) -> usize {
+    line.chars()
+        .take_while(|c| !matches!(c, '\n' | '\r'))
+        .count()
+}
+
+/// Splits the `:line` or `:line:column` suffix, which are 1-based, off `path`.
+fn parse_path_with_position(path: &str) -> (&str, Option<Position>) {
+    let mut numbers = Vec::new();
+    let mut rest = path;
+    while numbers.len() < 2 {
+        match rest.rsplit_once(':') {
+            Some((head, number)) if !head.is_empty() => match number.parse::<usize>() {
+                Ok(number) => {
+                    numbers.push(number);
+                    rest = head
+                }
+                Err(_) => break,
+            },
+            _ => break,
+        }
+    }
+    let position = match numbers.as_slice() {
+        [line] => Some((*line, 1)),
+        [column, line] => Some((*line, *column)),
+        _ => None,
+    }
+    .map(|(line, column)| Position {
+        line: line.saturating_sub(1),
+        column: column.saturating_sub(1),
+    });
+    (rest, position)
+}
+
+/// Replaces the indentation after the last newline of `gap`, which is to be inserted at `char_index`,
+/// with the indentation computed from the syntax tree.
+fn syntax_indent_gap(buffer: &Buffer, char_index: CharIndex, gap: Rope, unit: &str) -> Rope {
+    let gap = gap.to_string();
+    let Some((head, tail)) = gap.rsplit_once('\n') else {
+        return gap.into();
+    };
+    if !tail.chars().all(|c| matches!(c, ' ' | '\t')) {
+        return gap.into();
+    }
+    let Some(indentation) = buffer.char_to_byte(char_index).ok().and_then(|byte| {
+        let syntax_indent = SyntaxIndent::new(buffer, byte..byte)?;
+        syntax_indent.indentation(byte, unit, |line_index| {
+            syntax_indent.leading_whitespaces(line_index)
+        })
+    }) else {
+        return gap.into();
+    };
+    format!("{head}\n{}", indentation.indentation()).into()
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
 pub enum ViewAlignment {
     Top,
@@ -4908,6 +5677,12 @@ pub enum DispatchEditor {
     ShowCurrentTreeSitterNodeSexp,
     Indent,
     Dedent,
+    Reindent,
+    GoToMatchingPair,
+    SelectBlock,
+    /// Overrides the indentation character and its count per level of the current buffer,
+    /// `None` restores the indentation from `.editorconfig` or the detected one.
+    SetIndentation(Option<(char, usize)>),
     SwapExtensionAnchor,
     FilterSelectionMatchingSearch {
         search: String,
@@ -4926,8 +5701,23 @@ pub enum DispatchEditor {
     },
     ToggleLineComment,
     ToggleBlockComment,
+    HardWrap,
+    /// Increments the first number, date, time or word ring member of each selection
+    /// by `amount`, see `transformation::Increment`.
+    Increment {
+        amount: i64,
+        progressive: bool,
+    },
+    /// Shows the count, sum, average, minimum and maximum of the values of the selections.
+    SummarizeSelections,
     RepeatSearch(Scope, IfCurrentNotFound, Option<PriorChange>),
     RevertHunk(DiffMode),
+    /// Replaces the merge conflicts that intersect with the selections,
+    /// or every merge conflict of the buffer if `all` is true.
+    ResolveMergeConflict {
+        resolution: MergeConflictResolution,
+        all: bool,
+    },
     GitBlame,
     ReloadFile {
         force: bool,
@@ -232,6 +232,7 @@ pub enum PromptHistoryKey {
     Null,
     Theme,
     PipeToShell,
+    SortByRegex,
     FilterSelectionsMatchingSearch {
         maintain: bool,
     },
@@ -241,6 +242,7 @@ pub enum PromptHistoryKey {
     WorkspaceSymbol,
     ChangeWorkingDirectory,
     SaveAs,
+    GlobalSearchScope,
 }
 
 impl Prompt {
@@ -814,6 +816,7 @@ mod test_prompt {
                             escaped: false,
                             case_sensitive: false,
                             match_whole_word: false,
+                            multi_line: false,
                         }),
                     ),
                     scope: Scope::Local,
@@ -906,7 +906,22 @@ impl Editor {
             }),
         ));
 
-        let content = buffer.rope().to_string();
+        // In large-file mode, only the visible lines are materialized for the regex-based highlights
+        let (content, content_byte_offset) = if buffer.large_file_mode() {
+            let byte_range = buffer
+                .line_range_to_byte_range(visible_line_range)
+                .unwrap_or_default();
+            (
+                buffer
+                    .rope()
+                    .get_byte_slice(byte_range.clone())
+                    .map(|slice| slice.to_string())
+                    .unwrap_or_default(),
+                byte_range.start,
+            )
+        } else {
+            (buffer.rope().to_string(), 0)
+        };
 
         let diagnostics = buffer.diagnostics();
         let diagnostics = diagnostics
@@ -927,6 +942,29 @@ impl Editor {
                 is_protected_range_start: false,
             });
 
+        // `content` is the whole content unless in large-file mode
+        let merge_conflicts = if buffer.large_file_mode() {
+            crate::git::merge_conflict::merge_conflicts(&content)
+        } else {
+            buffer.merge_conflicts().to_vec()
+        };
+        let merge_conflict_markers = merge_conflicts
+            .into_iter()
+            .flat_map(|conflict| conflict.markers().to_vec())
+            .map(|range| {
+                let marker = content[range.clone()].trim_end();
+                HighlightSpan {
+                    set_symbol: None,
+                    is_cursor: false,
+                    range: HighlightSpanRange::ByteRange(
+                        range.start + content_byte_offset
+                            ..range.start + marker.len() + content_byte_offset,
+                    ),
+                    source: Source::StyleKey(StyleKey::MergeConflictMarker),
+                    is_protected_range_start: false,
+                }
+            });
+
         let jumps = self.jumps().iter().enumerate().map(|(index, jump)| {
             let style = if index % 2 == 0 {
                 theme.ui.jump_mark_even
@@ -988,7 +1026,12 @@ impl Editor {
         };
         let custom_regex_highlights = lazy_regex::regex!("(?i)#[0-9a-f]{6}")
             .find_iter(&content)
-            .map(|m| (m.as_str().to_string(), m.range()))
+            .map(|m| {
+                (
+                    m.as_str().to_string(),
+                    m.start() + content_byte_offset..m.end() + content_byte_offset,
+                )
+            })
             .filter_map(|(hex, range)| {
                 let color = crate::themes::Color::from_hex(&hex).ok()?;
                 Some(HighlightSpan {
@@ -1013,7 +1056,10 @@ impl Editor {
                     let match_ = captures.name(name)?;
                     Some(HighlightSpan {
                         source,
-                        range: HighlightSpanRange::ByteRange(match_.range()),
+                        range: HighlightSpanRange::ByteRange(
+                            match_.start() + content_byte_offset
+                                ..match_.end() + content_byte_offset,
+                        ),
                         set_symbol: None,
                         is_cursor: false,
                         is_protected_range_start: false,
@@ -1055,6 +1101,7 @@ impl Editor {
             .into_iter()
             .chain(visible_parent_lines)
             .chain(filtered_highlighted_spans)
+            .chain(merge_conflict_markers)
             .chain(possible_selections)
             .chain(primary_selection_highlight_span)
             .chain(secondary_selections_highlight_spans)
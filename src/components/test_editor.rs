@@ -8,19 +8,21 @@ use crate::components::editor::{
     Movement::*,
     PriorChange,
 };
-use crate::context::{Context, GlobalMode, LocalSearchConfigMode, Search};
-use crate::git::DiffMode;
+use crate::context::{Context, FuzzyCandidate, GlobalMode, LocalSearchConfigMode, Search};
+use crate::git::{merge_conflict::MergeConflictResolution, DiffMode};
 use crate::grid::IndexedHighlightGroup;
 use crate::list::grep::RegexConfig;
 use crate::lsp::process::LspNotification;
 use crate::quickfix_list::{Location, QuickfixListItem};
 use crate::rectangle::Rectangle;
 use crate::selection::CharIndex;
-use crate::selection_mode::GetGapMovement;
+use crate::selection_mode::{GetGapMovement, TextobjectKind};
 use crate::style::Style;
 use crate::test_app::*;
 
+use crate::conversion::Conversion;
 use crate::themes::GitGutterStyles;
+use crate::transformation::{Reorder, SortKey, Transformation};
 use crate::ui_tree::ComponentKind;
 use crate::{
     components::editor::{Direction, Mode, ViewAlignment},
@@ -2488,6 +2490,7 @@ fn next_prev_after_current_selection_is_deleted() -> anyhow::Result<()> {
                                 escaped: false,
                                 case_sensitive: false,
                                 match_whole_word: false,
+                                multi_line: false,
                             }),
                             search: r"\d+".to_string(),
                         },
@@ -2629,6 +2632,7 @@ fn main() {
                     escaped: true,
                     case_sensitive: false,
                     match_whole_word: false,
+                    multi_line: false,
                 }),
             },
         },
@@ -2746,7 +2750,7 @@ fn select_surround_inside_same_symbols() -> Result<(), anyhow::Error> {
                 owner: BufferOwner::User,
                 focus: true,
             }),
-            Editor(SetContent("hello 'world'".to_string())),
+            Editor(SetContent(r#"let x = "hello 'world'";"#.to_string())),
             Editor(MatchLiteral("rl".to_string())),
             Editor(DeleteSurround(crate::surround::EnclosureKind::SingleQuotes)),
             Expect(CurrentSelectedTexts(&["world"])),
@@ -2867,6 +2871,7 @@ fn replace_with_pattern() -> Result<(), anyhow::Error> {
             escaped: false,
             case_sensitive: false,
             match_whole_word: false,
+            multi_line: false,
         }),
         "ali_123 abu_456 adam_99",
         r"abu_(\d+)",
@@ -2885,6 +2890,107 @@ fn replace_with_pattern() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test]
+fn reorder_selections() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("c10 a b10 b9".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, BigWord)),
+            Editor(CursorAddToAllSelections),
+            Editor(Transform(Transformation::Reorder(Reorder::Sort(
+                SortKey::Natural,
+            )))),
+            Expect(CurrentComponentContent("a b9 b10 c10")),
+            Expect(CurrentSelectedTexts(&["a", "b9", "b10", "c10"])),
+            Editor(Transform(Transformation::Reorder(Reorder::Reverse))),
+            Expect(CurrentComponentContent("c10 b10 b9 a")),
+        ])
+    })
+}
+
+#[test]
+fn failed_conversion_leaves_other_selections_converted() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("aGk= ??? aGk=".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, BigWord)),
+            Editor(CursorAddToAllSelections),
+            Editor(Transform(Transformation::Convert(Conversion::Base64Decode))),
+            Expect(CurrentComponentContent("hi ??? hi")),
+            Expect(CurrentSelectedTexts(&["hi", "???", "hi"])),
+            Expect(ExpectKind::GlobalInfo(
+                "Selection 2: Invalid symbol 63, offset 0.".to_string(),
+            )),
+        ])
+    })
+}
+
+#[test]
+fn evaluate_and_summarize_selections() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("1+2 0x10 x 2.5".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, BigWord)),
+            Editor(CursorAddToAllSelections),
+            Editor(SummarizeSelections),
+            Expect(EditorInfoContents(&[
+                "Count: 3\nSum: 21.5\nAverage: 7.166666666666667\nMin: 2.5\nMax: 16\nSkipped: 1 (not a number)",
+            ])),
+            Editor(Transform(Transformation::Evaluate {
+                keep_expression: false,
+            })),
+            Expect(CurrentComponentContent("3 16 x 2.5")),
+        ])
+    })
+}
+
+#[test]
+fn fuzzy_search_selects_best_match_first() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("bar\n  f_o_o\nfoo".to_string())),
+            App(UpdateLocalSearchConfig {
+                update: LocalSearchConfigUpdate::Mode(LocalSearchConfigMode::Fuzzy(
+                    FuzzyCandidate::Line,
+                )),
+                scope: Scope::Local,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: true,
+            }),
+            App(UpdateLocalSearchConfig {
+                update: LocalSearchConfigUpdate::Search("foo".to_string()),
+                scope: Scope::Local,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: true,
+            }),
+            Expect(CurrentSelectedTexts(&["foo"])),
+            Editor(CursorAddToAllSelections),
+            // The selections are ordered from the best match to the worst
+            Expect(CurrentSelectedTexts(&["foo", "f_o_o"])),
+        ])
+    })
+}
+
 #[test]
 fn replace_extended_selection_should_not_derail_selection_range() -> Result<(), anyhow::Error> {
     execute_test(|s| {
@@ -3605,6 +3711,7 @@ foov foou bar
                         escaped: true,
                         case_sensitive: false,
                         match_whole_word: false,
+                        multi_line: false,
                     }),
                     search: "foo".to_string(),
                 },
@@ -4777,6 +4884,7 @@ java script"
                             escaped: false,
                             case_sensitive: false,
                             match_whole_word: false,
+                            multi_line: false,
                         }),
                         search: "[a-zA-Z]+".to_string(),
                     },
@@ -6545,3 +6653,328 @@ fn coarse_undo_redo_should_not_group_insertions_iniated_in_different_insert_sess
         ])
     })
 }
+
+#[test]
+fn textobject_selection_modes_work_with_movements() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn f(x: A, y: B) {}\nfn g() {}".to_string())),
+            Editor(MatchLiteral("x".to_string())),
+            Editor(SetSelectionMode(
+                IfCurrentNotFound::LookForward,
+                Textobject {
+                    kind: TextobjectKind::Parameter,
+                    inside: true,
+                },
+            )),
+            Expect(CurrentSelectedTexts(&["x: A"])),
+            Editor(SwapWithMovement(Right)),
+            Expect(CurrentComponentContent("fn f(y: B, x: A) {}\nfn g() {}")),
+            Expect(CurrentSelectedTexts(&["x: A"])),
+            Editor(SetSelectionMode(
+                IfCurrentNotFound::LookForward,
+                Textobject {
+                    kind: TextobjectKind::Function,
+                    inside: false,
+                },
+            )),
+            Expect(CurrentSelectedTexts(&["fn f(y: B, x: A) {}"])),
+            Editor(MoveSelection(Right)),
+            Expect(CurrentSelectedTexts(&["fn g() {}"])),
+            Editor(MoveSelection(Left)),
+            Editor(DeleteWithMovement(Right)),
+            Expect(CurrentComponentContent("fn g() {}")),
+            Expect(CurrentSelectedTexts(&["fn g() {}"])),
+        ])
+    })
+}
+
+#[test]
+fn syntax_aware_indentation() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn main() {}".to_string())),
+            Editor(MatchLiteral("}".to_string())),
+            Editor(EnterInsertMode(Direction::Start)),
+            Editor(EnterNewline),
+            Expect(CurrentComponentContent("fn main() {\n    \n}")),
+            Editor(Insert("foo(".to_string())),
+            Editor(EnterNewline),
+            Editor(Insert("x,".to_string())),
+            Editor(EnterNewline),
+            Expect(CurrentComponentContent(
+                "fn main() {\n    foo(\n        x,\n        \n}",
+            )),
+            // Typing a closing token dedents the line
+            Editor(InsertChar(')')),
+            Expect(CurrentComponentContent(
+                "fn main() {\n    foo(\n        x,\n    )\n}",
+            )),
+            // One undo reverts both the typed token and the dedent
+            Editor(FineUndo),
+            Expect(CurrentComponentContent(
+                "fn main() {\n    foo(\n        x,\n        \n}",
+            )),
+            Editor(EnterNormalMode),
+            Editor(SetContent("fn main() {\nfoo(\nx,\n);\n}".to_string())),
+            Editor(MatchLiteral("fn".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, SyntaxNode)),
+            Editor(Reindent),
+            Expect(CurrentComponentContent(
+                "fn main() {\n    foo(\n        x,\n    );\n}",
+            )),
+            Expect(CurrentSelectedTexts(&[
+                "fn main() {\n    foo(\n        x,\n    );\n}",
+            ])),
+        ])
+    })
+}
+
+#[test]
+fn syntax_aware_indentation_after_colon_in_python() -> anyhow::Result<()> {
+    execute_test(|s| {
+        let path = s.new_path("main.py");
+        Box::new([
+            App(AddPath(path.display().to_string())),
+            App(OpenFileFromPathBuf {
+                path,
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("def f():\n    pass".to_string())),
+            Editor(MatchLiteral(":".to_string())),
+            Editor(EnterInsertMode(Direction::End)),
+            Editor(EnterNewline),
+            Expect(CurrentComponentContent("def f():\n    \n    pass")),
+        ])
+    })
+}
+
+#[test]
+fn surround_ignores_symbols_in_strings() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent(r#"fn main() { f("(", x) }"#.to_string())),
+            Editor(MatchLiteral("x".to_string())),
+            Editor(ChangeSurround {
+                from: crate::surround::EnclosureKind::Parentheses,
+                to: crate::surround::EnclosureKind::SquareBrackets,
+            }),
+            Expect(CurrentComponentContent(r#"fn main() { f["(", x] }"#)),
+            Expect(CurrentSelectedTexts(&[r#"["(", x]"#])),
+            Editor(SetContent(r#"fn main() { f("(", x) }"#.to_string())),
+            Editor(MatchLiteral("x".to_string())),
+            Editor(DeleteSurround(crate::surround::EnclosureKind::Parentheses)),
+            Expect(CurrentComponentContent(r#"fn main() { f"(", x }"#)),
+            Expect(CurrentSelectedTexts(&[r#""(", x"#])),
+        ])
+    })
+}
+
+#[test]
+fn go_to_matching_pair() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn main() { g(x) }".to_string())),
+            Editor(MatchLiteral("{".to_string())),
+            App(HandleKeyEvents(keys!(", g").to_vec())),
+            Expect(CurrentSelectedTexts(&["}"])),
+            Expect(CurrentSelectionMode(SelectionMode::Custom)),
+            Editor(GoToMatchingPair),
+            Expect(CurrentSelectedTexts(&["{"])),
+        ])
+    })
+}
+
+#[test]
+fn block_selection() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("abcd\na\nabcdef".to_string())),
+            Editor(MatchLiteral("cd\na\nabcd".to_string())),
+            Editor(SelectBlock),
+            Expect(CurrentSelectionMode(SelectionMode::Block)),
+            Expect(CurrentSelectedTexts(&["cd", "", "cd"])),
+            Editor(Copy),
+            // Short lines are padded up to the column of the block
+            Editor(EnterInsertMode(Direction::Start)),
+            Editor(Insert("X".to_string())),
+            Expect(CurrentComponentContent("abXcd\na X\nabXcdef")),
+            Editor(EnterNormalMode),
+            // The copied block is pasted as a rectangle
+            Editor(SetContent("1\n2".to_string())),
+            Editor(MatchLiteral("1".to_string())),
+            Editor(PasteWithMovement(GetGapMovement::AfterWithoutGap)),
+            Expect(CurrentComponentContent("1cd\n2\n cd")),
+            Expect(CurrentSelectedTexts(&["cd", "", "cd"])),
+        ])
+    })
+}
+
+#[test]
+fn increment_progressively() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("x0 x0 x0".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, Word)),
+            Editor(CursorAddToAllSelections),
+            Editor(Increment {
+                amount: 1,
+                progressive: true,
+            }),
+            Expect(CurrentComponentContent("x1 x2 x3")),
+            Editor(Increment {
+                amount: -1,
+                progressive: false,
+            }),
+            Expect(CurrentComponentContent("x0 x1 x2")),
+        ])
+    })
+}
+
+#[test]
+fn resolve_merge_conflicts() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent(
+                "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> x\nd\n<<<<<<< HEAD\ne\n=======\nf\n>>>>>>> x\n"
+                    .to_string(),
+            )),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, MergeConflict)),
+            Expect(CurrentSelectedTexts(&["<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> x"])),
+            Editor(ResolveMergeConflict {
+                resolution: MergeConflictResolution::Theirs,
+                all: false,
+            }),
+            Expect(CurrentComponentContent(
+                "a\nc\nd\n<<<<<<< HEAD\ne\n=======\nf\n>>>>>>> x\n",
+            )),
+            Editor(ResolveMergeConflict {
+                resolution: MergeConflictResolution::Both,
+                all: true,
+            }),
+            Expect(CurrentComponentContent("a\nc\nd\ne\nf\n")),
+        ])
+    })
+}
+
+#[test]
+fn sentence_selection_mode_in_comments() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent(
+                "// See e.g. the docs. They are\n// good.\nfn main() {}".to_string(),
+            )),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, Sentence)),
+            Expect(CurrentSelectedTexts(&["See e.g. the docs."])),
+            Editor(MoveSelection(Right)),
+            Expect(CurrentSelectedTexts(&["They are\n// good."])),
+        ])
+    })
+}
+
+#[test]
+fn syntax_node_and_line_comment_use_injected_language() -> anyhow::Result<()> {
+    execute_test(|s| {
+        let path: shared::absolute_path::AbsolutePath = s.new_path("readme.md").try_into().unwrap();
+        Box::new([
+            Shell("touch", [path.display_absolute()].to_vec()),
+            App(OpenFile {
+                path: path.clone(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent(
+                "# Title\n\n```rust\nlet x = f(a, b);\n```\n".to_string(),
+            )),
+            Editor(MatchLiteral("a".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, SyntaxNode)),
+            Expect(CurrentSelectedTexts(&["a"])),
+            Editor(MoveSelection(Right)),
+            Expect(CurrentSelectedTexts(&["b"])),
+            Editor(ToggleLineComment),
+            Expect(CurrentComponentContent(
+                "# Title\n\n```rust\n// let x = f(a, b);\n```\n",
+            )),
+        ])
+    })
+}
+
+#[test]
+fn indentation_block_movements() -> anyhow::Result<()> {
+    execute_test(|s| {
+        let path: shared::absolute_path::AbsolutePath =
+            s.new_path("outline.txt").try_into().unwrap();
+        Box::new([
+            Shell("touch", [path.display_absolute()].to_vec()),
+            App(OpenFile {
+                path: path.clone(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent(
+                "fruits\n  apple\n    red\n  banana\nveggies\n".to_string(),
+            )),
+            Editor(MatchLiteral("apple".to_string())),
+            Editor(SetSelectionMode(
+                IfCurrentNotFound::LookForward,
+                IndentationBlock,
+            )),
+            Expect(CurrentSelectedTexts(&["apple\n    red"])),
+            Editor(MoveSelection(Down)),
+            Expect(CurrentSelectedTexts(&["banana"])),
+            Editor(MoveSelection(Up)),
+            Expect(CurrentSelectedTexts(&["apple\n    red"])),
+            Editor(MoveSelection(Expand)),
+            Expect(CurrentSelectedTexts(&[
+                "fruits\n  apple\n    red\n  banana",
+            ])),
+            Editor(MoveSelection(Down)),
+            Expect(CurrentSelectedTexts(&["veggies"])),
+            Editor(MatchLiteral("red".to_string())),
+            Editor(SetSelectionMode(IfCurrentNotFound::LookForward, Word)),
+            Editor(MoveSelection(ParentLine)),
+            Expect(CurrentSelectedTexts(&["apple"])),
+        ])
+    })
+}
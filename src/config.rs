@@ -28,6 +28,11 @@ pub struct AppConfig {
     indent_width: usize,
     show_key_in_keymap: bool,
     icon_config: shared::icons::IconsConfig,
+    large_file_size_threshold: usize,
+    large_file_line_length_threshold: usize,
+    auto_save: AutoSaveConfig,
+    word_rings: Vec<Vec<String>>,
+    global_search_result_limit: usize,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema)]
@@ -45,6 +50,30 @@ pub struct RawConfig {
     show_key_in_keymap: bool,
     #[serde(default)]
     icon_style: shared::icons::IconStyle,
+    /// Files larger than this (in bytes) are opened in large-file mode,
+    /// which disables tree-sitter, LSP, git hunks and the word index.
+    large_file_size_threshold: usize,
+    /// Files containing a line longer than this (in characters)
+    /// are also opened in large-file mode.
+    large_file_line_length_threshold: usize,
+    auto_save: AutoSaveConfig,
+    /// Groups of words that `Increment`/`Decrement` cycle through,
+    /// such as `["true", "false"]`.
+    word_rings: Vec<Vec<String>>,
+    /// Global search stops after finding this many matches.
+    global_search_result_limit: usize,
+}
+
+/// Saves dirty buffers automatically after a period of inactivity,
+/// before switching files, and when the terminal loses focus.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AutoSaveConfig {
+    pub enabled: bool,
+    /// How long (in milliseconds) to wait after the last input before saving.
+    pub idle_delay_ms: u64,
+    /// Whether to run the language formatter before saving.
+    pub run_formatter: bool,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema)]
@@ -99,6 +128,11 @@ impl TryFrom<RawConfig> for AppConfig {
             indent_width: value.indent_width,
             show_key_in_keymap: value.show_key_in_keymap,
             icon_config: shared::icons::build_icon_config(&value.icon_style),
+            large_file_size_threshold: value.large_file_size_threshold,
+            large_file_line_length_threshold: value.large_file_line_length_threshold,
+            auto_save: value.auto_save,
+            word_rings: value.word_rings,
+            global_search_result_limit: value.global_search_result_limit,
         })
     }
 }
@@ -268,6 +302,26 @@ impl AppConfig {
     pub fn icon_config(&self) -> &shared::icons::IconsConfig {
         &self.icon_config
     }
+
+    pub fn large_file_size_threshold(&self) -> usize {
+        self.large_file_size_threshold
+    }
+
+    pub fn large_file_line_length_threshold(&self) -> usize {
+        self.large_file_line_length_threshold
+    }
+
+    pub fn auto_save(&self) -> &AutoSaveConfig {
+        &self.auto_save
+    }
+
+    pub fn word_rings(&self) -> &Vec<Vec<String>> {
+        &self.word_rings
+    }
+
+    pub fn global_search_result_limit(&self) -> usize {
+        self.global_search_result_limit
+    }
 }
 
 pub fn from_path(path: &AbsolutePath) -> Option<Language> {
@@ -284,6 +338,25 @@ pub fn from_extension(extension: &str) -> Option<Language> {
         .map(|(_, language)| (*language).clone())
 }
 
+/// Find the language of an injection, such as the info string of a Markdown code fence,
+/// which can be the name of a language (`rust`), its LSP ID or one of its extensions (`rs`).
+pub fn from_injection_name(name: &str) -> Option<Language> {
+    let name = name.trim().to_lowercase();
+    let languages = AppConfig::singleton().languages();
+    languages
+        .get(&name)
+        .or_else(|| {
+            languages.values().find(|language| {
+                language
+                    .lsp_language_id()
+                    .as_ref()
+                    .is_some_and(|id| id.to_string() == name)
+            })
+        })
+        .cloned()
+        .or_else(|| from_extension(&name))
+}
+
 pub fn from_filename(path: &AbsolutePath) -> Option<Language> {
     let file_name = path.file_name()?;
     AppConfig::singleton()
@@ -7,11 +7,13 @@ use itertools::{Either, Itertools};
 use shared::absolute_path::AbsolutePath;
 
 use crate::{
-    app::{GlobalSearchConfigUpdate, LocalSearchConfigUpdate, Scope},
+    app::{GlobalSearchConfigUpdate, GlobalSearchScopeUpdate, LocalSearchConfigUpdate, Scope},
+    ast_grep_rule::AstGrepRule,
     char_index_range::CharIndexRange,
     clipboard::{Clipboard, RingHistory, Texts},
     components::{editor_keymap::KeyboardLayout, prompt::PromptHistoryKey},
-    list::grep::RegexConfig,
+    config::AutoSaveConfig,
+    list::{grep::RegexConfig, replace_preview::ReplacePreview},
     persistence::{Persistence, WorkspaceSession},
     quickfix_list::{DiagnosticSeverityRange, Location, QuickfixList, QuickfixListItem},
     selection::SelectionMode,
@@ -28,7 +30,10 @@ pub struct Context {
     current_working_directory: AbsolutePath,
     local_search_config: LocalSearchConfig,
     global_search_config: GlobalSearchConfig,
+    global_search_scope: GlobalSearchScope,
+    global_search_scope_presets: HashMap<String, GlobalSearchScope>,
     quickfix_list: QuickfixList,
+    replace_preview: Option<ReplacePreview>,
     prompt_histories: HashMap<PromptHistoryKey, IndexSet<String>>,
     last_non_contiguous_selection_mode: Option<Either<SelectionMode, GlobalMode>>,
     keyboard_layout: KeyboardLayout,
@@ -51,6 +56,9 @@ pub struct Context {
     file_dirty_status: HashMap<AbsolutePath, bool>,
     indent_char: char,
     indent_width: usize,
+    auto_save: AutoSaveConfig,
+    word_rings: Vec<Vec<String>>,
+    ast_grep_rules: Vec<AstGrepRule>,
 
     /// This is used to prevent the same non-existent formatter error from being reported more than once.
     non_existent_formatter_commands: Vec<FormatterCommand>,
@@ -107,6 +115,11 @@ impl Context {
                         .map(|(path, marks)| (path.to_path_buf().clone(), marks.clone()))
                         .collect(),
                     prompt_histories: self.prompt_histories.clone(),
+                    global_search_scope_presets: self
+                        .global_search_scope_presets
+                        .iter()
+                        .map(|(name, scope)| (name.clone(), scope.display()))
+                        .collect(),
                 },
             );
 
@@ -233,6 +246,18 @@ impl Context {
         self.quickfix_list.set_kind(kind);
     }
 
+    pub fn replace_preview_mut(&mut self) -> Option<&mut ReplacePreview> {
+        self.replace_preview.as_mut()
+    }
+
+    pub fn set_replace_preview(&mut self, replace_preview: ReplacePreview) {
+        self.replace_preview = Some(replace_preview);
+    }
+
+    pub fn take_replace_preview(&mut self) -> Option<ReplacePreview> {
+        self.replace_preview.take()
+    }
+
     pub fn set_quickfix_list_title(&mut self, title: &str) {
         self.quickfix_list.set_title(title);
     }
@@ -258,6 +283,28 @@ impl Context {
         self.indent_char
     }
 
+    pub(crate) fn auto_save(&self) -> &AutoSaveConfig {
+        &self.auto_save
+    }
+
+    pub(crate) fn word_rings(&self) -> &Vec<Vec<String>> {
+        &self.word_rings
+    }
+
+    pub(crate) fn ast_grep_rules(&self) -> &[AstGrepRule] {
+        &self.ast_grep_rules
+    }
+
+    pub(crate) fn reload_ast_grep_rules(&mut self) -> anyhow::Result<()> {
+        self.ast_grep_rules = crate::ast_grep_rule::load(&self.current_working_directory)?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_auto_save(&mut self, auto_save: AutoSaveConfig) {
+        self.auto_save = auto_save
+    }
+
     /// Returns `true` if the given `formatter_command` is not seen before.
     pub(crate) fn add_formatter_not_exist_error(
         &mut self,
@@ -278,6 +325,7 @@ impl Context {
 
 pub enum QuickfixListKind {
     Mark,
+    ReplacePreview,
 }
 
 impl Context {
@@ -318,6 +366,26 @@ impl Context {
                 persistence.get_prompt_histories(current_working_directory.to_path_buf())
             })
             .unwrap_or_default();
+
+        let global_search_scope_presets = persistence
+            .as_ref()
+            .and_then(|persistence| {
+                Some(
+                    persistence
+                        .get_global_search_scope_presets(current_working_directory.to_path_buf())?
+                        .into_iter()
+                        .filter_map(|(name, scope)| {
+                            Some((name, crate::search::parse_search_scope(&scope).ok()?))
+                        })
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+        let ast_grep_rules =
+            crate::ast_grep_rule::load(&current_working_directory).unwrap_or_else(|error| {
+                log::error!("{error:?}");
+                Vec::new()
+            });
         let app_config = crate::config::AppConfig::singleton();
         Self {
             clipboard: Clipboard::new(),
@@ -328,6 +396,8 @@ impl Context {
             current_working_directory,
             local_search_config: LocalSearchConfig::default(),
             global_search_config: GlobalSearchConfig::default(),
+            global_search_scope: GlobalSearchScope::default(),
+            global_search_scope_presets,
             prompt_histories,
             last_non_contiguous_selection_mode: None,
             keyboard_layout: app_config.keyboard_layout().clone(),
@@ -340,10 +410,14 @@ impl Context {
             marks,
             lsp_progress: "".to_string(),
             quickfix_list: QuickfixList::default(),
+            replace_preview: None,
             kill_ring: RingHistory::new(),
             file_dirty_status: HashMap::new(),
             indent_char: app_config.indent_char(),
             indent_width: app_config.indent_width(),
+            auto_save: app_config.auto_save().clone(),
+            word_rings: app_config.word_rings().clone(),
+            ast_grep_rules,
             non_existent_formatter_commands: Vec::new(),
         }
     }
@@ -468,6 +542,37 @@ impl Context {
         Ok(())
     }
 
+    pub fn global_search_scope(&self) -> &GlobalSearchScope {
+        &self.global_search_scope
+    }
+
+    pub fn global_search_scope_presets(&self) -> &HashMap<String, GlobalSearchScope> {
+        &self.global_search_scope_presets
+    }
+
+    pub fn update_global_search_scope(
+        &mut self,
+        update: GlobalSearchScopeUpdate,
+    ) -> anyhow::Result<()> {
+        match update {
+            GlobalSearchScopeUpdate::Scope(scope) => self.global_search_scope = scope,
+            GlobalSearchScopeUpdate::LoadPreset(name) => {
+                self.global_search_scope = self
+                    .global_search_scope_presets
+                    .get(&name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("There is no search scope preset named {name:?}.")
+                    })?
+                    .clone()
+            }
+            GlobalSearchScopeUpdate::SaveAsPreset { name, scope } => {
+                self.global_search_scope_presets.insert(name, scope.clone());
+                self.global_search_scope = scope;
+            }
+        };
+        Ok(())
+    }
+
     pub fn local_search_config(&self, scope: Scope) -> &LocalSearchConfig {
         match scope {
             Scope::Local => &self.local_search_config,
@@ -599,8 +704,10 @@ impl Context {
 
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct GlobalSearchConfig {
-    pub include_glob: Option<Glob>,
-    pub exclude_glob: Option<Glob>,
+    /// A file is searched if it matches any of these, or if there are none.
+    pub include_globs: Vec<Glob>,
+    /// A file is not searched if it matches any of these.
+    pub exclude_globs: Vec<Glob>,
     pub local_config: LocalSearchConfig,
 }
 impl GlobalSearchConfig {
@@ -608,12 +715,84 @@ impl GlobalSearchConfig {
         &self.local_config
     }
 
-    pub fn include_glob(&self) -> Option<Glob> {
-        self.include_glob.clone()
+    pub fn include_globs(&self) -> Vec<Glob> {
+        self.include_globs.clone()
     }
 
-    pub fn exclude_glob(&self) -> Option<Glob> {
-        self.exclude_glob.clone()
+    pub fn exclude_globs(&self) -> Vec<Glob> {
+        self.exclude_globs.clone()
+    }
+}
+
+/// The files that are visited by global search and replace, on top of the globs
+/// of `GlobalSearchConfig`. Unlike the globs, it is kept across searches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlobalSearchScope {
+    pub include_hidden: bool,
+    /// Whether files ignored by `.gitignore` (and `.ignore`) are included.
+    pub include_ignored: bool,
+    /// Names or extensions of the languages of the files to be searched, such as `rust` or `ts`.
+    pub file_types: Vec<String>,
+    pub limit: GlobalSearchLimit,
+}
+
+impl Default for GlobalSearchScope {
+    fn default() -> Self {
+        Self {
+            include_hidden: true,
+            include_ignored: false,
+            file_types: Vec::new(),
+            limit: GlobalSearchLimit::default(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlobalSearchLimit {
+    #[default]
+    WorkingDirectory,
+    /// The directory of the current file.
+    CurrentDirectory,
+    MarkedFiles,
+}
+
+impl GlobalSearchScope {
+    /// Displays the settings that differ from the default in the syntax of
+    /// `parse_search_scope`, thus the default scope is displayed as an empty string.
+    pub fn display(&self) -> String {
+        [
+            (!self.include_hidden).then(|| "-hidden".to_string()),
+            self.include_ignored.then(|| "+ignored".to_string()),
+            (!self.file_types.is_empty()).then(|| format!("type:{}", self.file_types.join(","))),
+            match self.limit {
+                GlobalSearchLimit::WorkingDirectory => None,
+                GlobalSearchLimit::CurrentDirectory => Some("dir".to_string()),
+                GlobalSearchLimit::MarkedFiles => Some("marked".to_string()),
+            },
+        ]
+        .into_iter()
+        .flatten()
+        .join(" ")
+    }
+
+    /// The globs of the file names of `file_types`, which are looked up
+    /// from the configured languages.
+    pub fn file_name_globs(&self) -> anyhow::Result<Vec<Glob>> {
+        self.file_types
+            .iter()
+            .map(|file_type| {
+                let language = crate::config::from_injection_name(file_type)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown file type: {file_type:?}"))?;
+                language
+                    .extensions()
+                    .iter()
+                    .map(|extension| format!("*.{extension}"))
+                    .chain(language.file_names().iter().cloned())
+                    .map(|glob| Ok(Glob::new(&glob)?))
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .flatten_ok()
+            .collect()
     }
 }
 
@@ -622,6 +801,15 @@ pub enum LocalSearchConfigMode {
     Regex(RegexConfig),
     AstGrep,
     NamingConventionAgnostic,
+    Fuzzy(FuzzyCandidate),
+}
+
+/// What a fuzzy search matches against.
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+pub enum FuzzyCandidate {
+    Line,
+    /// Named syntax nodes that span a single line.
+    SyntaxNode,
 }
 impl LocalSearchConfigMode {
     pub fn display(&self) -> String {
@@ -632,6 +820,10 @@ impl LocalSearchConfigMode {
             LocalSearchConfigMode::NamingConventionAgnostic => {
                 "Naming Convention Agnostic".to_string()
             }
+            LocalSearchConfigMode::Fuzzy(FuzzyCandidate::Line) => "Fuzzy Line".to_string(),
+            LocalSearchConfigMode::Fuzzy(FuzzyCandidate::SyntaxNode) => {
+                "Fuzzy Syntax Node".to_string()
+            }
         }
     }
 }
@@ -645,7 +837,7 @@ impl Default for LocalSearchConfigMode {
 impl RegexConfig {
     fn display(&self) -> String {
         format!(
-            "{}{}{}",
+            "{}{}{}{}",
             if self.escaped { "Literal" } else { "Regex" },
             if self.case_sensitive {
                 " A=a".to_string()
@@ -656,6 +848,11 @@ impl RegexConfig {
                 " [Ab]".to_string()
             } else {
                 String::new()
+            },
+            if self.multi_line {
+                " \\n".to_string()
+            } else {
+                String::new()
             }
         )
     }
@@ -719,7 +916,11 @@ impl LocalSearchConfig {
     }
 
     pub fn require_tree_sitter(&self) -> bool {
-        self.mode == LocalSearchConfigMode::AstGrep
+        matches!(
+            self.mode,
+            LocalSearchConfigMode::AstGrep
+                | LocalSearchConfigMode::Fuzzy(FuzzyCandidate::SyntaxNode)
+        )
     }
 }
 
@@ -732,8 +933,9 @@ mod test_context {
     use shared::absolute_path::AbsolutePath;
 
     use crate::{
-        char_index_range::CharIndexRange, components::prompt::PromptHistoryKey, context::Context,
-        persistence::Persistence, selection::CharIndex,
+        app::GlobalSearchScopeUpdate, char_index_range::CharIndexRange,
+        components::prompt::PromptHistoryKey, context::Context, persistence::Persistence,
+        selection::CharIndex,
     };
 
     #[test]
@@ -760,6 +962,10 @@ mod test_context {
             context
                 .prompt_histories
                 .insert(PromptHistoryKey::Theme, index_set);
+            context.update_global_search_scope(GlobalSearchScopeUpdate::SaveAsPreset {
+                name: "rust".to_string(),
+                scope: crate::search::parse_search_scope("type:rust -hidden")?,
+            })?;
             context.persist_data();
         }
 
@@ -792,6 +998,14 @@ mod test_context {
                     .len(),
                 1
             );
+
+            assert_eq!(
+                context
+                    .global_search_scope_presets
+                    .get("rust")
+                    .map(|scope| scope.display()),
+                Some("-hidden type:rust".to_string())
+            );
         }
 
         Ok(())
@@ -0,0 +1,281 @@
+use base64::Engine;
+use indexmap::IndexMap;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
+use sha1::Digest;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl std::fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataFormat::Json => write!(f, "JSON"),
+            DataFormat::Yaml => write!(f, "YAML"),
+            DataFormat::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
+/// The value of a data format, whose object keys are kept in their original order,
+/// unlike those of `serde_json::Value`.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+enum Data {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<Data>),
+    Object(IndexMap<String, Data>),
+}
+
+impl DataFormat {
+    fn parse(self, string: &str) -> anyhow::Result<Data> {
+        Ok(match self {
+            DataFormat::Json => serde_json::from_str(string)?,
+            DataFormat::Yaml => serde_yaml_ng::from_str(string)?,
+            DataFormat::Toml => toml::from_str(string)?,
+        })
+    }
+
+    fn print(self, value: &Data) -> anyhow::Result<String> {
+        Ok(match self {
+            DataFormat::Json => serde_json::to_string_pretty(value)?,
+            DataFormat::Yaml => serde_yaml_ng::to_string(value)?,
+            DataFormat::Toml => toml::to_string_pretty(value)?,
+        })
+    }
+}
+
+/// Conversions of the content of a selection that run in-process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conversion {
+    PrettyJson,
+    MinifyJson,
+    Convert {
+        from: DataFormat,
+        to: DataFormat,
+    },
+    Base64Encode,
+    Base64Decode,
+    /// Percent-encodes every character except the unreserved ones of RFC 3986.
+    UrlEncode,
+    UrlDecode,
+    HtmlEscape,
+    HtmlUnescape,
+    /// Escapes the content as a JSON string literal, without the surrounding quotes.
+    EscapeString,
+    /// Unescapes a JSON string literal, whose surrounding quotes are optional.
+    UnescapeString,
+    Sha1,
+    Sha256,
+    Crc32,
+}
+
+impl std::fmt::Display for Conversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Conversion::PrettyJson => write!(f, "Pretty JSON"),
+            Conversion::MinifyJson => write!(f, "Minify JSON"),
+            Conversion::Convert { from, to } => write!(f, "{from} to {to}"),
+            Conversion::Base64Encode => write!(f, "Base64 Encode"),
+            Conversion::Base64Decode => write!(f, "Base64 Decode"),
+            Conversion::UrlEncode => write!(f, "URL Encode"),
+            Conversion::UrlDecode => write!(f, "URL Decode"),
+            Conversion::HtmlEscape => write!(f, "HTML Escape"),
+            Conversion::HtmlUnescape => write!(f, "HTML Unescape"),
+            Conversion::EscapeString => write!(f, "Escape String"),
+            Conversion::UnescapeString => write!(f, "Unescape String"),
+            Conversion::Sha1 => write!(f, "SHA-1"),
+            Conversion::Sha256 => write!(f, "SHA-256"),
+            Conversion::Crc32 => write!(f, "CRC32"),
+        }
+    }
+}
+
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+impl Conversion {
+    pub fn apply(&self, string: &str) -> anyhow::Result<String> {
+        match self {
+            Conversion::PrettyJson => Ok(keep_trailing_newline(
+                string,
+                serde_json::to_string_pretty(&DataFormat::Json.parse(string)?)?,
+            )),
+            Conversion::MinifyJson => Ok(keep_trailing_newline(
+                string,
+                serde_json::to_string(&DataFormat::Json.parse(string)?)?,
+            )),
+            Conversion::Convert { from, to } => Ok(keep_trailing_newline(
+                string,
+                to.print(&from.parse(string)?)?,
+            )),
+            Conversion::Base64Encode => {
+                Ok(base64::engine::general_purpose::STANDARD.encode(string))
+            }
+            Conversion::Base64Decode => Ok(String::from_utf8(
+                base64::engine::general_purpose::STANDARD.decode(string.trim())?,
+            )?),
+            Conversion::UrlEncode => {
+                Ok(percent_encoding::utf8_percent_encode(string, URL_ENCODE_SET).to_string())
+            }
+            Conversion::UrlDecode => Ok(percent_encoding::percent_decode_str(string)
+                .decode_utf8()?
+                .to_string()),
+            Conversion::HtmlEscape => Ok(html_escape(string)),
+            Conversion::HtmlUnescape => Ok(html_unescape(string)),
+            Conversion::EscapeString => {
+                let literal = serde_json::to_string(string)?;
+                Ok(literal[1..literal.len() - 1].to_string())
+            }
+            Conversion::UnescapeString => {
+                let literal =
+                    if string.len() >= 2 && string.starts_with('"') && string.ends_with('"') {
+                        string.to_string()
+                    } else {
+                        format!("\"{string}\"")
+                    };
+                Ok(serde_json::from_str(&literal)?)
+            }
+            Conversion::Sha1 => Ok(format!("{:x}", sha1::Sha1::digest(string.as_bytes()))),
+            Conversion::Sha256 => Ok(format!("{:x}", sha2::Sha256::digest(string.as_bytes()))),
+            Conversion::Crc32 => Ok(format!("{:08x}", crc32fast::hash(string.as_bytes()))),
+        }
+    }
+}
+
+/// Serializers disagree on the trailing newline,
+/// so the result ends with a newline only if the original content does.
+fn keep_trailing_newline(original: &str, result: String) -> String {
+    let result = result.trim_end_matches('\n');
+    if original.ends_with('\n') {
+        format!("{result}\n")
+    } else {
+        result.to_string()
+    }
+}
+
+fn html_escape(string: &str) -> String {
+    string
+        .chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&#39;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Unknown or invalid character references are kept as is.
+fn html_unescape(string: &str) -> String {
+    lazy_regex::regex!(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);")
+        .replace_all(string, |captures: &regex::Captures| {
+            let reference = &captures[1];
+            let c = match reference {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => reference
+                    .strip_prefix("#x")
+                    .or_else(|| reference.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| reference.strip_prefix('#').map(|decimal| decimal.parse()))
+                    .and_then(|code| char::from_u32(code.ok()?)),
+            };
+            c.map(|c| c.to_string())
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod test_conversion {
+    use super::{Conversion, DataFormat};
+
+    fn run_test(conversion: Conversion, input: &str, expected: &str) {
+        assert_eq!(conversion.apply(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn data_formats() {
+        run_test(
+            Conversion::PrettyJson,
+            "{\"b\":[1,2],\"a\":null}\n",
+            "{\n  \"b\": [\n    1,\n    2\n  ],\n  \"a\": null\n}\n",
+        );
+        run_test(Conversion::MinifyJson, "{ \"b\": [1, 2] }", "{\"b\":[1,2]}");
+        run_test(
+            Conversion::Convert {
+                from: DataFormat::Json,
+                to: DataFormat::Yaml,
+            },
+            "{\"name\":\"ki\",\"tags\":[\"editor\"]}",
+            "name: ki\ntags:\n- editor",
+        );
+        run_test(
+            Conversion::Convert {
+                from: DataFormat::Yaml,
+                to: DataFormat::Toml,
+            },
+            "name: ki\nversion: 1\n",
+            "name = \"ki\"\nversion = 1\n",
+        );
+        assert!(Conversion::PrettyJson.apply("{").is_err());
+    }
+
+    #[test]
+    fn encodings() {
+        run_test(Conversion::Base64Encode, "hello?", "aGVsbG8/");
+        run_test(Conversion::Base64Decode, "aGVsbG8/", "hello?");
+        run_test(Conversion::UrlEncode, "a b&c=d~", "a%20b%26c%3Dd~");
+        run_test(Conversion::UrlDecode, "a%20b%26c%3Dd~", "a b&c=d~");
+        run_test(
+            Conversion::HtmlEscape,
+            "<a href=\"x\">&</a>",
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;",
+        );
+        run_test(
+            Conversion::HtmlUnescape,
+            "&lt;b&gt; &#65;&#x42; &unknown;",
+            "<b> AB &unknown;",
+        );
+        run_test(
+            Conversion::EscapeString,
+            "say \"hi\"\n",
+            "say \\\"hi\\\"\\n",
+        );
+        run_test(
+            Conversion::UnescapeString,
+            "say \\\"hi\\\"\\n",
+            "say \"hi\"\n",
+        );
+        run_test(Conversion::UnescapeString, "\"a\\tb\"", "a\tb");
+        run_test(
+            Conversion::Sha1,
+            "abc",
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+        );
+        run_test(
+            Conversion::Sha256,
+            "abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        );
+        run_test(Conversion::Crc32, "123456789", "cbf43926");
+    }
+}
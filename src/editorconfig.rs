@@ -0,0 +1,542 @@
+//! Support for [EditorConfig](https://editorconfig.org/) files.
+//!
+//! The `.editorconfig` files are looked up from the directory of the given file
+//! towards the filesystem root, stopping at the first file declaring `root = true`.
+//! Files nearer to the given file take precedence.
+
+use std::path::Path;
+
+use globset::GlobBuilder;
+use itertools::Itertools;
+use shared::absolute_path::AbsolutePath;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentSize {
+    Columns(usize),
+    /// Use the value of `tab_width`
+    Tab,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl EndOfLine {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndOfLine::Lf => "\n",
+            EndOfLine::Crlf => "\r\n",
+            EndOfLine::Cr => "\r",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    Latin1,
+    Utf8,
+    Utf8Bom,
+    Utf16Be,
+    Utf16Le,
+}
+
+/// The properties that apply to one file.
+/// `None` means the property is not specified (or is `unset`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditorConfig {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<usize>,
+    pub end_of_line: Option<EndOfLine>,
+    pub charset: Option<Charset>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub max_line_length: Option<usize>,
+}
+
+const FILE_NAME: &str = ".editorconfig";
+
+impl EditorConfig {
+    pub fn from_path(path: &AbsolutePath) -> EditorConfig {
+        let mut config_files = Vec::new();
+        for directory in path.to_path_buf().ancestors().skip(1) {
+            let Ok(content) = std::fs::read_to_string(directory.join(FILE_NAME)) else {
+                continue;
+            };
+            let file = ConfigFile::parse(&content);
+            let is_root = file.is_root;
+            config_files.push((directory.to_path_buf(), file));
+            if is_root {
+                break;
+            }
+        }
+
+        let mut config = EditorConfig::default();
+        for (directory, file) in config_files.into_iter().rev() {
+            let Ok(relative_path) = path.to_path_buf().strip_prefix(&directory) else {
+                continue;
+            };
+            for section in file.sections {
+                if section.matches(relative_path) {
+                    for (key, value) in &section.properties {
+                        config.set(key, value);
+                    }
+                }
+            }
+        }
+        config
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        let unset = value == "unset";
+        match key {
+            "indent_style" => {
+                self.indent_style = match value {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ if unset => None,
+                    _ => self.indent_style,
+                }
+            }
+            "indent_size" => {
+                self.indent_size = match value {
+                    "tab" => Some(IndentSize::Tab),
+                    _ if unset => None,
+                    _ => value
+                        .parse()
+                        .ok()
+                        .map(IndentSize::Columns)
+                        .or(self.indent_size),
+                }
+            }
+            "tab_width" => {
+                self.tab_width = if unset {
+                    None
+                } else {
+                    value.parse().ok().or(self.tab_width)
+                }
+            }
+            "end_of_line" => {
+                self.end_of_line = match value {
+                    "lf" => Some(EndOfLine::Lf),
+                    "crlf" => Some(EndOfLine::Crlf),
+                    "cr" => Some(EndOfLine::Cr),
+                    _ if unset => None,
+                    _ => self.end_of_line,
+                }
+            }
+            "charset" => {
+                self.charset = match value {
+                    "latin1" => Some(Charset::Latin1),
+                    "utf-8" => Some(Charset::Utf8),
+                    "utf-8-bom" => Some(Charset::Utf8Bom),
+                    "utf-16be" => Some(Charset::Utf16Be),
+                    "utf-16le" => Some(Charset::Utf16Le),
+                    _ if unset => None,
+                    _ => self.charset,
+                }
+            }
+            "trim_trailing_whitespace" => {
+                self.trim_trailing_whitespace = parse_bool(value, self.trim_trailing_whitespace)
+            }
+            "insert_final_newline" => {
+                self.insert_final_newline = parse_bool(value, self.insert_final_newline)
+            }
+            "max_line_length" => {
+                self.max_line_length = if unset || value == "off" {
+                    None
+                } else {
+                    value.parse().ok().or(self.max_line_length)
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the indentation character and the number of it per indentation level.
+    /// `indent_style` and `indent_size` apply independently, each unspecified one
+    /// falls back to `detected`, then to `default`.
+    pub fn indentation(
+        &self,
+        detected: Option<(char, usize)>,
+        default: (char, usize),
+    ) -> (char, usize) {
+        let (detected_char, _) = detected.unwrap_or(default);
+        let indent_char = match self.indent_style {
+            Some(IndentStyle::Tab) => '\t',
+            Some(IndentStyle::Space) => ' ',
+            None => detected_char,
+        };
+        // The width of a detected or default indentation is only meaningful
+        // for the character that it was counted with
+        let fallback_width = [detected, Some(default)]
+            .into_iter()
+            .flatten()
+            .find(|(c, _)| *c == indent_char)
+            .map(|(_, width)| width);
+        if indent_char == '\t' {
+            // `indent_size` is the display width of a tab, so each indentation level is one tab,
+            // unless the tab indentation is detected or defaulted to have more
+            return match self.indent_style {
+                Some(IndentStyle::Tab) => ('\t', 1),
+                _ => ('\t', fallback_width.unwrap_or(1)),
+            };
+        }
+        let width = match self.indent_size {
+            Some(IndentSize::Columns(columns)) => Some(columns),
+            Some(IndentSize::Tab) => self.tab_width,
+            None => None,
+        };
+        (indent_char, width.or(fallback_width).unwrap_or(default.1))
+    }
+
+    /// Applies `end_of_line`, `trim_trailing_whitespace` and `insert_final_newline`.
+    pub fn normalize(&self, content: &str) -> String {
+        if self.end_of_line.is_none()
+            && self.trim_trailing_whitespace != Some(true)
+            && self.insert_final_newline.is_none()
+        {
+            return content.to_string();
+        }
+        let detected_end_of_line = if content.contains("\r\n") {
+            EndOfLine::Crlf
+        } else if content.contains('\r') && !content.contains('\n') {
+            EndOfLine::Cr
+        } else {
+            EndOfLine::Lf
+        };
+        let end_of_line = self.end_of_line.unwrap_or(detected_end_of_line).as_str();
+
+        let mut lines = split_lines(content)
+            .into_iter()
+            .map(|line| {
+                if self.trim_trailing_whitespace == Some(true) {
+                    line.trim_end()
+                } else {
+                    line
+                }
+            })
+            .collect_vec();
+        let has_final_newline = content.ends_with(['\n', '\r']);
+        if has_final_newline {
+            // `split_lines` yields an empty line after the final newline
+            lines.pop();
+        }
+        if self.insert_final_newline == Some(false) {
+            // The trailing empty lines would end the file with a newline
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+        }
+        let mut result = lines.join(end_of_line);
+        let final_newline = match self.insert_final_newline {
+            Some(insert_final_newline) => insert_final_newline && !result.is_empty(),
+            None => has_final_newline,
+        };
+        if final_newline {
+            result.push_str(end_of_line);
+        }
+        result
+    }
+
+    pub fn decode(&self, bytes: Vec<u8>) -> anyhow::Result<String> {
+        match self.charset {
+            Some(Charset::Latin1) => Ok(bytes.into_iter().map(char::from).collect()),
+            Some(Charset::Utf16Be) => decode_utf16(&bytes, u16::from_be_bytes),
+            Some(Charset::Utf16Le) => decode_utf16(&bytes, u16::from_le_bytes),
+            Some(Charset::Utf8Bom) => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(&bytes);
+                Ok(String::from_utf8(bytes.to_vec())?)
+            }
+            Some(Charset::Utf8) | None => Ok(String::from_utf8(bytes)?),
+        }
+    }
+
+    pub fn encode(&self, content: &str) -> anyhow::Result<Vec<u8>> {
+        match self.charset {
+            Some(Charset::Latin1) => content
+                .chars()
+                .map(|c| {
+                    u8::try_from(c).map_err(|_| {
+                        anyhow::anyhow!("The character {c:?} cannot be encoded as latin1")
+                    })
+                })
+                .collect(),
+            Some(Charset::Utf16Be) => Ok(std::iter::once(0xFEFF)
+                .chain(content.encode_utf16())
+                .flat_map(u16::to_be_bytes)
+                .collect()),
+            Some(Charset::Utf16Le) => Ok(std::iter::once(0xFEFF)
+                .chain(content.encode_utf16())
+                .flat_map(u16::to_le_bytes)
+                .collect()),
+            Some(Charset::Utf8Bom) => Ok(UTF8_BOM.iter().copied().chain(content.bytes()).collect()),
+            Some(Charset::Utf8) | None => Ok(content.as_bytes().to_vec()),
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> anyhow::Result<String> {
+    let units = bytes
+        .chunks(2)
+        .map(|chunk| from_bytes([chunk[0], chunk.get(1).copied().unwrap_or_default()]))
+        .skip_while(|unit| *unit == 0xFEFF)
+        .collect_vec();
+    Ok(String::from_utf16(&units)?)
+}
+
+fn parse_bool(value: &str, current: Option<bool>) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        "unset" => None,
+        _ => current,
+    }
+}
+
+/// Splits by `\r\n`, `\n` or `\r`, without the line terminators.
+fn split_lines(content: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let bytes = content.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\r' if bytes.get(index + 1) == Some(&b'\n') => {
+                lines.push(&content[start..index]);
+                index += 2;
+                start = index;
+            }
+            b'\r' | b'\n' => {
+                lines.push(&content[start..index]);
+                index += 1;
+                start = index;
+            }
+            _ => index += 1,
+        }
+    }
+    lines.push(&content[start..]);
+    lines
+}
+
+struct ConfigFile {
+    is_root: bool,
+    sections: Vec<Section>,
+}
+
+struct Section {
+    glob: String,
+    properties: Vec<(String, String)>,
+}
+
+impl ConfigFile {
+    fn parse(content: &str) -> ConfigFile {
+        let mut is_root = false;
+        let mut sections: Vec<Section> = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(glob) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                sections.push(Section {
+                    glob: glob.to_string(),
+                    properties: Vec::new(),
+                });
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+            match sections.last_mut() {
+                Some(section) => section.properties.push((key, value)),
+                None => {
+                    if key == "root" {
+                        is_root = value == "true"
+                    }
+                }
+            }
+        }
+        ConfigFile { is_root, sections }
+    }
+}
+
+impl Section {
+    /// `relative_path` is relative to the directory of the `.editorconfig` file.
+    fn matches(&self, relative_path: &Path) -> bool {
+        // A glob without a slash matches files in any subdirectory
+        let glob = if self.glob.contains('/') {
+            self.glob.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{}", self.glob)
+        };
+        GlobBuilder::new(&glob)
+            .literal_separator(true)
+            .build()
+            .map(|glob| glob.compile_matcher().is_match(relative_path))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test_editorconfig {
+    use shared::absolute_path::AbsolutePath;
+
+    use super::{Charset, EditorConfig, EndOfLine, IndentSize, IndentStyle};
+
+    #[test]
+    fn nearer_files_take_precedence_until_root() -> anyhow::Result<()> {
+        let directory = tempfile::tempdir()?;
+        let root = directory.path();
+        std::fs::create_dir_all(root.join("project/web"))?;
+        std::fs::write(root.join(".editorconfig"), "[*]\nindent_size = 8\n")?;
+        std::fs::write(
+            root.join("project/.editorconfig"),
+            "root = true
+
+[*]
+indent_style = space
+indent_size = 4
+end_of_line = lf
+
+[*.{js,ts}]
+indent_size = 2
+max_line_length = 100
+
+[Makefile]
+indent_style = tab
+",
+        )?;
+        std::fs::write(
+            root.join("project/web/.editorconfig"),
+            "[*.ts]\ncharset = utf-8-bom\ntrim_trailing_whitespace = true\nend_of_line = unset\n",
+        )?;
+        std::fs::write(root.join("project/web/main.ts"), "")?;
+        std::fs::write(root.join("project/main.rs"), "")?;
+        std::fs::write(root.join("project/web/Makefile"), "")?;
+
+        let main_ts = AbsolutePath::try_from(root.join("project/web/main.ts"))?;
+        assert_eq!(
+            EditorConfig::from_path(&main_ts),
+            EditorConfig {
+                indent_style: Some(IndentStyle::Space),
+                indent_size: Some(IndentSize::Columns(2)),
+                tab_width: None,
+                end_of_line: None,
+                charset: Some(Charset::Utf8Bom),
+                trim_trailing_whitespace: Some(true),
+                insert_final_newline: None,
+                max_line_length: Some(100),
+            }
+        );
+
+        // The `root = true` of `project/.editorconfig` hides the topmost file
+        let main_rs = AbsolutePath::try_from(root.join("project/main.rs"))?;
+        assert_eq!(
+            EditorConfig::from_path(&main_rs).indent_size,
+            Some(IndentSize::Columns(4))
+        );
+
+        // A glob without slash matches files in subdirectories too
+        let makefile = AbsolutePath::try_from(root.join("project/web/Makefile"))?;
+        assert_eq!(
+            EditorConfig::from_path(&makefile).indentation(None, (' ', 4)),
+            ('\t', 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn indentation_properties_apply_independently() {
+        let indent_style = |indent_style| EditorConfig {
+            indent_style: Some(indent_style),
+            ..Default::default()
+        };
+        let indent_size = |columns| EditorConfig {
+            indent_size: Some(IndentSize::Columns(columns)),
+            ..Default::default()
+        };
+        // The detected width is kept when only `indent_style` is specified
+        assert_eq!(
+            indent_style(IndentStyle::Space).indentation(Some((' ', 2)), (' ', 4)),
+            (' ', 2)
+        );
+        // The width of detected tabs does not apply to spaces
+        assert_eq!(
+            indent_style(IndentStyle::Space).indentation(Some(('\t', 1)), (' ', 4)),
+            (' ', 4)
+        );
+        // The detected style is kept when only `indent_size` is specified
+        assert_eq!(
+            indent_size(8).indentation(Some((' ', 2)), (' ', 4)),
+            (' ', 8)
+        );
+        assert_eq!(
+            indent_size(8).indentation(Some(('\t', 1)), (' ', 4)),
+            ('\t', 1)
+        );
+        assert_eq!(
+            EditorConfig::default().indentation(None, (' ', 4)),
+            (' ', 4)
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        let config = EditorConfig {
+            end_of_line: Some(EndOfLine::Crlf),
+            trim_trailing_whitespace: Some(true),
+            insert_final_newline: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(config.normalize("a  \nb\t\r\nc"), "a\r\nb\r\nc\r\n");
+
+        let config = EditorConfig {
+            insert_final_newline: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(config.normalize("a\n"), "a");
+        assert_eq!(config.normalize("a\r\n\r\n"), "a");
+        assert_eq!(config.normalize("a"), "a");
+    }
+
+    #[test]
+    fn encode_and_decode() -> anyhow::Result<()> {
+        for charset in [
+            Charset::Latin1,
+            Charset::Utf8,
+            Charset::Utf8Bom,
+            Charset::Utf16Be,
+            Charset::Utf16Le,
+        ] {
+            let config = EditorConfig {
+                charset: Some(charset),
+                ..Default::default()
+            };
+            let content = "café\n";
+            assert_eq!(config.decode(config.encode(content)?)?, content);
+        }
+        let latin1 = EditorConfig {
+            charset: Some(Charset::Latin1),
+            ..Default::default()
+        };
+        assert!(latin1.encode("ü").is_ok());
+        assert!(latin1.encode("😀").is_err());
+        Ok(())
+    }
+}
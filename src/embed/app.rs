@@ -521,6 +521,21 @@ impl EmbeddedApp {
             crate::selection::SelectionMode::Paragraph => {
                 ki_protocol_types::SelectionMode::Paragraph
             }
+            crate::selection::SelectionMode::Textobject { .. } => {
+                ki_protocol_types::SelectionMode::Textobject
+            }
+            crate::selection::SelectionMode::Block => ki_protocol_types::SelectionMode::Block,
+            crate::selection::SelectionMode::Sentence => ki_protocol_types::SelectionMode::Sentence,
+            crate::selection::SelectionMode::Markdown => ki_protocol_types::SelectionMode::Markdown,
+            crate::selection::SelectionMode::MergeConflict => {
+                ki_protocol_types::SelectionMode::MergeConflict
+            }
+            crate::selection::SelectionMode::IndentationBlock => {
+                ki_protocol_types::SelectionMode::IndentationBlock
+            }
+            crate::selection::SelectionMode::Pattern(_) => {
+                ki_protocol_types::SelectionMode::Pattern
+            }
             crate::selection::SelectionMode::Diagnostic(kind) => {
                 ki_protocol_types::SelectionMode::Diagnostic(match kind {
                     crate::quickfix_list::DiagnosticSeverityRange::All => {
@@ -31,8 +31,9 @@ use crossterm::{
     clipboard::CopyToClipboard,
     cursor::{Hide, MoveTo, SetCursorStyle, Show},
     event::{
-        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-        KeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
     },
     execute, queue,
     terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
@@ -78,6 +79,8 @@ impl Frontend for Crossterm {
     fn enter_alternate_screen(&mut self) -> anyhow::Result<()> {
         self.stdout.execute(EnterAlternateScreen)?;
         self.stdout.execute(EnableBracketedPaste)?;
+        // Focus events are used for auto-saving when the terminal loses focus
+        self.stdout.execute(EnableFocusChange)?;
 
         // Enable [Kitty's Keyboard Protocol](https://sw.kovidgoyal.net/kitty/keyboard-protocol/)
         // so that we can detect Key Release events
@@ -111,6 +114,7 @@ impl Frontend for Crossterm {
 
         self.stdout.execute(LeaveAlternateScreen)?;
         self.stdout.execute(DisableBracketedPaste)?;
+        self.stdout.execute(DisableFocusChange)?;
         Ok(())
     }
 
@@ -0,0 +1,209 @@
+//! Conflict regions that Git writes into a file when a merge cannot be resolved automatically:
+//!
+//! ```text
+//! <<<<<<< ours
+//! ...
+//! ||||||| base (only with `merge.conflictStyle = diff3` or `zdiff3`)
+//! ...
+//! =======
+//! ...
+//! >>>>>>> theirs
+//! ```
+
+use std::ops::Range;
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MergeConflictResolution {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs.
+    Both,
+    /// The common ancestor, which is only available for diff3-style conflicts.
+    Base,
+}
+
+impl MergeConflictResolution {
+    pub fn display(&self) -> &'static str {
+        match self {
+            MergeConflictResolution::Ours => "Ours",
+            MergeConflictResolution::Theirs => "Theirs",
+            MergeConflictResolution::Both => "Both",
+            MergeConflictResolution::Base => "Base",
+        }
+    }
+}
+
+/// All byte ranges are of whole lines, including their line break.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// From the `<<<<<<<` line to the `>>>>>>>` line.
+    range: Range<usize>,
+    ours: Range<usize>,
+    base: Option<Range<usize>>,
+    theirs: Range<usize>,
+    /// The lines of the markers.
+    markers: Vec<Range<usize>>,
+}
+
+impl MergeConflict {
+    pub fn range(&self) -> &Range<usize> {
+        &self.range
+    }
+
+    pub fn markers(&self) -> &[Range<usize>] {
+        &self.markers
+    }
+
+    /// Returns the content that replaces `self.range()` for the given `resolution`,
+    /// or `None` if the base is requested but this conflict has none.
+    pub fn resolve(&self, content: &str, resolution: MergeConflictResolution) -> Option<String> {
+        Some(match resolution {
+            MergeConflictResolution::Ours => content[self.ours.clone()].to_string(),
+            MergeConflictResolution::Theirs => content[self.theirs.clone()].to_string(),
+            MergeConflictResolution::Both => format!(
+                "{}{}",
+                &content[self.ours.clone()],
+                &content[self.theirs.clone()]
+            ),
+            MergeConflictResolution::Base => content[self.base.clone()?].to_string(),
+        })
+    }
+}
+
+/// Returns the conflicts of `content` in order.
+/// Regions whose markers are incomplete or out of order are ignored.
+pub fn merge_conflicts(content: &str) -> Vec<MergeConflict> {
+    enum Section {
+        Ours,
+        Base,
+        Theirs,
+    }
+    struct Pending {
+        section: Section,
+        start: usize,
+        section_start: usize,
+        ours: Option<Range<usize>>,
+        base: Option<Range<usize>>,
+        markers: Vec<Range<usize>>,
+    }
+
+    let mut result = Vec::new();
+    let mut pending: Option<Pending> = None;
+    let mut line_start = 0;
+    for line in content.split_inclusive('\n') {
+        let line_range = line_start..line_start + line.len();
+        line_start = line_range.end;
+        if is_marker(line, OURS_MARKER) {
+            // A new region also discards an unterminated one
+            pending = Some(Pending {
+                section: Section::Ours,
+                start: line_range.start,
+                section_start: line_range.end,
+                ours: None,
+                base: None,
+                markers: vec![line_range],
+            });
+            continue;
+        }
+        let Some(current) = pending.as_mut() else {
+            continue;
+        };
+        match current.section {
+            Section::Ours if is_marker(line, BASE_MARKER) => {
+                current.ours = Some(current.section_start..line_range.start);
+                current.section = Section::Base;
+            }
+            Section::Ours | Section::Base if is_marker(line, SEPARATOR_MARKER) => {
+                let section = current.section_start..line_range.start;
+                match current.section {
+                    Section::Ours => current.ours = Some(section),
+                    _ => current.base = Some(section),
+                }
+                current.section = Section::Theirs;
+            }
+            Section::Theirs if is_marker(line, THEIRS_MARKER) => {
+                let mut current = pending.take().expect("`pending` was matched above");
+                current.markers.push(line_range.clone());
+                result.push(MergeConflict {
+                    range: current.start..line_range.end,
+                    ours: current.ours.unwrap_or_default(),
+                    base: current.base,
+                    theirs: current.section_start..line_range.start,
+                    markers: current.markers,
+                });
+                continue;
+            }
+            _ => continue,
+        }
+        current.section_start = line_range.end;
+        current.markers.push(line_range);
+    }
+    result
+}
+
+/// A marker is followed by either the end of the line or a space and a label.
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\n', '\r']))
+}
+
+#[cfg(test)]
+mod test_merge_conflict {
+    use super::*;
+
+    #[test]
+    fn resolutions() {
+        let content = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\nb\n";
+        let conflicts = merge_conflicts(content);
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(
+            &content[conflict.range().clone()],
+            "<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\n"
+        );
+        let resolve = |resolution| conflict.resolve(content, resolution);
+        assert_eq!(resolve(MergeConflictResolution::Ours).unwrap(), "ours\n");
+        assert_eq!(
+            resolve(MergeConflictResolution::Theirs).unwrap(),
+            "theirs\n"
+        );
+        assert_eq!(
+            resolve(MergeConflictResolution::Both).unwrap(),
+            "ours\ntheirs\n"
+        );
+        assert_eq!(resolve(MergeConflictResolution::Base), None);
+        assert_eq!(conflict.markers().len(), 3);
+    }
+
+    #[test]
+    fn diff3_base() {
+        let content = "<<<<<<< ours\nx\n||||||| base\ny\n=======\n>>>>>>> theirs";
+        let conflicts = merge_conflicts(content);
+        assert_eq!(conflicts.len(), 1);
+        let resolve = |resolution| conflicts[0].resolve(content, resolution);
+        assert_eq!(resolve(MergeConflictResolution::Base).unwrap(), "y\n");
+        assert_eq!(resolve(MergeConflictResolution::Theirs).unwrap(), "");
+        assert_eq!(conflicts[0].markers().len(), 4);
+    }
+
+    #[test]
+    fn incomplete_or_unrelated_markers_are_ignored() {
+        assert_eq!(merge_conflicts("<<<<<<< a\nx\n=======\n"), Vec::new());
+        assert_eq!(
+            merge_conflicts("========\n=======\n>>>>>>> a\n"),
+            Vec::new()
+        );
+        assert_eq!(
+            merge_conflicts("<<<<<<< a\n<<<<<<< b\nx\n=======\ny\n>>>>>>> c\n")
+                .iter()
+                .map(|conflict| conflict.range().clone())
+                .collect::<Vec<_>>(),
+            [10..42]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 pub mod blame;
 pub mod hunk;
+pub mod merge_conflict;
 
 use anyhow::bail;
 use rayon::prelude::*;
@@ -46,6 +47,25 @@ impl GitRepo {
         &self.path
     }
 
+    /// The files that have unresolved merge conflicts, according to the index.
+    pub fn merge_conflicted_files(&self) -> anyhow::Result<Vec<AbsolutePath>> {
+        Ok(self
+            .repo
+            .index()?
+            .conflicts()?
+            // Like `diff_entries`, conflicts that cannot be read are ignored
+            .flatten()
+            .filter_map(|conflict| {
+                // The file is deleted on one side if `our` or `their` is missing
+                let entry = conflict.our.or(conflict.their).or(conflict.ancestor)?;
+                let path = str::from_utf8(&entry.path).ok()?;
+                self.path.join(path).ok()
+            })
+            .filter(|path| path.exists())
+            .unique()
+            .collect())
+    }
+
     pub fn diff_entries(&self, diff_mode: DiffMode) -> anyhow::Result<Vec<DiffEntry>> {
         // Open the repository
         let repo = &self.repo;
@@ -687,6 +687,7 @@ pub enum StyleKey {
     HunkOldEmphasized,
     HunkNew,
     HunkNewEmphasized,
+    MergeConflictMarker,
     UiFuzzyMatchedChar,
     ParentLine,
     UiPrimarySelectionSecondaryCursor,
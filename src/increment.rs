@@ -0,0 +1,224 @@
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use itertools::Itertools;
+
+/// A replacement for `string[start..end]`.
+struct Candidate {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+/// Increments the first incrementable token of `string` by `amount`.
+///
+/// The following tokens are recognized:
+/// - ISO dates (`2024-02-29`), incremented by days
+/// - times (`23:59` or `23:59:59`), incremented by minutes
+/// - decimal, hexadecimal (`0x`), binary (`0b`) and octal (`0o`) integers,
+///   keeping their prefix, letter case and zero-padding
+/// - words of `word_rings`, which are replaced by the word `amount` steps
+///   further along their ring
+///
+/// Returns `None` if `string` contains no incrementable token.
+pub(crate) fn increment(string: &str, amount: i64, word_rings: &[Vec<String>]) -> Option<String> {
+    // When several tokens start at the same position, the earlier kind wins,
+    // so that `2024-01-01` is treated as a date rather than the number `2024`.
+    let candidate = [
+        date(string, amount),
+        time(string, amount),
+        number(string, amount),
+        word(string, amount, word_rings),
+    ]
+    .into_iter()
+    .enumerate()
+    .filter_map(|(priority, candidate)| Some((candidate?, priority)))
+    .min_by_key(|(candidate, priority)| (candidate.start, *priority))
+    .map(|(candidate, _)| candidate)?;
+    Some(format!(
+        "{}{}{}",
+        &string[..candidate.start],
+        candidate.replacement,
+        &string[candidate.end..]
+    ))
+}
+
+fn date(string: &str, amount: i64) -> Option<Candidate> {
+    lazy_regex::regex!(r"\d{4}-\d{2}-\d{2}")
+        .find_iter(string)
+        .find_map(|found| {
+            let date = NaiveDate::parse_from_str(found.as_str(), "%Y-%m-%d").ok()?;
+            let date = date.checked_add_signed(TimeDelta::try_days(amount)?)?;
+            Some(Candidate {
+                start: found.start(),
+                end: found.end(),
+                replacement: date.format("%Y-%m-%d").to_string(),
+            })
+        })
+}
+
+fn time(string: &str, amount: i64) -> Option<Candidate> {
+    lazy_regex::regex!(r"\d{2}:\d{2}(?::\d{2})?")
+        .find_iter(string)
+        .find_map(|found| {
+            let (format, time) = ["%H:%M:%S", "%H:%M"].into_iter().find_map(|format| {
+                Some((
+                    format,
+                    NaiveTime::parse_from_str(found.as_str(), format).ok()?,
+                ))
+            })?;
+            let (time, _) = time.overflowing_add_signed(TimeDelta::try_minutes(amount)?);
+            Some(Candidate {
+                start: found.start(),
+                end: found.end(),
+                replacement: time.format(format).to_string(),
+            })
+        })
+}
+
+fn number(string: &str, amount: i64) -> Option<Candidate> {
+    let found =
+        lazy_regex::regex!(r"-?(?:0[xX][0-9a-fA-F]+|0[bB][01]+|0[oO][0-7]+|\d+)").find(string)?;
+    let (start, text) =
+        if found.as_str().starts_with('-') && is_preceded_by_word(string, found.start()) {
+            // The dash of `a-1` is a minus operator rather than a sign
+            (found.start() + 1, &found.as_str()[1..])
+        } else {
+            (found.start(), found.as_str())
+        };
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text),
+    };
+    let (prefix, digits, radix) = match unsigned.get(..2) {
+        Some("0x" | "0X") => (&unsigned[..2], &unsigned[2..], 16),
+        Some("0b" | "0B") => (&unsigned[..2], &unsigned[2..], 2),
+        Some("0o" | "0O") => (&unsigned[..2], &unsigned[2..], 8),
+        _ => ("", unsigned, 10),
+    };
+    let magnitude = i128::from_str_radix(digits, radix).ok()?;
+    let value = if negative { -magnitude } else { magnitude };
+    let value = value.checked_add(amount as i128)?;
+    let formatted = match radix {
+        16 if digits.chars().any(|c| c.is_ascii_uppercase()) => {
+            format!("{:X}", value.unsigned_abs())
+        }
+        16 => format!("{:x}", value.unsigned_abs()),
+        2 => format!("{:b}", value.unsigned_abs()),
+        8 => format!("{:o}", value.unsigned_abs()),
+        _ => value.unsigned_abs().to_string(),
+    };
+    // Zero-padding is preserved, e.g. `007` becomes `008`
+    let width = if radix != 10 || digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    };
+    Some(Candidate {
+        start,
+        end: found.end(),
+        replacement: format!(
+            "{}{prefix}{formatted:0>width$}",
+            if value < 0 { "-" } else { "" }
+        ),
+    })
+}
+
+fn word(string: &str, amount: i64, word_rings: &[Vec<String>]) -> Option<Candidate> {
+    word_rings
+        .iter()
+        .flat_map(|ring| {
+            ring.iter().enumerate().flat_map(move |(index, word)| {
+                string
+                    .match_indices(word.as_str())
+                    .filter(|(start, _)| {
+                        !word.is_empty()
+                            && !is_preceded_by_word(string, *start)
+                            && !string[start + word.len()..]
+                                .chars()
+                                .next()
+                                .is_some_and(is_word_char)
+                    })
+                    .map(move |(start, word)| {
+                        let next = (index as i64 + amount).rem_euclid(ring.len() as i64);
+                        Candidate {
+                            start,
+                            end: start + word.len(),
+                            replacement: ring[next as usize].clone(),
+                        }
+                    })
+            })
+        })
+        // Prefer the longest word, so that `pub(crate)` is not taken for `pub`
+        .sorted_by_key(|candidate| (candidate.start, std::cmp::Reverse(candidate.end)))
+        .next()
+}
+
+fn is_preceded_by_word(string: &str, index: usize) -> bool {
+    string[..index]
+        .chars()
+        .next_back()
+        .is_some_and(is_word_char)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod test_increment {
+    use super::increment;
+
+    fn rings() -> Vec<Vec<String>> {
+        [vec!["true", "false"], vec!["pub", "pub(crate)", "private"]]
+            .into_iter()
+            .map(|ring| ring.into_iter().map(|word| word.to_string()).collect())
+            .collect()
+    }
+
+    fn run(string: &str, amount: i64) -> Option<String> {
+        increment(string, amount, &rings())
+    }
+
+    #[test]
+    fn decimal() {
+        assert_eq!(run("x = 9;", 1).as_deref(), Some("x = 10;"));
+        assert_eq!(run("-1", 2).as_deref(), Some("1"));
+        assert_eq!(run("0", -3).as_deref(), Some("-3"));
+        assert_eq!(run("007", 1).as_deref(), Some("008"));
+        assert_eq!(run("a-1", 1).as_deref(), Some("a-2"));
+    }
+
+    #[test]
+    fn other_radixes() {
+        assert_eq!(run("0xff", 1).as_deref(), Some("0x100"));
+        assert_eq!(run("0x0F", 1).as_deref(), Some("0x10"));
+        assert_eq!(run("0x00ff", 1).as_deref(), Some("0x0100"));
+        assert_eq!(run("0b0111", 1).as_deref(), Some("0b1000"));
+        assert_eq!(run("0o17", 1).as_deref(), Some("0o20"));
+    }
+
+    #[test]
+    fn dates_and_times() {
+        assert_eq!(run("2024-02-28", 1).as_deref(), Some("2024-02-29"));
+        assert_eq!(
+            run("2024-12-31T10:00:00", 1).as_deref(),
+            Some("2025-01-01T10:00:00")
+        );
+        assert_eq!(run("at 23:59", 1).as_deref(), Some("at 00:00"));
+        assert_eq!(run("10:30:15", -31).as_deref(), Some("09:59:15"));
+    }
+
+    #[test]
+    fn word_rings() {
+        assert_eq!(run("true", 1).as_deref(), Some("false"));
+        assert_eq!(run("false", 1).as_deref(), Some("true"));
+        assert_eq!(run("pub fn", -1).as_deref(), Some("private fn"));
+        assert_eq!(run("pub(crate) fn", 1).as_deref(), Some("private fn"));
+        assert_eq!(run("untrue", 1), None);
+    }
+
+    #[test]
+    fn first_token_wins() {
+        assert_eq!(run("true || 1", 1).as_deref(), Some("false || 1"));
+        assert_eq!(run("1 || true", 1).as_deref(), Some("2 || true"));
+    }
+}
@@ -12,14 +12,15 @@ use crate::{
         },
     },
     context::{Context, LocalSearchConfigMode, Search},
-    git::DiffMode,
+    conversion::{Conversion, DataFormat},
+    git::{merge_conflict::MergeConflictResolution, DiffMode},
     list::grep::RegexConfig,
     quickfix_list::{DiagnosticSeverityRange, QuickfixListType},
     scripting::custom_keymap,
     selection::SelectionMode,
-    selection_mode::GetGapMovement,
+    selection_mode::{GetGapMovement, PatternKind, TextobjectKind},
     surround::EnclosureKind,
-    transformation::Transformation,
+    transformation::{Reorder, SortKey, Transformation},
 };
 
 use convert_case::Case;
@@ -36,16 +37,29 @@ pub fn transform_keymap_legend_config() -> KeymapLegendConfig {
     }
 }
 
-fn generate_enclosures_keymap(get_dispatch: impl Fn(EnclosureKind) -> Dispatch) -> Keymap {
-    Keymap::new(&[
-        Keybinding::new_undocumented("m", "( )", get_dispatch(EnclosureKind::Parentheses)),
-        Keybinding::new_undocumented(",", "[ ]", get_dispatch(EnclosureKind::SquareBrackets)),
-        Keybinding::new_undocumented(".", "{ }", get_dispatch(EnclosureKind::CurlyBraces)),
-        Keybinding::new_undocumented("/", "< >", get_dispatch(EnclosureKind::AngularBrackets)),
-        Keybinding::new_undocumented("j", "' '", get_dispatch(EnclosureKind::SingleQuotes)),
-        Keybinding::new_undocumented("k", "\" \"", get_dispatch(EnclosureKind::DoubleQuotes)),
-        Keybinding::new_undocumented("l", "` `", get_dispatch(EnclosureKind::Backticks)),
-    ])
+/// Enclosures for which `get_dispatch` returns `None` are omitted.
+fn generate_enclosures_keymap(get_dispatch: impl Fn(EnclosureKind) -> Option<Dispatch>) -> Keymap {
+    Keymap::new(
+        &[
+            ("m", "( )", EnclosureKind::Parentheses),
+            (",", "[ ]", EnclosureKind::SquareBrackets),
+            (".", "{ }", EnclosureKind::CurlyBraces),
+            ("/", "< >", EnclosureKind::AngularBrackets),
+            ("j", "' '", EnclosureKind::SingleQuotes),
+            ("k", "\" \"", EnclosureKind::DoubleQuotes),
+            ("l", "` `", EnclosureKind::Backticks),
+            ("n", "Keywords", EnclosureKind::Keywords),
+        ]
+        .into_iter()
+        .filter_map(|(key, description, enclosure)| {
+            Some(Keybinding::new_undocumented(
+                key,
+                description,
+                get_dispatch(enclosure)?,
+            ))
+        })
+        .collect_vec(),
+    )
 }
 
 pub fn multicursor_menu_keymap(editor: &Editor) -> Keymap {
@@ -66,6 +80,7 @@ pub fn multicursor_menu_keymap(editor: &Editor) -> Keymap {
             Dispatch::OpenFilterSelectionsPrompt { maintain: false },
         ),
         Keybinding::new_undocumented("l", "Keep Primary Curs", Dispatch::KeepCursorPrimaryOnly),
+        Keybinding::new_undocumented("o", "Curs Block", Dispatch::ToEditor(SelectBlock)),
     ];
     Keymap::new(
         &[].into_iter()
@@ -134,6 +149,18 @@ fn secondary_selection_modes_keybindings(
     ]
     .into_iter()
     .chain([
+        Keybinding::new_undocumented(
+            "m",
+            "Conflict",
+            match scope {
+                Scope::Global => Dispatch::GetRepoMergeConflicts,
+                Scope::Local => Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                    if_current_not_found,
+                    MergeConflict,
+                    prior_change,
+                )),
+            },
+        ),
         Keybinding::new_undocumented(
             "g",
             "Hunk@",
@@ -254,6 +281,7 @@ fn secondary_selection_modes_keybindings(
                         escaped: false,
                         match_whole_word: false,
                         case_sensitive: false,
+                        multi_line: false,
                     }),
                 };
                 let dispatch = Dispatch::ToEditor(SetSelectionModeWithPriorChange(
@@ -289,6 +317,68 @@ fn secondary_selection_modes_keybindings(
                     "One",
                     Dispatch::ToEditor(FindOneChar(if_current_not_found)),
                 ),
+                Keybinding::new_undocumented(
+                    "S",
+                    "Sentence",
+                    Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                        if_current_not_found,
+                        Sentence,
+                        prior_change,
+                    )),
+                ),
+                Keybinding::new_undocumented(
+                    "E",
+                    "Markdown",
+                    Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                        if_current_not_found,
+                        Markdown,
+                        prior_change,
+                    )),
+                ),
+                Keybinding::new_undocumented(
+                    "u",
+                    "URL",
+                    Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                        if_current_not_found,
+                        Pattern(PatternKind::Url),
+                        prior_change,
+                    )),
+                ),
+                Keybinding::new_undocumented(
+                    "p",
+                    "Path",
+                    Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                        if_current_not_found,
+                        Pattern(PatternKind::FilePath),
+                        prior_change,
+                    )),
+                ),
+                Keybinding::new_undocumented(
+                    "o",
+                    "Number",
+                    Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                        if_current_not_found,
+                        Pattern(PatternKind::Number),
+                        prior_change,
+                    )),
+                ),
+                Keybinding::new_undocumented(
+                    "i",
+                    "Indent Block",
+                    Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                        if_current_not_found,
+                        IndentationBlock,
+                        prior_change,
+                    )),
+                ),
+                Keybinding::new_undocumented(
+                    "T",
+                    "Textobject",
+                    Dispatch::ShowMenu(textobject_keymap_legend_config(
+                        if_current_not_found,
+                        prior_change,
+                    )),
+                ),
                 Keybinding::new_undocumented(
                     "r",
                     "Repeat Search →",
@@ -477,6 +567,28 @@ pub fn space_editor_keymap_legend_config() -> KeymapLegendConfig {
                     scope: Scope::Global,
                 },
             ),
+            Keybinding::new_undocumented(
+                "X",
+                "Preview Replace all",
+                Dispatch::PreviewGlobalReplace,
+            ),
+            Keybinding::new_undocumented(
+                "z",
+                "Exclude Change",
+                Dispatch::ExcludeFromReplacePreview { whole_file: false },
+            ),
+            Keybinding::new_undocumented(
+                "Z",
+                "Exclude File",
+                Dispatch::ExcludeFromReplacePreview { whole_file: true },
+            ),
+            Keybinding::new_undocumented("a", "Apply Replace", Dispatch::ApplyReplacePreview),
+            Keybinding::new_undocumented("l", "Preview Rule Fixes", Dispatch::PreviewAstGrepFixes),
+            Keybinding::new_undocumented(
+                "e",
+                "Search Scope",
+                Dispatch::OpenGlobalSearchScopePrompt,
+            ),
             Keybinding::new_undocumented(
                 "enter",
                 "Force Save",
@@ -495,6 +607,14 @@ pub fn space_editor_keymap_legend_config() -> KeymapLegendConfig {
                 "Reload File",
                 Dispatch::ToEditor(ReloadFile { force: false }),
             ),
+            Keybinding::new_undocumented(
+                "g",
+                "Toggle Large File Mode",
+                Dispatch::ToggleLargeFileMode,
+            ),
+            Keybinding::new_undocumented("r", "Toggle Read-only", Dispatch::ToggleReadOnly),
+            Keybinding::new_undocumented("s", "Sudo Save", Dispatch::SaveWithElevatedPrivileges),
+            Keybinding::new_undocumented("t", "Indentation", Dispatch::OpenIndentationPrompt),
         ]),
     }
 }
@@ -541,6 +661,16 @@ pub fn space_context_keymap_legend_config(editor: &Editor) -> KeymapLegendConfig
                 "Git Blame",
                 Dispatch::ToEditor(DispatchEditor::GitBlame),
             ),
+            Keybinding::new_undocumented(
+                "v",
+                "Revision@",
+                Dispatch::OpenFileAtRevision(DiffMode::UnstagedAgainstCurrentBranch),
+            ),
+            Keybinding::new_undocumented(
+                "V",
+                "Revision^",
+                Dispatch::OpenFileAtRevision(DiffMode::UnstagedAgainstMainBranch),
+            ),
             Keybinding::new_undocumented(
                 "x",
                 "Go to File",
@@ -648,6 +778,11 @@ pub fn keymap_transform() -> Vec<Keybinding> {
         "Wrap",
         Dispatch::ToEditor(Transform(Transformation::Wrap)),
     )))
+    .chain(Some(Keybinding::new_undocumented(
+        "J",
+        "Hard Wrap",
+        Dispatch::ToEditor(DispatchEditor::HardWrap),
+    )))
     .chain(Some(Keybinding::new_undocumented(
         "h",
         "Unwrap",
@@ -663,8 +798,213 @@ pub fn keymap_transform() -> Vec<Keybinding> {
         "Block Comment",
         Dispatch::ToEditor(DispatchEditor::ToggleBlockComment),
     )))
+    .chain(Some(Keybinding::new_undocumented(
+        "u",
+        "Reindent",
+        Dispatch::ToEditor(DispatchEditor::Reindent),
+    )))
+    .chain(Some(Keybinding::new_undocumented(
+        "m",
+        "Merge Conflict",
+        Dispatch::ShowMenu(merge_conflict_keymap_legend_config()),
+    )))
+    .chain(Some(Keybinding::new_undocumented(
+        "n",
+        "Reorder",
+        Dispatch::ShowMenu(reorder_keymap_legend_config()),
+    )))
+    .chain(Some(Keybinding::new_undocumented(
+        "c",
+        "Convert",
+        Dispatch::ShowMenu(convert_keymap_legend_config()),
+    )))
+    .chain([
+        Keybinding::new_undocumented(
+            "v",
+            "Evaluate",
+            Dispatch::ToEditor(Transform(Transformation::Evaluate {
+                keep_expression: false,
+            })),
+        ),
+        Keybinding::new_undocumented(
+            "V",
+            "Evaluate =",
+            Dispatch::ToEditor(Transform(Transformation::Evaluate {
+                keep_expression: true,
+            })),
+        ),
+        Keybinding::new_undocumented(
+            "b",
+            "Sum/Avg/Min/Max",
+            Dispatch::ToEditor(SummarizeSelections),
+        ),
+    ])
+    .chain(
+        [
+            ("i", "Increment", 1, false),
+            ("o", "Decrement", -1, false),
+            ("I", "Increment Seq", 1, true),
+            ("O", "Decrement Seq", -1, true),
+        ]
+        .into_iter()
+        .map(|(keybinding, description, amount, progressive)| {
+            Keybinding::new_undocumented(
+                keybinding,
+                description,
+                Dispatch::ToEditor(DispatchEditor::Increment {
+                    amount,
+                    progressive,
+                }),
+            )
+        }),
+    )
     .collect_vec()
 }
+/// Reorders the lines of the selection, or the selections if there are several.
+fn reorder_keymap_legend_config() -> KeymapLegendConfig {
+    KeymapLegendConfig {
+        title: "Reorder".to_string(),
+        keymap: Keymap::new(
+            &[
+                ("s", Reorder::Sort(SortKey::Lexicographic)),
+                ("i", Reorder::Sort(SortKey::CaseInsensitive)),
+                ("n", Reorder::Sort(SortKey::Natural)),
+                ("l", Reorder::Sort(SortKey::Length)),
+                ("r", Reorder::Reverse),
+                ("u", Reorder::Unique),
+                ("h", Reorder::Shuffle),
+            ]
+            .into_iter()
+            .map(|(keybinding, reorder)| {
+                Keybinding::new_dynamic(
+                    keybinding,
+                    reorder.to_string(),
+                    Dispatch::ToEditor(Transform(Transformation::Reorder(reorder))),
+                )
+            })
+            .chain(Some(Keybinding::new_undocumented(
+                "x",
+                "Sort by Regex",
+                Dispatch::OpenSortByRegexPrompt,
+            )))
+            .collect_vec(),
+        ),
+    }
+}
+
+/// Uppercase keys undo the conversion of their lowercase counterparts.
+fn convert_keymap_legend_config() -> KeymapLegendConfig {
+    use DataFormat::*;
+    KeymapLegendConfig {
+        title: "Convert".to_string(),
+        keymap: Keymap::new(
+            &[
+                ("j", Conversion::PrettyJson),
+                ("J", Conversion::MinifyJson),
+                (
+                    "q",
+                    Conversion::Convert {
+                        from: Json,
+                        to: Yaml,
+                    },
+                ),
+                (
+                    "Q",
+                    Conversion::Convert {
+                        from: Yaml,
+                        to: Json,
+                    },
+                ),
+                (
+                    "w",
+                    Conversion::Convert {
+                        from: Json,
+                        to: Toml,
+                    },
+                ),
+                (
+                    "W",
+                    Conversion::Convert {
+                        from: Toml,
+                        to: Json,
+                    },
+                ),
+                (
+                    "e",
+                    Conversion::Convert {
+                        from: Yaml,
+                        to: Toml,
+                    },
+                ),
+                (
+                    "E",
+                    Conversion::Convert {
+                        from: Toml,
+                        to: Yaml,
+                    },
+                ),
+                ("a", Conversion::Base64Encode),
+                ("A", Conversion::Base64Decode),
+                ("s", Conversion::UrlEncode),
+                ("S", Conversion::UrlDecode),
+                ("d", Conversion::HtmlEscape),
+                ("D", Conversion::HtmlUnescape),
+                ("f", Conversion::EscapeString),
+                ("F", Conversion::UnescapeString),
+                ("z", Conversion::Sha1),
+                ("x", Conversion::Crc32),
+                ("c", Conversion::Sha256),
+            ]
+            .into_iter()
+            .map(|(keybinding, conversion)| {
+                Keybinding::new_dynamic(
+                    keybinding,
+                    conversion.to_string(),
+                    Dispatch::ToEditor(Transform(Transformation::Convert(conversion))),
+                )
+            })
+            .collect_vec(),
+        ),
+    }
+}
+
+/// Lowercase keys resolve the selected merge conflicts, uppercase keys resolve all of them.
+fn merge_conflict_keymap_legend_config() -> KeymapLegendConfig {
+    KeymapLegendConfig {
+        title: "Merge Conflict".to_string(),
+        keymap: Keymap::new(
+            &[
+                (["o", "O"], MergeConflictResolution::Ours),
+                (["t", "T"], MergeConflictResolution::Theirs),
+                (["b", "B"], MergeConflictResolution::Both),
+                (["a", "A"], MergeConflictResolution::Base),
+            ]
+            .into_iter()
+            .flat_map(|(keys, resolution)| {
+                [
+                    Keybinding::new_undocumented(
+                        keys[0],
+                        resolution.display(),
+                        Dispatch::ToEditor(ResolveMergeConflict {
+                            resolution,
+                            all: false,
+                        }),
+                    ),
+                    Keybinding::new_dynamic(
+                        keys[1],
+                        format!("{} (all)", resolution.display()),
+                        Dispatch::ToEditor(ResolveMergeConflict {
+                            resolution,
+                            all: true,
+                        }),
+                    ),
+                ]
+            })
+            .collect_vec(),
+        ),
+    }
+}
+
 pub fn extend_mode_keymap_legend_config(editor: &Editor) -> KeymapLegendConfig {
     KeymapLegendConfig {
         title: "Extend".to_string(),
@@ -731,16 +1071,54 @@ pub fn secondary_selection_modes_keymap_legend_config(
     }
 }
 
+fn textobject_keymap_legend_config(
+    if_current_not_found: IfCurrentNotFound,
+    prior_change: Option<PriorChange>,
+) -> KeymapLegendConfig {
+    let textobject_keybindings = |keys: [&'static str; 2], description: &'static str, kind| {
+        let to_dispatch = |inside| {
+            Dispatch::ToEditor(SetSelectionModeWithPriorChange(
+                if_current_not_found,
+                Textobject { kind, inside },
+                prior_change,
+            ))
+        };
+        [
+            Keybinding::new_undocumented(keys[0], description, to_dispatch(false)),
+            Keybinding::new_dynamic(
+                keys[1],
+                format!("{description} (inside)"),
+                to_dispatch(true),
+            ),
+        ]
+    };
+    KeymapLegendConfig {
+        title: "Textobject".to_string(),
+        keymap: Keymap::new(
+            &[
+                textobject_keybindings(["u", "U"], "Function", TextobjectKind::Function),
+                textobject_keybindings(["i", "I"], "Class", TextobjectKind::Class),
+                textobject_keybindings(["o", "O"], "Parameter", TextobjectKind::Parameter),
+                textobject_keybindings(["j", "J"], "Comment", TextobjectKind::Comment),
+                textobject_keybindings(["k", "K"], "Test", TextobjectKind::Test),
+            ]
+            .into_iter()
+            .flatten()
+            .collect_vec(),
+        ),
+    }
+}
+
 pub fn keymap_surround() -> Keymap {
     fn select_surround_keymap_legend_config(kind: SurroundKind) -> KeymapLegendConfig {
         KeymapLegendConfig {
             title: format!("Select Surround ({kind:?})"),
 
             keymap: generate_enclosures_keymap(|enclosure| {
-                Dispatch::ToEditor(SelectSurround {
+                Some(Dispatch::ToEditor(SelectSurround {
                     enclosure,
                     kind: kind.clone(),
-                })
+                }))
             }),
         }
     }
@@ -750,7 +1128,7 @@ pub fn keymap_surround() -> Keymap {
             title: "Delete Surround".to_string(),
 
             keymap: generate_enclosures_keymap(|enclosure| {
-                Dispatch::ToEditor(DeleteSurround(enclosure))
+                Some(Dispatch::ToEditor(DeleteSurround(enclosure)))
             }),
         }
     }
@@ -761,8 +1139,11 @@ pub fn keymap_surround() -> Keymap {
 
             keymap: Keymap::new(
                 &generate_enclosures_keymap(|enclosure| {
-                    let (open, close) = enclosure.open_close_symbols_str();
-                    Dispatch::ToEditor(Surround(open.to_string(), close.to_string()))
+                    let (open, close) = enclosure.open_close_symbols_str()?;
+                    Some(Dispatch::ToEditor(Surround(
+                        open.to_string(),
+                        close.to_string(),
+                    )))
                 })
                 .into_vec()
                 .into_iter()
@@ -781,7 +1162,9 @@ pub fn keymap_surround() -> Keymap {
             title: "Change Surround from:".to_string(),
 
             keymap: generate_enclosures_keymap(|enclosure| {
-                Dispatch::ShowMenu(change_surround_to_keymap_legend_config(enclosure))
+                Some(Dispatch::ShowMenu(change_surround_to_keymap_legend_config(
+                    enclosure,
+                )))
             }),
         }
     }
@@ -791,10 +1174,11 @@ pub fn keymap_surround() -> Keymap {
             title: format!("Change Surround from {} to:", from.to_str()),
 
             keymap: generate_enclosures_keymap(|enclosure| {
-                Dispatch::ToEditor(ChangeSurround {
+                enclosure.open_close_symbols_str()?;
+                Some(Dispatch::ToEditor(ChangeSurround {
                     from,
                     to: enclosure,
-                })
+                }))
             }),
         }
     }
@@ -824,6 +1208,7 @@ pub fn keymap_surround() -> Keymap {
             "Select Around",
             Dispatch::ShowMenu(select_surround_keymap_legend_config(SurroundKind::Around)),
         ),
+        Keybinding::new_undocumented("g", "Matching Pair", Dispatch::ToEditor(GoToMatchingPair)),
     ])
 }
 
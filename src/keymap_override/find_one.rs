@@ -36,6 +36,7 @@ impl KeymapOverrideTrait for FindOneCharKeymapOverride {
                                 escaped: true,
                                 case_sensitive: true,
                                 match_whole_word: false,
+                                multi_line: false,
                             }),
                         },
                     },
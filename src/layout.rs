@@ -25,7 +25,7 @@ use indexmap::IndexMap;
 use itertools::Itertools;
 use nary_tree::NodeId;
 use shared::absolute_path::AbsolutePath;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[cfg(test)]
 pub type BufferContentsMap = std::collections::HashMap<String, String>;
@@ -279,6 +279,31 @@ impl Layout {
             })
     }
 
+    /// Saves the dirty buffers which do not conflict with their files on disk.
+    /// The paths of conflicting buffers are returned, so that the caller decides
+    /// whether to show the save-conflict prompt.
+    pub fn auto_save_all(
+        &self,
+        run_formatter: bool,
+        context: &Context,
+    ) -> anyhow::Result<(Dispatches, Vec<AbsolutePath>)> {
+        let mut conflicting_paths = Vec::new();
+        let mut dispatches = Dispatches::empty();
+        for (path, editor) in &self.background_suggestive_editors {
+            if editor.borrow().editor().buffer().has_save_conflict(context) {
+                conflicting_paths.push(path.clone());
+                continue;
+            }
+            dispatches = dispatches.chain(
+                editor
+                    .borrow_mut()
+                    .editor_mut()
+                    .auto_save(run_formatter, context)?,
+            );
+        }
+        Ok((dispatches, conflicting_paths))
+    }
+
     pub fn reveal_path_in_explorer(
         &mut self,
         path: &AbsolutePath,
@@ -366,25 +391,11 @@ impl Layout {
             .collect_vec()
     }
 
-    pub fn reload_buffers(
-        &self,
-        context: &Context,
-        affected_paths: Vec<AbsolutePath>,
-    ) -> anyhow::Result<Dispatches> {
-        self.buffers()
-            .into_iter()
-            .try_fold(Dispatches::default(), |dispatches, buffer| {
-                let mut buffer = buffer.borrow_mut();
-                if let Some(path) = buffer.path() {
-                    if affected_paths
-                        .iter()
-                        .any(|affected_path| affected_path == &path)
-                    {
-                        return Ok(dispatches.chain(buffer.reload(context, true)?));
-                    }
-                }
-                Ok(dispatches)
-            })
+    pub fn get_opened_buffer_contents(&self) -> HashMap<AbsolutePath, String> {
+        self.background_suggestive_editors
+            .iter()
+            .map(|(path, editor)| (path.clone(), editor.borrow().editor().buffer().content()))
+            .collect()
     }
 
     #[cfg(test)]
@@ -6,14 +6,18 @@ mod git;
 
 mod alternator;
 mod app;
+mod arithmetic;
+mod ast_grep_rule;
 pub mod char_index_range;
 mod cli;
 mod clipboard;
 mod components;
 pub mod config;
 mod context;
+mod conversion;
 mod divide_viewport;
 mod edit;
+mod editorconfig;
 mod embed;
 mod env;
 pub mod file_watcher;
@@ -23,6 +27,7 @@ pub mod frontend;
 mod generate_recipes;
 mod grid;
 pub mod history;
+mod increment;
 mod integration_event;
 #[cfg(test)]
 mod integration_test;
@@ -49,6 +54,8 @@ pub mod soft_wrap;
 pub mod style;
 pub mod surround;
 pub mod syntax_highlight;
+mod syntax_indent;
+mod syntax_injection;
 #[cfg(test)]
 mod test_app;
 #[cfg(test)]
@@ -78,6 +85,8 @@ pub fn main() {
 pub struct RunConfig {
     pub entry_path: Option<AbsolutePath>,
     pub working_directory: Option<AbsolutePath>,
+    /// Overrides whether `entry_path` is opened in large-file mode.
+    pub large_file_mode: Option<bool>,
 }
 
 fn init_logger() -> anyhow::Result<()> {
@@ -158,7 +167,7 @@ pub fn run(config: RunConfig) -> anyhow::Result<()> {
             .map_err(|err| log::info!("main::run::crossterm {err:#?}"));
     });
 
-    app.run(config.entry_path)
+    app.run(config.entry_path, config.large_file_mode)
         .map_err(|error| anyhow::anyhow!("screen.run {:?}", error))?;
 
     Ok(())
@@ -1,6 +1,13 @@
 use itertools::Itertools;
+use shared::absolute_path::AbsolutePath;
 
-use crate::{list::Match, selection_mode::AstGrep, thread::SendResult};
+use crate::{
+    ast_grep_rule::{self, AstGrepRule},
+    buffer::Buffer,
+    list::{replace_preview::ReplacePreview, Match},
+    selection_mode::AstGrep,
+    thread::SendResult,
+};
 
 use std::sync::Arc;
 
@@ -26,3 +33,27 @@ pub fn run(
         }),
     )
 }
+
+/// Computes the fixes of the `rules` without modifying any file or buffer.
+pub fn preview_fixes(
+    rules: Vec<AstGrepRule>,
+    walk_builder_config: WalkBuilderConfig,
+) -> anyhow::Result<ReplacePreview> {
+    let buffer_contents = walk_builder_config.buffer_contents.clone();
+    let files = walk_builder_config.run(Box::new(move |path, sender| {
+        let path: AbsolutePath = path.try_into()?;
+        let Some(language) = crate::config::from_path(&path) else {
+            return Ok(());
+        };
+        let content = match buffer_contents.get(&path) {
+            Some(content) => content.clone(),
+            None => Buffer::from_path(&path, false)?.content(),
+        };
+        let fixes = ast_grep_rule::fixes(&ast_grep_rule::find_matches(&rules, &language, &content));
+        if !fixes.is_empty() {
+            let _ = sender.send((path, content, fixes));
+        }
+        Ok(())
+    }))?;
+    Ok(ReplacePreview::new(files))
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use crate::{context::FuzzyCandidate, list::Match, selection_mode::Fuzzy, thread::SendResult};
+
+use super::WalkBuilderConfig;
+
+pub fn run(
+    pattern: String,
+    candidate: FuzzyCandidate,
+    walk_builder_config: WalkBuilderConfig,
+    send_match: Arc<dyn Fn(Match) -> SendResult + Send + Sync>,
+) -> anyhow::Result<()> {
+    walk_builder_config.run_with_search(
+        candidate == FuzzyCandidate::SyntaxNode,
+        send_match,
+        Arc::new(move |buffer| {
+            // Files without a Tree-sitter language have no syntax node to match
+            Fuzzy::new(buffer, &pattern, candidate)
+                .map(|fuzzy| fuzzy.find_all())
+                .unwrap_or_default()
+        }),
+    )
+}
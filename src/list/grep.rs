@@ -9,7 +9,7 @@ use crate::{
     app::Dispatches,
     buffer::Buffer,
     context::{Context, LocalSearchConfig},
-    list::{reorder_batches::reorder_batches, Match},
+    list::{reorder_batches::reorder_batches, replace_preview::ReplacePreview, Match},
     quickfix_list::Location,
     selection::SelectionSet,
     selection_mode::regex::get_regex,
@@ -24,6 +24,11 @@ pub struct RegexConfig {
     pub escaped: bool,
     pub case_sensitive: bool,
     pub match_whole_word: bool,
+    /// Allows matches to span several lines, such as `foo\(\s*\n\s*bar`.
+    ///
+    /// Global search reads each file as a whole in this mode instead of line by line,
+    /// so it is opt-in.
+    pub multi_line: bool,
 }
 
 impl RegexConfig {
@@ -36,6 +41,7 @@ impl RegexConfig {
             case_sensitive: false,
             escaped: true,
             match_whole_word: false,
+            multi_line: false,
         }
     }
 
@@ -44,6 +50,7 @@ impl RegexConfig {
             escaped: true,
             match_whole_word: true,
             case_sensitive: true,
+            multi_line: false,
         }
     }
 
@@ -52,6 +59,7 @@ impl RegexConfig {
             escaped: false,
             match_whole_word: false,
             case_sensitive: false,
+            multi_line: false,
         }
     }
 
@@ -60,6 +68,7 @@ impl RegexConfig {
             escaped: true,
             match_whole_word: true,
             case_sensitive: false,
+            multi_line: false,
         }
     }
 
@@ -68,6 +77,7 @@ impl RegexConfig {
             escaped: true,
             match_whole_word: false,
             case_sensitive: true,
+            multi_line: false,
         }
     }
 }
@@ -78,11 +88,51 @@ impl Default for RegexConfig {
             escaped: true,
             case_sensitive: false,
             match_whole_word: false,
+            multi_line: false,
         }
     }
 }
 
-/// Returns list of affected files
+/// A file whose content is changed by a global replacement.
+struct ReplacedBuffer {
+    path: AbsolutePath,
+    opened: bool,
+    buffer: Buffer,
+}
+
+fn replaced_buffers(
+    walk_builder_config: WalkBuilderConfig,
+    local_search_config: LocalSearchConfig,
+) -> anyhow::Result<Vec<ReplacedBuffer>> {
+    let buffer_contents = walk_builder_config.buffer_contents.clone();
+    walk_builder_config.run(Box::new(move |path, sender| {
+        let path: AbsolutePath = path.try_into()?;
+        let require_tree_sitter = local_search_config.require_tree_sitter();
+        let opened = buffer_contents.get(&path);
+        let mut buffer = match opened {
+            Some(content) => Buffer::from_content(&path, content.clone(), require_tree_sitter),
+            None => Buffer::from_path(&path, require_tree_sitter)?,
+        };
+        let (modified, _, _, _) =
+            buffer.replace(local_search_config.clone(), SelectionSet::default(), 0)?;
+        if modified {
+            sender
+                .send(ReplacedBuffer {
+                    path,
+                    opened: opened.is_some(),
+                    buffer,
+                })
+                .map_err(|err| log::info!("Error = {err:?}"))
+                .unwrap_or_default();
+        }
+        Ok(())
+    }))
+}
+
+/// Returns the opened files that have matches.
+///
+/// Opened files are not modified here, because their buffers might contain unsaved changes,
+/// so the caller is expected to apply the replacement to their buffers instead.
 pub fn replace(
     context: &Context,
     walk_builder_config: WalkBuilderConfig,
@@ -93,27 +143,16 @@ pub fn replace(
     // `Rc<RefCell<Buffer>>` (via `QuickfixList`) which is not `Send + Sync`.
     // Instead we return the modified `Buffer` to the main thread and call
     // `save_without_formatting` here, where `context` is available.
-    let (buffers, paths): (Vec<_>, Vec<_>) = walk_builder_config
-        .run(Box::new(move |path, sender| {
-            let path: AbsolutePath = path.try_into()?;
-            let mut buffer = Buffer::from_path(&path, local_search_config.require_tree_sitter())?;
-            let (modified, _, _, _) =
-                buffer.replace(local_search_config.clone(), SelectionSet::default(), 0)?;
-            if modified {
-                sender
-                    .send((buffer, path))
-                    .map_err(|err| log::info!("Error = {err:?}"))
-                    .unwrap_or_default();
-            }
-            Ok(())
-        }))?
-        .into_iter()
-        .unzip();
+    let (opened, unopened): (Vec<_>, Vec<_>) =
+        replaced_buffers(walk_builder_config, local_search_config)?
+            .into_iter()
+            .partition(|replaced| replaced.opened);
 
-    let dispatches = buffers
+    let dispatches = unopened
         .into_iter()
-        .map(|mut buffer| {
-            buffer
+        .map(|mut replaced| {
+            replaced
+                .buffer
                 .save_without_formatting(context, true)
                 .map(|(d, _)| d)
         })
@@ -121,7 +160,30 @@ pub fn replace(
         .into_iter()
         .reduce(Dispatches::chain)
         .unwrap_or_default();
-    Ok((dispatches, paths))
+    let opened_paths = opened.into_iter().map(|replaced| replaced.path).collect();
+    Ok((dispatches, opened_paths))
+}
+
+/// Computes the replacement without modifying any file or buffer.
+pub fn preview_replace(
+    walk_builder_config: WalkBuilderConfig,
+    local_search_config: LocalSearchConfig,
+) -> anyhow::Result<ReplacePreview> {
+    let buffer_contents = walk_builder_config.buffer_contents.clone();
+    let files = walk_builder_config.run(Box::new(move |path, sender| {
+        let path: AbsolutePath = path.try_into()?;
+        let require_tree_sitter = local_search_config.require_tree_sitter();
+        let buffer = match buffer_contents.get(&path) {
+            Some(content) => Buffer::from_content(&path, content.clone(), require_tree_sitter),
+            None => Buffer::from_path(&path, require_tree_sitter)?,
+        };
+        let replacements = buffer.replacements(&local_search_config)?;
+        if !replacements.is_empty() {
+            let _ = sender.send((path, buffer.content(), replacements));
+        }
+        Ok(())
+    }))?;
+    Ok(ReplacePreview::new(files))
 }
 
 pub fn run(
@@ -131,37 +193,50 @@ pub fn run(
     send_match: Arc<dyn Fn(Match) -> SendResult + Send + Sync>,
 ) -> anyhow::Result<()> {
     let pattern = get_regex(pattern, grep_config)?.as_str().to_string();
-    let matcher = RegexMatcher::new_line_matcher(&pattern)?;
+    let matcher = if grep_config.multi_line {
+        RegexMatcher::new(&pattern)?
+    } else {
+        RegexMatcher::new_line_matcher(&pattern)?
+    };
     let regex = Regex::new(&pattern)?;
 
     let sender = reorder_batches(send_match);
+    let progress = walk_builder_config.progress.clone();
 
     walk_builder_config.run_async(
         false,
         Arc::new(move |path_index, path, buffer| {
-            let mut searcher = SearcherBuilder::new().build();
+            if progress.is_cancelled() {
+                let _ = sender.send((path_index, Vec::new()));
+                return;
+            }
+            let mut searcher = SearcherBuilder::new()
+                .multi_line(grep_config.multi_line)
+                .build();
             let mut matches = vec![];
 
-            let _ = searcher.search_path(
+            // In multi-line mode, `lines` contains every line spanned by the matches,
+            // and `line_number` is the number of the first line
+            // The content of the buffer is searched instead of the file,
+            // because it might be an opened buffer with unsaved changes
+            let _ = searcher.search_slice(
                 &matcher,
-                path.clone(),
-                sinks::UTF8(|line_number, line| {
-                    if let Ok(locations) = to_locations(
+                buffer.content().as_bytes(),
+                sinks::UTF8(|line_number, lines| {
+                    if let Ok(found) = to_matches(
                         &buffer,
                         path.clone(),
                         line_number as usize,
-                        line,
+                        lines,
                         regex.clone(),
                     ) {
-                        matches.extend(locations.into_iter().map(|location| Match {
-                            location,
-                            line: line.to_string(),
-                        }));
+                        matches.extend(found);
                     }
                     Ok(true)
                 }),
             );
 
+            progress.add_matches(matches.len());
             // The path_index needs to be sent even if there is no matches
             // otherwise buffer_entries will not work
             let _ = sender.send((path_index, matches));
@@ -169,26 +244,36 @@ pub fn run(
     )
 }
 
-fn to_locations(
+fn to_matches(
     buffer: &Buffer,
     path: AbsolutePath,
     line_number: usize,
-    line: &str,
+    lines: &str,
     regex: Regex,
-) -> anyhow::Result<Vec<Location>> {
+) -> anyhow::Result<Vec<Match>> {
     let start_byte = buffer.line_to_byte(line_number.saturating_sub(1))?;
-    let locations = regex
-        .find_iter(line)
-        .flat_map(|match_| -> anyhow::Result<Location> {
+    let matches = regex
+        .find_iter(lines)
+        .flat_map(|match_| -> anyhow::Result<Match> {
             let range = match_?.range();
             let start = buffer.byte_to_char(range.start + start_byte)?;
             let end = buffer.byte_to_char(range.end + start_byte)?;
-            Ok(Location {
-                range: (start..end).into(),
-                path: path.clone(),
+            // Only the line where the match starts is shown in the quickfix list
+            let line_start = lines[..range.start]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            let line_end = lines[range.start..]
+                .find('\n')
+                .map_or(lines.len(), |index| range.start + index + 1);
+            Ok(Match {
+                location: Location {
+                    range: (start..end).into(),
+                    path: path.clone(),
+                },
+                line: lines[line_start..line_end].to_string(),
             })
         })
         .collect();
 
-    Ok(locations)
+    Ok(matches)
 }
@@ -1,10 +1,15 @@
 use std::{
+    collections::HashMap,
     ops::Range,
-    path::PathBuf,
-    sync::{mpsc::Sender, Arc},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
 };
 
-use globset::Glob;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::{WalkBuilder, WalkState};
 use itertools::Itertools;
 use rayon::iter::{ParallelBridge, ParallelIterator};
@@ -17,18 +22,127 @@ use crate::{
 
 pub mod ast_grep;
 
+pub mod fuzzy;
 pub mod grep;
 pub mod naming_convention_agnostic;
 pub mod reorder_batches;
+pub mod replace_preview;
 
 pub struct WalkBuilderConfig {
     pub root: PathBuf,
-    pub include: Option<Glob>,
-    pub exclude: Option<Glob>,
+    /// Relative to `root`.
+    pub include: Vec<Glob>,
+    /// Relative to `root`.
+    pub exclude: Vec<Glob>,
+    pub include_hidden: bool,
+    pub include_ignored: bool,
+    /// Matched against the file names only.
+    /// If it is not empty, files that match none of these are not visited.
+    pub file_name_globs: Vec<Glob>,
+    /// The files and directories to visit instead of `root`.
+    pub paths: Option<Vec<PathBuf>>,
+    /// The contents of the opened buffers, which are visited instead of their files,
+    /// so that unsaved changes are taken into account.
+    pub buffer_contents: Arc<HashMap<AbsolutePath, String>>,
+    pub progress: WalkProgress,
+}
+
+/// Shared between a walk and its owner, so that the owner can stop the walk
+/// and report its progress while it runs in the background.
+#[derive(Clone, Debug, Default)]
+pub struct WalkProgress {
+    cancelled: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>,
+    scanned_files: Arc<AtomicUsize>,
+    matches: Arc<AtomicUsize>,
+}
+
+impl WalkProgress {
+    /// Stops visiting files, and stops reporting the matches of the visited ones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    pub fn scanned_files(&self) -> usize {
+        self.scanned_files.load(Ordering::Relaxed)
+    }
+
+    pub fn matches(&self) -> usize {
+        self.matches.load(Ordering::Relaxed)
+    }
+
+    /// Should be called by every search that walks with `WalkBuilderConfig::run_async`.
+    fn add_matches(&self, count: usize) {
+        self.matches.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn display(&self) -> String {
+        format!(
+            "Searching: {} files, {} matches",
+            self.scanned_files(),
+            self.matches()
+        )
+    }
 }
 type GetRange = dyn Fn(&Buffer) -> Vec<Range<usize>> + Send + Sync;
+type FileFilter = dyn Fn(&Path) -> bool + Send + Sync;
 
 impl WalkBuilderConfig {
+    /// Returns the walker, and the predicate of the files to be visited.
+    /// The predicate is not a `filter_entry` of the walker,
+    /// because `filter_entry` is not applied to `paths`, such as the marked files.
+    fn walk_builder(&self) -> anyhow::Result<(WalkBuilder, Arc<FileFilter>)> {
+        let build_glob_set = |globs: &[Glob], root: &Path| -> anyhow::Result<_> {
+            if globs.is_empty() {
+                return Ok(None);
+            }
+            let mut builder = GlobSetBuilder::new();
+            for glob in globs {
+                builder.add(Glob::new(&root.join(glob.glob()).to_string_lossy())?);
+            }
+            Ok(Some(builder.build()?))
+        };
+        let include = build_glob_set(&self.include, &self.root)?;
+        let exclude = build_glob_set(&self.exclude, &self.root)?;
+        let file_names = build_glob_set(&self.file_name_globs, Path::new(""))?;
+        let paths = self
+            .paths
+            .clone()
+            .unwrap_or_else(|| vec![self.root.clone()]);
+        let mut walk_builder = WalkBuilder::new(paths.first().unwrap_or(&self.root));
+        for path in paths.iter().skip(1) {
+            walk_builder.add(path);
+        }
+        walk_builder
+            .standard_filters(!self.include_ignored)
+            .hidden(!self.include_hidden)
+            .filter_entry(|entry| !entry.path().ends_with(".git"));
+        let is_match = |glob_set: &Option<GlobSet>, path: &Path| {
+            glob_set.as_ref().map(|glob_set| glob_set.is_match(path))
+        };
+        let file_filter: Arc<FileFilter> = Arc::new(move |path: &Path| {
+            is_match(&include, path).unwrap_or(true)
+                && !is_match(&exclude, path).unwrap_or(false)
+                && path
+                    .file_name()
+                    .and_then(|file_name| is_match(&file_names, Path::new(file_name)))
+                    .unwrap_or(true)
+        });
+        Ok((walk_builder, file_filter))
+    }
+
     pub fn run_with_search(
         self,
         enable_tree_sitter: bool,
@@ -36,9 +150,16 @@ impl WalkBuilderConfig {
         get_ranges: Arc<GetRange>,
     ) -> anyhow::Result<()> {
         let sender = reorder_batches(send_match);
+        let progress = self.progress.clone();
         self.run_async(
             enable_tree_sitter,
             Arc::new(move |path_index, path, buffer| {
+                // An empty batch is still sent for every file,
+                // because `reorder_batches` expects contiguous indices
+                if progress.is_cancelled() {
+                    let _ = sender.send((path_index, Vec::new()));
+                    return;
+                }
                 let matches = get_ranges(&buffer)
                     .into_iter()
                     .filter_map(|range| {
@@ -56,6 +177,7 @@ impl WalkBuilderConfig {
                         None
                     })
                     .collect_vec();
+                progress.add_matches(matches.len());
                 let _ = sender.send((path_index, matches));
             }),
         )
@@ -64,55 +186,29 @@ impl WalkBuilderConfig {
         self,
         f: Box<dyn Fn(PathBuf, Sender<T>) -> anyhow::Result<()> + Send + Sync>,
     ) -> anyhow::Result<Vec<T>> {
-        let WalkBuilderConfig {
-            root,
-            include,
-            exclude,
-        } = self;
         let (sender, receiver) = std::sync::mpsc::channel();
-        let build_matcher = |glob: Option<&Glob>| -> anyhow::Result<_> {
-            let pattern = if let Some(glob) = glob {
-                Some(Glob::new(&root.join(glob.glob()).to_string_lossy())?.compile_matcher())
-            } else {
-                None
-            };
-            Ok(Box::new(move |path: &str| {
-                pattern.as_ref().map(|pattern| pattern.is_match(path))
-            }))
-        };
-        let include_match = build_matcher(include.as_ref())?;
-        let exclude_match = build_matcher(exclude.as_ref())?;
-        WalkBuilder::new(root)
-            .filter_entry(move |entry| {
-                let path = entry.path().display().to_string();
-
-                entry
-                    .file_type()
-                    .map(|file_type| !file_type.is_file())
-                    .unwrap_or(false)
-                    || (include_match(&path).unwrap_or(true)
-                        && !exclude_match(&path).unwrap_or(false))
-            })
-            .hidden(false)
-            .build_parallel()
-            .run(|| {
-                Box::new(|path| {
-                    if let Ok(path) = path {
-                        if path
-                            .file_type()
-                            .is_some_and(|file_type| file_type.is_file())
-                        {
-                            let path = path.path().into();
-                            if let Err(error) = f(path, sender.clone()) {
-                                log::error!("sender.send {error:?}");
-                            }
-                        } else if path.path().ends_with(".git") {
-                            return WalkState::Skip;
+        let (walk_builder, file_filter) = self.walk_builder()?;
+        walk_builder.build_parallel().run(|| {
+            Box::new(|path| {
+                if self.progress.is_cancelled() {
+                    return WalkState::Quit;
+                }
+                if let Ok(path) = path {
+                    if path
+                        .file_type()
+                        .is_some_and(|file_type| file_type.is_file())
+                        && file_filter(path.path())
+                    {
+                        let path = path.path().into();
+                        if let Err(error) = f(path, sender.clone()) {
+                            log::error!("sender.send {error:?}");
                         }
+                        self.progress.scanned_files.fetch_add(1, Ordering::Relaxed);
                     }
-                    WalkState::Continue
-                })
-            });
+                }
+                WalkState::Continue
+            })
+        });
         {
             // This line is necessary to prevent deadlock
             // See https://stackoverflow.com/a/71413508/6587634
@@ -129,47 +225,34 @@ impl WalkBuilderConfig {
             dyn Fn(/*file index (0 = first file)*/ usize, AbsolutePath, Buffer) + Send + Sync,
         >,
     ) -> anyhow::Result<()> {
-        let build_matcher = |glob: Option<&Glob>| -> anyhow::Result<_> {
-            let pattern = if let Some(glob) = glob {
-                Some(Glob::new(&self.root.join(glob.glob()).to_string_lossy())?.compile_matcher())
-            } else {
-                None
-            };
-            Ok(Box::new(move |path: &str| {
-                pattern.as_ref().map(|pattern| pattern.is_match(path))
-            }))
-        };
-        let include_match = Arc::new(build_matcher(self.include.as_ref())?);
-        let exclude_match = Arc::new(build_matcher(self.exclude.as_ref())?);
+        let (mut walk_builder, file_filter) = self.walk_builder()?;
         std::thread::spawn(move || {
-            WalkBuilder::new(self.root)
+            walk_builder
                 // NOTE: `sort_by_file_path` is crucial for
                 // `buffer_entries` to work correctly.
                 .sort_by_file_path(|a, b| a.cmp(b))
-                .filter_entry(move |entry| {
-                    let path = entry.path().display().to_string();
-
-                    !path.ends_with(".git")
-                        && (entry
-                            .file_type()
-                            .map(|file_type| !file_type.is_file())
-                            .unwrap_or(false)
-                            || (include_match(&path).unwrap_or(true)
-                                && !exclude_match(&path).unwrap_or(false)))
-                })
-                .hidden(false)
                 .build()
+                .take_while(|_| !self.progress.is_cancelled())
                 .filter_map(|path| path.ok())
                 .filter(|path| {
                     path.file_type()
                         .is_some_and(|file_type| file_type.is_file())
+                        && file_filter(path.path())
                 })
                 .filter_map(|path| {
                     let path: PathBuf = path.path().into();
                     if let Ok(path) = path.try_into() {
+                        let buffer = match self.buffer_contents.get(&path) {
+                            Some(content) => Ok(Buffer::from_content(
+                                &path,
+                                content.clone(),
+                                enable_tree_sitter,
+                            )),
+                            None => Buffer::from_path(&path, enable_tree_sitter),
+                        };
                         // Tree-sitter should be disabled whenever possible during
                         // global search, because it will slow down the operation tremendously
-                        if let Ok(buffer) = Buffer::from_path(&path, enable_tree_sitter) {
+                        if let Ok(buffer) = buffer {
                             if !enable_tree_sitter {
                                 debug_assert!(buffer.tree().is_none());
                             }
@@ -180,7 +263,10 @@ impl WalkBuilderConfig {
                 })
                 .enumerate()
                 .par_bridge()
-                .for_each(|(index, (path, buffer))| on_visit_buffer(index, path, buffer));
+                .for_each(|(index, (path, buffer))| {
+                    on_visit_buffer(index, path, buffer);
+                    self.progress.scanned_files.fetch_add(1, Ordering::Relaxed);
+                });
         });
         Ok(())
     }
@@ -237,14 +323,20 @@ mod test_walk_builder_config {
 
     use itertools::Itertools;
 
-    use super::WalkBuilderConfig;
+    use super::{WalkBuilderConfig, WalkProgress};
 
     #[test]
     fn test_exclude() -> anyhow::Result<()> {
         let config = WalkBuilderConfig {
             root: "./mock_repos/rust1".into(),
-            include: None,
-            exclude: Some(Glob::new("src/*.rs")?),
+            include: Vec::new(),
+            exclude: vec![Glob::new("src/*.rs")?],
+            include_hidden: true,
+            include_ignored: false,
+            file_name_globs: Vec::new(),
+            paths: None,
+            buffer_contents: Default::default(),
+            progress: Default::default(),
         };
         let paths = config.run(Box::new(|path, sender| {
             sender.send(path).unwrap();
@@ -266,8 +358,14 @@ mod test_walk_builder_config {
     fn test_include() -> anyhow::Result<()> {
         let config = WalkBuilderConfig {
             root: "./mock_repos/rust1".into(),
-            include: Some(Glob::new("src/*.rs")?),
-            exclude: None,
+            include: vec![Glob::new("src/*.rs")?],
+            exclude: Vec::new(),
+            include_hidden: true,
+            include_ignored: false,
+            file_name_globs: Vec::new(),
+            paths: None,
+            buffer_contents: Default::default(),
+            progress: Default::default(),
         };
         let paths = config.run(Box::new(|path, sender| {
             sender.send(path).unwrap();
@@ -282,6 +380,89 @@ mod test_walk_builder_config {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_scope() -> anyhow::Result<()> {
+        let run = |config: WalkBuilderConfig| -> anyhow::Result<Vec<PathBuf>> {
+            Ok(config
+                .run(Box::new(|path, sender| {
+                    sender.send(path).unwrap();
+                    Ok(())
+                }))?
+                .into_iter()
+                .sorted()
+                .collect_vec())
+        };
+        let config = || -> anyhow::Result<WalkBuilderConfig> {
+            Ok(WalkBuilderConfig {
+                root: "./mock_repos/rust1".into(),
+                include: vec![Glob::new("src/*")?, Glob::new("*.toml")?],
+                exclude: vec![Glob::new("src/main.rs")?],
+                include_hidden: false,
+                include_ignored: false,
+                file_name_globs: Vec::new(),
+                paths: None,
+                buffer_contents: Default::default(),
+                progress: Default::default(),
+            })
+        };
+        assert_eq!(
+            run(config()?)?,
+            [
+                PathBuf::from("./mock_repos/rust1/Cargo.toml"),
+                PathBuf::from("./mock_repos/rust1/src/foo.rs"),
+                PathBuf::from("./mock_repos/rust1/src/hello.ts")
+            ]
+        );
+        assert_eq!(
+            run(WalkBuilderConfig {
+                file_name_globs: vec![Glob::new("*.ts")?],
+                ..config()?
+            })?,
+            [PathBuf::from("./mock_repos/rust1/src/hello.ts")]
+        );
+        assert_eq!(
+            run(WalkBuilderConfig {
+                paths: Some(vec![
+                    "./mock_repos/rust1/Cargo.lock".into(),
+                    "./mock_repos/rust1/src/foo.rs".into()
+                ]),
+                ..config()?
+            })?,
+            [PathBuf::from("./mock_repos/rust1/src/foo.rs")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_progress() -> anyhow::Result<()> {
+        let config = |progress: WalkProgress| -> anyhow::Result<WalkBuilderConfig> {
+            Ok(WalkBuilderConfig {
+                root: "./mock_repos/rust1".into(),
+                include: vec![Glob::new("src/*.rs")?],
+                exclude: Vec::new(),
+                include_hidden: true,
+                include_ignored: false,
+                file_name_globs: Vec::new(),
+                paths: None,
+                buffer_contents: Default::default(),
+                progress,
+            })
+        };
+        let run = |config: WalkBuilderConfig| {
+            config.run(Box::new(|path, sender| {
+                sender.send(path).unwrap();
+                Ok(())
+            }))
+        };
+        let progress = WalkProgress::default();
+        assert_eq!(run(config(progress.clone())?)?.len(), 2);
+        assert_eq!(progress.scanned_files(), 2);
+
+        progress.cancel();
+        assert!(run(config(progress)?)?.is_empty());
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+use itertools::Itertools;
+use shared::absolute_path::AbsolutePath;
+
+use crate::{
+    components::suggestive_editor::Info,
+    git::hunk::Hunk,
+    lsp::completion::PositionalEdit,
+    position::Position,
+    quickfix_list::{Location, QuickfixListItem},
+    selection::CharIndex,
+};
+
+/// The changes of a global replacement that are yet to be applied,
+/// so that they can be reviewed and excluded one by one.
+#[derive(Debug, Clone)]
+pub struct ReplacePreview {
+    files: Vec<ReplacePreviewFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplacePreviewFile {
+    pub path: AbsolutePath,
+    /// The content that the changes are computed against.
+    pub content: String,
+    changes: Vec<ReplacePreviewChange>,
+}
+
+#[derive(Debug, Clone)]
+struct ReplacePreviewChange {
+    location: Location,
+    /// The byte range of the match in `ReplacePreviewFile::content`.
+    byte_range: Range<usize>,
+    new_text: String,
+    info: Info,
+}
+
+impl ReplacePreview {
+    /// `files` are tuples of path, current content and replacements,
+    /// where each replacement is the byte range of a match and its new text.
+    /// The replacements of a file are expected to be sorted and non-overlapping.
+    pub fn new(files: Vec<(AbsolutePath, String, Vec<(Range<usize>, String)>)>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|(path, content, replacements)| {
+                    let changes = changes(&path, &content, replacements);
+                    ReplacePreviewFile {
+                        path,
+                        content,
+                        changes,
+                    }
+                })
+                .filter(|file| !file.changes.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn quickfix_list_items(&self) -> Vec<QuickfixListItem> {
+        self.files
+            .iter()
+            .flat_map(|file| &file.changes)
+            .map(|change| {
+                QuickfixListItem::new(change.location.clone(), Some(change.info.clone()), None)
+            })
+            .collect()
+    }
+
+    /// Excludes the change at `location`, or all changes of its file if `whole_file` is true.
+    pub fn exclude(&mut self, location: &Location, whole_file: bool) {
+        for file in self
+            .files
+            .iter_mut()
+            .filter(|file| file.path == location.path)
+        {
+            file.changes.retain(|change| {
+                !whole_file && change.location.range.start != location.range.start
+            });
+        }
+        self.files.retain(|file| !file.changes.is_empty());
+    }
+
+    pub fn into_files(self) -> Vec<ReplacePreviewFile> {
+        self.files
+    }
+}
+
+impl ReplacePreviewFile {
+    pub fn changes_count(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// The edits are of the matches, so that they can be applied to the buffer
+    /// as long as its content is still `self.content`.
+    pub fn positional_edits(&self) -> Vec<PositionalEdit> {
+        let position = |byte: usize| {
+            let line_start = self.content[..byte]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            Position::new(
+                self.content[..line_start].matches('\n').count(),
+                self.content[line_start..byte].chars().count(),
+            )
+        };
+        self.changes
+            .iter()
+            .map(|change| PositionalEdit {
+                range: position(change.byte_range.start)..position(change.byte_range.end),
+                new_text: change.new_text.clone(),
+            })
+            .collect()
+    }
+}
+
+fn changes(
+    path: &AbsolutePath,
+    content: &str,
+    replacements: Vec<(Range<usize>, String)>,
+) -> Vec<ReplacePreviewChange> {
+    replacements
+        .into_iter()
+        .filter(|(byte_range, new_text)| content[byte_range.clone()] != **new_text)
+        .map(|(byte_range, new_text)| {
+            // The diff shows the whole lines of the match, without their last line break
+            let lines_start = content[..byte_range.start]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            let lines_end = content[byte_range.end..]
+                .find('\n')
+                .map_or(content.len(), |index| byte_range.end + index);
+            let old_lines = &content[lines_start..lines_end];
+            let new_lines = format!(
+                "{}{new_text}{}",
+                &content[lines_start..byte_range.start],
+                &content[byte_range.end..lines_end]
+            );
+            let (diff, decorations) = Hunk::get_detailed_hunk(old_lines, &new_lines);
+            let char_index = |byte: usize| CharIndex(content[..byte].chars().count());
+            ReplacePreviewChange {
+                location: Location {
+                    path: path.clone(),
+                    range: (char_index(byte_range.start)..char_index(byte_range.end)).into(),
+                },
+                byte_range,
+                new_text,
+                info: Info::new("Replace Preview".to_string(), diff).set_decorations(decorations),
+            }
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod test_replace_preview {
+    use super::*;
+
+    #[test]
+    fn exclude_changes() -> anyhow::Result<()> {
+        let path: AbsolutePath = std::env::current_dir()?.join("Cargo.toml").try_into()?;
+        let mut preview = ReplacePreview::new(vec![(
+            path.clone(),
+            "foo\nbar\nfoo foo\n".to_string(),
+            vec![
+                (0..3, "spam".to_string()),
+                (8..11, "spam".to_string()),
+                (12..15, "spam".to_string()),
+            ],
+        )]);
+        let files = preview.clone().into_files();
+        assert_eq!(
+            files[0]
+                .positional_edits()
+                .into_iter()
+                .map(|edit| (edit.range, edit.new_text))
+                .collect_vec(),
+            [
+                (Position::new(0, 0)..Position::new(0, 3), "spam".to_string()),
+                (Position::new(2, 0)..Position::new(2, 3), "spam".to_string()),
+                (Position::new(2, 4)..Position::new(2, 7), "spam".to_string())
+            ]
+        );
+
+        let locations = preview
+            .quickfix_list_items()
+            .into_iter()
+            .map(|item| item.location().clone())
+            .collect_vec();
+        preview.exclude(&locations[0], false);
+        assert_eq!(preview.clone().into_files()[0].changes_count(), 2);
+        preview.exclude(&locations[2], false);
+        assert_eq!(preview.clone().into_files()[0].changes_count(), 1);
+        preview.exclude(&locations[1], true);
+        assert!(preview.is_empty());
+        Ok(())
+    }
+}
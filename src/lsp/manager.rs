@@ -73,6 +73,13 @@ impl LspManager {
         )
     }
 
+    /// Returns true if a language server is started for the language of `path`.
+    pub fn has_server(&self, path: &AbsolutePath) -> bool {
+        crate::config::from_path(path)
+            .and_then(|language| language.id())
+            .is_some_and(|id| self.lsp_server_process_channels.contains_key(&id))
+    }
+
     /// Open file can do one of the following:
     /// 1. Start a new LSP server process if it is not started yet.
     /// 2. Notify the LSP server process that a new file is opened.
@@ -147,6 +147,9 @@ pub enum FromEditor {
     TextDocumentDidSave {
         file_path: AbsolutePath,
     },
+    TextDocumentDidClose {
+        file_path: AbsolutePath,
+    },
     TextDocumentPrepareRename(RequestParams),
     TextDocumentRename {
         params: RequestParams,
@@ -231,7 +234,8 @@ impl LspServerProcessChannel {
     }
 
     pub fn document_did_open(&self, path: AbsolutePath) -> Result<(), anyhow::Error> {
-        let content = path.read()?;
+        let content = crate::editorconfig::EditorConfig::from_path(&path)
+            .decode(std::fs::read(path.to_path_buf())?)?;
         let Some(language_id) = self.language.id() else {
             return Ok(());
         };
@@ -1253,6 +1257,14 @@ impl LspServerProcess {
         )
     }
 
+    fn text_document_did_close(&mut self, file_path: AbsolutePath) -> Result<(), anyhow::Error> {
+        self.send_notification::<lsp_notification!("textDocument/didClose")>(
+            DidCloseTextDocumentParams {
+                text_document: path_buf_to_text_document_identifier(file_path)?,
+            },
+        )
+    }
+
     fn workspace_did_rename_files(
         &mut self,
         old: AbsolutePath,
@@ -1717,6 +1729,9 @@ impl LspServerProcess {
                 content,
             } => self.text_document_did_change(file_path, version, content),
             FromEditor::TextDocumentDidSave { file_path } => self.text_document_did_save(file_path),
+            FromEditor::TextDocumentDidClose { file_path } => {
+                self.text_document_did_close(file_path)
+            }
             FromEditor::TextDocumentSignatureHelp(params) => {
                 self.text_document_signature_help(params)
             }
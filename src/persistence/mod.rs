@@ -20,12 +20,13 @@ pub mod _00001;
 pub mod _00002;
 pub mod _00003;
 pub mod _00004;
+pub mod _00005;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Version(pub u8);
 
-pub type Root = _00004::Root;
-pub type WorkspaceSession = _00004::WorkspaceSession;
+pub type Root = _00005::Root;
+pub type WorkspaceSession = _00005::WorkspaceSession;
 
 pub struct Persistence {
     path: PathBuf,
@@ -114,6 +115,16 @@ impl Persistence {
             .get(working_directory)
             .map(|session| session.prompt_histories.clone())
     }
+
+    pub fn get_global_search_scope_presets(
+        &self,
+        working_directory: &Path,
+    ) -> Option<HashMap<String, String>> {
+        self.root
+            .workspace_sessions
+            .get(working_directory)
+            .map(|session| session.global_search_scope_presets.clone())
+    }
 }
 
 pub trait Migration:
@@ -304,6 +304,18 @@ impl QuickfixList {
         self.dropdown.current_item_index
     }
 
+    pub(crate) fn current_location(&self) -> Option<Location> {
+        self.dropdown
+            .current_item()?
+            .dispatches
+            .into_vec()
+            .into_iter()
+            .find_map(|dispatch| match dispatch {
+                crate::app::Dispatch::GotoLocation(location) => Some(location),
+                _ => None,
+            })
+    }
+
     pub fn dropdown_items(&self) -> Vec<DropdownItem> {
         self.dropdown.items()
     }
@@ -436,6 +448,8 @@ pub enum QuickfixListType {
     Diagnostic(DiagnosticSeverityRange),
     Items(Vec<QuickfixListItem>),
     Mark,
+    /// The changes of `crate::list::replace_preview::ReplacePreview`.
+    ReplacePreview(Vec<QuickfixListItem>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
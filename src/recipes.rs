@@ -1919,7 +1919,7 @@ pub(crate) fn run(path: Option<CanonicalizedPath>) -> anyhow::Result<()> {
         }
     });
 
-    app.run(path)
+    app.run(path, None)
         .map_err(|error| anyhow::anyhow!("screen.run {:?}", error))?;
 
     println!("Good bye!");
@@ -1952,7 +1952,7 @@ pub(crate) fn run(path: Option<CanonicalizedPath>) -> anyhow::Result<()> {
         }
     });
 
-    app.run(path)
+    app.run(path, None)
         .map_err(|error| anyhow::anyhow!("screen.run {:?}", error))?;
 }"#
                     )]),
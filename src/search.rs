@@ -1,17 +1,20 @@
-//  TODO: add fuzzy search which search by line, we can use nucleo for that
 use globset::Glob;
 use itertools::Itertools;
 
 use crate::{
-    context::{GlobalSearchConfig, LocalSearchConfig, LocalSearchConfigMode},
+    app::GlobalSearchScopeUpdate,
+    context::{
+        FuzzyCandidate, GlobalSearchConfig, GlobalSearchLimit, GlobalSearchScope,
+        LocalSearchConfig, LocalSearchConfigMode,
+    },
     list::grep::RegexConfig,
 };
 
 pub fn parse_search_config(input: &str) -> anyhow::Result<GlobalSearchConfig> {
     let default = || {
         Ok(GlobalSearchConfig {
-            include_glob: None,
-            exclude_glob: None,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
             local_config: LocalSearchConfig::default()
                 .set_search(input.to_string())
                 .clone(),
@@ -24,8 +27,13 @@ pub fn parse_search_config(input: &str) -> anyhow::Result<GlobalSearchConfig> {
         .collect_vec();
     let mode_chars_count = mode_chars.len();
     let mode_str: String = mode_chars.into_iter().map(|c| c.to_string()).join("");
+    // The multi-line option can be appended to any regex mode, for example `rm` or `rcm`
+    let (mode_str, multi_line) = match mode_str.strip_suffix('m') {
+        Some(regex_mode) if regex_mode.starts_with('r') => (regex_mode, true),
+        _ => (mode_str.as_str(), false),
+    };
     let mode = {
-        match mode_str.as_str() {
+        match mode_str {
             "l" => LocalSearchConfigMode::Regex(RegexConfig::literal()),
             "c" => LocalSearchConfigMode::Regex(RegexConfig::case_sensitive()),
             "w" => LocalSearchConfigMode::Regex(RegexConfig::match_whole_word()),
@@ -35,22 +43,36 @@ pub fn parse_search_config(input: &str) -> anyhow::Result<GlobalSearchConfig> {
                 escaped: false,
                 match_whole_word: false,
                 case_sensitive: true,
+                multi_line: false,
             }),
             "rw" => LocalSearchConfigMode::Regex(RegexConfig {
                 escaped: false,
                 match_whole_word: true,
                 case_sensitive: false,
+                multi_line: false,
             }),
             "rs" | "rcw" | "rwc" => LocalSearchConfigMode::Regex(RegexConfig {
                 escaped: false,
                 match_whole_word: true,
                 case_sensitive: true,
+                multi_line: false,
             }),
             "n" => LocalSearchConfigMode::NamingConventionAgnostic,
             "a" => LocalSearchConfigMode::AstGrep,
+            "f" => LocalSearchConfigMode::Fuzzy(FuzzyCandidate::Line),
+            "fs" => LocalSearchConfigMode::Fuzzy(FuzzyCandidate::SyntaxNode),
             _ => return default(),
         }
     };
+    let mode = match mode {
+        LocalSearchConfigMode::Regex(regex_config) if multi_line => {
+            LocalSearchConfigMode::Regex(RegexConfig {
+                multi_line,
+                ..regex_config
+            })
+        }
+        mode => mode,
+    };
     let Some(separator) = chars.get(mode_chars_count) else {
         return default();
     };
@@ -89,19 +111,18 @@ pub fn parse_search_config(input: &str) -> anyhow::Result<GlobalSearchConfig> {
         }
     };
     let (last_index, replacement) = parse_next_component(last_index);
-    let (last_index, include_glob) = parse_next_component(last_index);
-    let (_, exclude_glob) = parse_next_component(last_index);
+    let (last_index, include_globs) = parse_next_component(last_index);
+    let (_, exclude_globs) = parse_next_component(last_index);
 
-    let make_glob = |input: &str| {
-        if input.is_empty() {
-            Ok(None)
-        } else {
-            Some(Glob::new(input)).transpose()
-        }
+    let make_globs = |input: &str| {
+        split_globs(input)
+            .into_iter()
+            .map(Glob::new)
+            .collect::<Result<Vec<_>, _>>()
     };
     Ok(GlobalSearchConfig {
-        include_glob: make_glob(&include_glob)?,
-        exclude_glob: make_glob(&exclude_glob)?,
+        include_globs: make_globs(&include_globs)?,
+        exclude_globs: make_globs(&exclude_globs)?,
         local_config: LocalSearchConfig::new(mode)
             .set_search(search)
             .set_replacment(replacement)
@@ -109,6 +130,86 @@ pub fn parse_search_config(input: &str) -> anyhow::Result<GlobalSearchConfig> {
     })
 }
 
+/// Splits a list of comma-separated globs,
+/// except at the commas of alternatives such as `{a,b}` and classes such as `[,;]`.
+fn split_globs(input: &str) -> Vec<&str> {
+    let mut globs = Vec::new();
+    let mut depth = 0usize;
+    let mut in_class = false;
+    let mut start = 0;
+    for (index, c) in input.char_indices() {
+        match c {
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            '{' if !in_class => depth += 1,
+            '}' if !in_class => depth = depth.saturating_sub(1),
+            ',' if !in_class && depth == 0 => {
+                globs.push(&input[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    globs.push(&input[start..]);
+    globs
+        .into_iter()
+        .map(|glob| glob.trim())
+        .filter(|glob| !glob.is_empty())
+        .collect()
+}
+
+/// Parses the input of the global search scope prompt.
+///
+/// A preset is referred to by a word that starts with `@`:
+/// it is loaded if it is the only word, otherwise the scope given by the other words is
+/// saved as the preset.
+pub fn parse_search_scope_update(input: &str) -> anyhow::Result<GlobalSearchScopeUpdate> {
+    let (presets, settings): (Vec<_>, Vec<_>) = input
+        .split_whitespace()
+        .partition(|word| word.starts_with('@'));
+    let scope = parse_search_scope(&settings.join(" "))?;
+    let name = match presets.as_slice() {
+        [] => return Ok(GlobalSearchScopeUpdate::Scope(scope)),
+        ["@"] => return Err(anyhow::anyhow!("The name of the preset is empty.")),
+        [preset] => preset[1..].to_string(),
+        _ => return Err(anyhow::anyhow!("Only one preset can be given.")),
+    };
+    Ok(if settings.is_empty() {
+        GlobalSearchScopeUpdate::LoadPreset(name)
+    } else {
+        GlobalSearchScopeUpdate::SaveAsPreset { name, scope }
+    })
+}
+
+/// Parses space-separated settings of `GlobalSearchScope`, which are applied on top of the
+/// default scope. This is the inverse of `GlobalSearchScope::display`.
+pub fn parse_search_scope(input: &str) -> anyhow::Result<GlobalSearchScope> {
+    let mut scope = GlobalSearchScope::default();
+    for setting in input.split_whitespace() {
+        match setting {
+            "+hidden" => scope.include_hidden = true,
+            "-hidden" => scope.include_hidden = false,
+            "+ignored" => scope.include_ignored = true,
+            "-ignored" => scope.include_ignored = false,
+            "root" => scope.limit = GlobalSearchLimit::WorkingDirectory,
+            "dir" => scope.limit = GlobalSearchLimit::CurrentDirectory,
+            "marked" => scope.limit = GlobalSearchLimit::MarkedFiles,
+            _ => {
+                let Some(file_types) = setting.strip_prefix("type:") else {
+                    return Err(anyhow::anyhow!("Unknown search scope setting: {setting:?}"));
+                };
+                scope.file_types.extend(
+                    file_types
+                        .split(',')
+                        .filter(|file_type| !file_type.is_empty())
+                        .map(|file_type| file_type.to_string()),
+                )
+            }
+        }
+    }
+    Ok(scope)
+}
+
 #[cfg(test)]
 mod test_parse_search_config {
     use super::*;
@@ -135,6 +236,7 @@ mod test_parse_search_config {
                 escaped: false,
                 match_whole_word: false,
                 case_sensitive: false,
+                multi_line: false,
             }),
         );
         run_test(
@@ -143,6 +245,7 @@ mod test_parse_search_config {
                 escaped: false,
                 match_whole_word: false,
                 case_sensitive: true,
+                multi_line: false,
             }),
         );
         run_test(
@@ -151,6 +254,7 @@ mod test_parse_search_config {
                 escaped: false,
                 match_whole_word: true,
                 case_sensitive: false,
+                multi_line: false,
             }),
         );
         run_test(
@@ -159,6 +263,7 @@ mod test_parse_search_config {
                 escaped: false,
                 match_whole_word: true,
                 case_sensitive: true,
+                multi_line: false,
             }),
         );
         run_test(
@@ -167,6 +272,7 @@ mod test_parse_search_config {
                 escaped: false,
                 match_whole_word: true,
                 case_sensitive: true,
+                multi_line: false,
             }),
         );
         run_test(
@@ -175,10 +281,29 @@ mod test_parse_search_config {
                 escaped: false,
                 match_whole_word: true,
                 case_sensitive: true,
+                multi_line: false,
+            }),
+        );
+        run_test(
+            "rm",
+            Regex(RegexConfig {
+                multi_line: true,
+                ..RegexConfig::regex()
+            }),
+        );
+        run_test(
+            "rcwm",
+            Regex(RegexConfig {
+                escaped: false,
+                match_whole_word: true,
+                case_sensitive: true,
+                multi_line: true,
             }),
         );
         run_test("n", NamingConventionAgnostic);
         run_test("a", AstGrep);
+        run_test("f", Fuzzy(FuzzyCandidate::Line));
+        run_test("fs", Fuzzy(FuzzyCandidate::SyntaxNode));
     }
 
     #[test]
@@ -227,11 +352,38 @@ mod test_parse_search_config {
         assert_eq!(actual.replacement(), "");
     }
 
+    #[test]
+    fn multi_line_option_is_only_for_regex_modes() {
+        let actual = parse_search_config("lm hello").unwrap().local_config;
+        assert_eq!(actual.mode, Regex(RegexConfig::literal()));
+        assert_eq!(actual.search(), "lm hello");
+    }
+
     #[test]
     fn include_glob_exclude_glob() {
         let actual = parse_search_config("a/search/replacement/*.include/*.exclude").unwrap();
-        assert_eq!(actual.include_glob, Some(Glob::new("*.include").unwrap()));
-        assert_eq!(actual.exclude_glob, Some(Glob::new("*.exclude").unwrap()));
+        assert_eq!(actual.include_globs, [Glob::new("*.include").unwrap()]);
+        assert_eq!(actual.exclude_globs, [Glob::new("*.exclude").unwrap()]);
+    }
+
+    #[test]
+    fn multiple_include_globs_and_exclude_globs() {
+        let actual =
+            parse_search_config("l;search;;src/**, **.{js,jsx};**/*.[,]*,target/**").unwrap();
+        assert_eq!(
+            actual.include_globs,
+            [
+                Glob::new("src/**").unwrap(),
+                Glob::new("**.{js,jsx}").unwrap()
+            ]
+        );
+        assert_eq!(
+            actual.exclude_globs,
+            [
+                Glob::new("**/*.[,]*").unwrap(),
+                Glob::new("target/**").unwrap()
+            ]
+        );
     }
 
     #[test]
@@ -240,3 +392,47 @@ mod test_parse_search_config {
         assert_eq!(actual.local_config.search(), "// hello");
     }
 }
+
+#[cfg(test)]
+mod test_parse_search_scope {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() -> anyhow::Result<()> {
+        let scope = parse_search_scope("-hidden +ignored type:rust type:ts,toml marked")?;
+        assert_eq!(
+            scope,
+            GlobalSearchScope {
+                include_hidden: false,
+                include_ignored: true,
+                file_types: vec!["rust".to_string(), "ts".to_string(), "toml".to_string()],
+                limit: GlobalSearchLimit::MarkedFiles,
+            }
+        );
+        assert_eq!(parse_search_scope(&scope.display())?, scope);
+        assert_eq!(GlobalSearchScope::default().display(), "");
+        assert!(parse_search_scope("hidden").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn presets() -> anyhow::Result<()> {
+        assert_eq!(
+            parse_search_scope_update("@web")?,
+            GlobalSearchScopeUpdate::LoadPreset("web".to_string())
+        );
+        assert_eq!(
+            parse_search_scope_update("type:ts @web dir")?,
+            GlobalSearchScopeUpdate::SaveAsPreset {
+                name: "web".to_string(),
+                scope: parse_search_scope("type:ts dir")?
+            }
+        );
+        assert_eq!(
+            parse_search_scope_update("")?,
+            GlobalSearchScopeUpdate::Scope(GlobalSearchScope::default())
+        );
+        assert!(parse_search_scope_update("@a @b").is_err());
+        Ok(())
+    }
+}
@@ -15,7 +15,10 @@ use crate::{
     non_empty_extensions::{NonEmptyTryCollectOption, NonEmptyTryCollectResult},
     position::Position,
     quickfix_list::{DiagnosticSeverityRange, QuickfixListItem},
-    selection_mode::{self, ApplyMovementResult, IterBased, PositionBased, SelectionModeParams},
+    selection_mode::{
+        self, ApplyMovementResult, IterBased, PatternKind, PositionBased, SelectionModeParams,
+        TextobjectKind,
+    },
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -464,12 +467,18 @@ pub enum SelectionMode {
     // Syntax-tree
     SyntaxNode,
     SyntaxNodeFine,
+    Textobject { kind: TextobjectKind, inside: bool },
 
     // LSP
     Diagnostic(DiagnosticSeverityRange),
 
     // Git
     GitHunk(crate::git::DiffMode),
+    MergeConflict,
+    // Blocks of lines determined by indentation, see `selection_mode::IndentationBlock`.
+    IndentationBlock,
+    // URLs, file paths or numbers, see `selection_mode::Pattern`.
+    Pattern(PatternKind),
 
     // Local quickfix
     LocalQuickfix { title: String },
@@ -479,6 +488,11 @@ pub enum SelectionMode {
     LineFull,
     BigWord,
     Paragraph,
+    Sentence,
+    // Sections, list items and fenced code blocks of Markdown.
+    Markdown,
+    // One selection per line of a rectangle, see `Editor::select_block`.
+    Block,
 }
 impl SelectionMode {
     pub fn is_node(&self) -> bool {
@@ -491,6 +505,7 @@ impl SelectionMode {
             SelectionMode::Line => "LINE".to_string(),
             SelectionMode::LineFull => "LINE*".to_string(),
             SelectionMode::Character => "CHAR".to_string(),
+            SelectionMode::Block => "BLOCK".to_string(),
             SelectionMode::Custom => "CUSTM".to_string(),
             SelectionMode::SyntaxNode => "NODE".to_string(),
             SelectionMode::SyntaxNodeFine => "NODE*".to_string(),
@@ -510,6 +525,14 @@ impl SelectionMode {
             SelectionMode::Word => "WORD".to_string(),
             SelectionMode::BigWord => "WORD*".to_string(),
             SelectionMode::Paragraph => "PARA".to_string(),
+            SelectionMode::Sentence => "SENT".to_string(),
+            SelectionMode::Markdown => "MD".to_string(),
+            SelectionMode::MergeConflict => "CONFLICT".to_string(),
+            SelectionMode::IndentationBlock => "INDENT".to_string(),
+            SelectionMode::Pattern(kind) => kind.display().to_string(),
+            SelectionMode::Textobject { kind, inside } => {
+                format!("{}{}", kind.display(), if *inside { "-IN" } else { "" })
+            }
         }
     }
 
@@ -533,7 +556,9 @@ impl SelectionMode {
             SelectionMode::BigWord => Box::new(PositionBased(selection_mode::BigWord)),
             SelectionMode::Line => Box::new(PositionBased(selection_mode::LineTrimmed)),
             SelectionMode::LineFull => Box::new(PositionBased(selection_mode::LineFull)),
-            SelectionMode::Character => Box::new(PositionBased(selection_mode::Character)),
+            SelectionMode::Character | SelectionMode::Block => {
+                Box::new(PositionBased(selection_mode::Character))
+            }
             SelectionMode::Custom => Box::new(IterBased(selection_mode::Custom::new(
                 current_selection.clone(),
             ))),
@@ -547,6 +572,9 @@ impl SelectionMode {
                 LocalSearchConfigMode::NamingConventionAgnostic => Box::new(IterBased(
                     selection_mode::NamingConventionAgnostic::new(search.search.clone()),
                 )),
+                LocalSearchConfigMode::Fuzzy(candidate) => Box::new(IterBased(
+                    selection_mode::Fuzzy::new(buffer, &search.search, candidate)?,
+                )),
             },
             SelectionMode::SyntaxNode => {
                 Box::new(IterBased(selection_mode::SyntaxNode { coarse: true }))
@@ -569,6 +597,25 @@ impl SelectionMode {
                 selection_mode::LocalQuickfix::new(params, quickfix_list_items),
             )),
             SelectionMode::Paragraph => Box::new(PositionBased(selection_mode::Paragraph)),
+            SelectionMode::Sentence => Box::new(IterBased(selection_mode::Sentence::new(buffer))),
+            SelectionMode::Markdown => Box::new(IterBased(selection_mode::Markdown::new(buffer)?)),
+            SelectionMode::MergeConflict => {
+                Box::new(IterBased(selection_mode::MergeConflict::new(buffer)))
+            }
+            SelectionMode::IndentationBlock => {
+                Box::new(IterBased(selection_mode::IndentationBlock::new(buffer)))
+            }
+            SelectionMode::Pattern(kind) => {
+                Box::new(IterBased(selection_mode::Pattern::new(buffer, *kind)))
+            }
+            SelectionMode::Textobject { kind, inside } => {
+                Box::new(IterBased(selection_mode::Textobject::new(
+                    buffer,
+                    current_selection.to_char_index(cursor_direction),
+                    *kind,
+                    *inside,
+                )?))
+            }
         })
     }
 
@@ -605,6 +652,11 @@ pub struct Selection {
 
     /// For example, used for Diagnostic and Git Hunk
     pub info: Option<Info>,
+
+    /// The number of spaces that are virtually present before this selection.
+    /// Used by block selections on lines that are shorter than the block,
+    /// so that text inserted on such lines is padded to the column of the block.
+    pub virtual_space: usize,
 }
 
 impl Selection {
@@ -650,6 +702,7 @@ impl Selection {
             range: (CharIndex(0)..CharIndex(0)).into(),
             initial_range: None,
             info: None,
+            virtual_space: 0,
         }
     }
 
@@ -703,6 +756,7 @@ impl Selection {
             range,
             initial_range: self.initial_range,
             info: self.info.clone(),
+            virtual_space: self.virtual_space,
         }
     }
 
@@ -711,7 +765,28 @@ impl Selection {
     }
 
     pub fn set_range(self, range: CharIndexRange) -> Selection {
-        Selection { range, ..self }
+        // The virtual space only belongs to the position where it was created
+        let virtual_space = if range == self.range {
+            self.virtual_space
+        } else {
+            0
+        };
+        Selection {
+            range,
+            virtual_space,
+            ..self
+        }
+    }
+
+    pub fn set_virtual_space(self, virtual_space: usize) -> Selection {
+        Selection {
+            virtual_space,
+            ..self
+        }
+    }
+
+    pub fn virtual_space(&self) -> usize {
+        self.virtual_space
     }
 
     /// WARNING: You should always use `extended_range` unless you know what you are doing
@@ -744,12 +819,14 @@ impl Selection {
             range,
             info,
             initial_range,
+            virtual_space,
         } = self;
 
         Some(Self {
             range: range.apply_edit(edit)?,
             initial_range: initial_range.and_then(|range| range.apply_edit(edit)),
             info,
+            virtual_space,
         })
     }
 
@@ -796,6 +873,7 @@ impl Add<usize> for Selection {
             range: (self.range.start + rhs..self.range.end + rhs).into(),
             initial_range: self.initial_range,
             info: self.info,
+            virtual_space: self.virtual_space,
         }
     }
 }
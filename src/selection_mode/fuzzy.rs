@@ -0,0 +1,148 @@
+use std::{cmp::Reverse, ops::Range};
+
+use itertools::Itertools;
+use nucleo_matcher::{
+    pattern::{CaseMatching, Normalization, Pattern},
+    Config, Matcher, Utf32Str,
+};
+
+use crate::{buffer::Buffer, components::editor::IfCurrentNotFound, context::FuzzyCandidate};
+
+use super::{ByteRange, IterBasedSelectionMode, SelectionModeParams};
+
+/// The lines or syntax nodes that match the search fuzzily,
+/// using the same matcher as the dropdowns of the prompts.
+///
+/// The matches are iterated from the best to the worst,
+/// so that the best ones are labelled first by `ShowJumps`.
+pub struct Fuzzy {
+    ranges: Vec<Range<usize>>,
+}
+
+impl Fuzzy {
+    pub fn new(buffer: &Buffer, search: &str, candidate: FuzzyCandidate) -> anyhow::Result<Self> {
+        let content = buffer.content();
+        let candidates = match candidate {
+            FuzzyCandidate::Line => line_ranges(&content),
+            FuzzyCandidate::SyntaxNode => syntax_node_ranges(buffer)?,
+        };
+        Ok(Self {
+            ranges: rank(&content, candidates, search),
+        })
+    }
+
+    /// The matches in the order of their position.
+    pub fn find_all(&self) -> Vec<Range<usize>> {
+        self.ranges
+            .iter()
+            .cloned()
+            .sorted_by_key(|range| (range.start, range.end))
+            .collect()
+    }
+}
+
+impl IterBasedSelectionMode for Fuzzy {
+    fn iter<'a>(
+        &'a self,
+        _: &SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(self.ranges.iter().cloned().map(ByteRange::new)))
+    }
+
+    /// Unlike other modes, the best match is selected when the cursor is not on a match,
+    /// instead of the nearest one.
+    fn current(
+        &self,
+        params: &SelectionModeParams,
+        if_current_not_found: IfCurrentNotFound,
+    ) -> anyhow::Result<Option<crate::selection::Selection>> {
+        let cursor_byte = params.buffer.char_to_byte(params.cursor_char_index())?;
+        if self.ranges.iter().any(|range| range.contains(&cursor_byte)) {
+            return self.current_default_impl(params, if_current_not_found);
+        }
+        self.ranges
+            .first()
+            .map(|range| {
+                ByteRange::new(range.clone()).to_selection(params.buffer, params.current_selection)
+            })
+            .transpose()
+    }
+}
+
+/// Returns true if `haystack` matches `search` fuzzily.
+pub fn is_match(search: &str, haystack: &str) -> bool {
+    !rank(haystack, vec![0..haystack.len()], search).is_empty()
+}
+
+/// Returns the matching `candidates` from the highest score to the lowest,
+/// where matches of the same score are ordered by their position.
+fn rank(content: &str, candidates: Vec<Range<usize>>, search: &str) -> Vec<Range<usize>> {
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let pattern = Pattern::parse(search, CaseMatching::Smart, Normalization::Smart);
+    let mut haystack = Vec::new();
+    candidates
+        .into_iter()
+        .filter_map(|range| {
+            let score = pattern.score(
+                Utf32Str::new(&content[range.clone()], &mut haystack),
+                &mut matcher,
+            )?;
+            Some((score, range))
+        })
+        .sorted_by_key(|(score, range)| (Reverse(*score), range.start))
+        .map(|(_, range)| range)
+        .collect()
+}
+
+/// The lines without their leading and trailing whitespaces, except blank lines.
+fn line_ranges(content: &str) -> Vec<Range<usize>> {
+    let mut line_start = 0;
+    content
+        .split_inclusive('\n')
+        .filter_map(|line| {
+            let start = line_start;
+            line_start += line.len();
+            let range = start + line.len() - line.trim_start().len()..start + line.trim_end().len();
+            (!range.is_empty()).then_some(range)
+        })
+        .collect()
+}
+
+/// The named nodes that span a single line, because a node spanning several lines,
+/// such as a function, matches almost any search.
+/// Nodes with the same range as their parent are yielded once.
+fn syntax_node_ranges(buffer: &Buffer) -> anyhow::Result<Vec<Range<usize>>> {
+    let tree = buffer.tree().ok_or(anyhow::anyhow!(
+        "Unable to search syntax nodes fuzzily because no Tree-sitter language is found."
+    ))?;
+    Ok(
+        tree_sitter_traversal2::traverse(tree.walk(), tree_sitter_traversal2::Order::Pre)
+            .filter(|node| {
+                node.is_named()
+                    && node.start_position().row == node.end_position().row
+                    && !node.byte_range().is_empty()
+            })
+            .map(|node| node.byte_range())
+            .dedup()
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test_fuzzy {
+    use super::*;
+
+    #[test]
+    fn ranked_lines() {
+        let content = "  f_o_o\n\nbar\n  foo  \n";
+        assert_eq!(
+            rank(content, line_ranges(content), "foo")
+                .into_iter()
+                .map(|range| &content[range])
+                .collect_vec(),
+            ["foo", "f_o_o"]
+        );
+        assert!(is_match("fb", "foo_bar"));
+        assert!(!is_match("fb", "bar"));
+    }
+}
@@ -0,0 +1,178 @@
+use std::ops::Range;
+
+use crate::{buffer::Buffer, selection::Selection};
+
+use super::{ApplyMovementResult, ByteRange, IterBasedSelectionMode, SelectionModeParams};
+
+/// A non-blank line together with the more indented lines that follow it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Block {
+    /// From the first non-whitespace character of the line
+    /// to the end of the last non-blank line of the block.
+    range: Range<usize>,
+    /// The index of the enclosing block.
+    parent: Option<usize>,
+}
+
+/// Blocks of lines that are determined by indentation alone,
+/// which works for any file, including those of languages without a grammar.
+///
+/// `Up`/`Down` move between the sibling blocks, that is, the blocks at the same
+/// indentation level under the same parent, while `Expand` selects the parent block.
+pub struct IndentationBlock {
+    blocks: Vec<Block>,
+}
+
+impl IndentationBlock {
+    pub fn new(buffer: &Buffer) -> Self {
+        Self {
+            blocks: blocks(&buffer.content()),
+        }
+    }
+
+    /// The index of the block that is selected, otherwise the innermost block at the cursor.
+    fn current_block(&self, params: &SelectionModeParams) -> anyhow::Result<Option<usize>> {
+        let buffer = params.buffer;
+        let range = buffer.char_index_range_to_byte_range(params.current_selection.range())?;
+        if let Some(index) = self.blocks.iter().position(|block| block.range == range) {
+            return Ok(Some(index));
+        }
+        let cursor = buffer.char_to_byte(params.cursor_char_index())?;
+        Ok(self
+            .blocks
+            .iter()
+            .rposition(|block| block.range.start <= cursor && cursor < block.range.end))
+    }
+
+    fn select(
+        &self,
+        params: &SelectionModeParams,
+        index: Option<usize>,
+    ) -> anyhow::Result<Option<Selection>> {
+        index
+            .map(|index| {
+                ByteRange::new(self.blocks[index].range.clone())
+                    .to_selection(params.buffer, params.current_selection)
+            })
+            .transpose()
+    }
+
+    fn sibling(
+        &self,
+        params: &SelectionModeParams,
+        forward: bool,
+    ) -> anyhow::Result<Option<ApplyMovementResult>> {
+        let Some(current) = self.current_block(params)? else {
+            return Ok(None);
+        };
+        let parent = self.blocks[current].parent;
+        let is_sibling = |index: &usize| self.blocks[*index].parent == parent;
+        let sibling = if forward {
+            (current + 1..self.blocks.len()).find(is_sibling)
+        } else {
+            (0..current).rev().find(is_sibling)
+        };
+        Ok(self
+            .select(params, sibling)?
+            .map(ApplyMovementResult::from_selection))
+    }
+
+    fn parent(&self, params: &SelectionModeParams) -> anyhow::Result<Option<Selection>> {
+        let Some(current) = self.current_block(params)? else {
+            return Ok(None);
+        };
+        self.select(params, self.blocks[current].parent)
+    }
+}
+
+impl IterBasedSelectionMode for IndentationBlock {
+    fn iter<'a>(
+        &'a self,
+        _: &SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(
+            self.blocks
+                .iter()
+                .map(|block| ByteRange::new(block.range.clone())),
+        ))
+    }
+
+    fn up(
+        &self,
+        params: &SelectionModeParams,
+        _: Option<usize>,
+    ) -> anyhow::Result<Option<ApplyMovementResult>> {
+        self.sibling(params, false)
+    }
+
+    fn down(
+        &self,
+        params: &SelectionModeParams,
+        _: Option<usize>,
+    ) -> anyhow::Result<Option<ApplyMovementResult>> {
+        self.sibling(params, true)
+    }
+
+    fn expand(&self, params: &SelectionModeParams) -> anyhow::Result<Option<ApplyMovementResult>> {
+        Ok(self
+            .parent(params)?
+            .map(ApplyMovementResult::from_selection))
+    }
+}
+
+/// Returns the blocks in the order of their start, so that a parent precedes its children.
+fn blocks(content: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    // The indices and indentations of the blocks that enclose the current line
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut line_start = 0;
+    for line in content.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indentation = line.len() - trimmed.len();
+        let end = start + line.trim_end().len();
+        while stack
+            .last()
+            .is_some_and(|(_, enclosing)| *enclosing >= indentation)
+        {
+            stack.pop();
+        }
+        for (index, _) in &stack {
+            blocks[*index].range.end = end;
+        }
+        stack.push((blocks.len(), indentation));
+        blocks.push(Block {
+            range: start + indentation..end,
+            parent: stack.iter().rev().nth(1).map(|(index, _)| *index),
+        });
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod test_indentation_block {
+    use super::*;
+
+    #[test]
+    fn nested_blocks() {
+        let content = "a:\n  b: 1\n\n  c:\n    d\ne\n";
+        let blocks = blocks(content);
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| (&content[block.range.clone()], block.parent))
+                .collect::<Vec<_>>(),
+            [
+                ("a:\n  b: 1\n\n  c:\n    d", None),
+                ("b: 1", Some(0)),
+                ("c:\n    d", Some(0)),
+                ("d", Some(2)),
+                ("e", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::cmp::Reverse;
+
+use super::{ByteRange, IterBasedSelectionMode};
+
+/// The node kinds of the `tree-sitter-md` block grammar that are selected.
+/// A `section` spans a heading and its content, including nested sections.
+const NODE_KINDS: &[&str] = &["section", "list_item", "fenced_code_block"];
+
+/// Structural units of Markdown: sections, list items and fenced code blocks.
+///
+/// The content is always parsed with the Markdown grammar,
+/// so that this also works for buffers of other languages, such as commit messages.
+pub struct Markdown {
+    ranges: Vec<ByteRange>,
+}
+
+impl Markdown {
+    pub fn new(buffer: &crate::buffer::Buffer) -> anyhow::Result<Self> {
+        let content = buffer.content();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tree_sitter_md::LANGUAGE.into())?;
+        let tree = parser
+            .parse(&content, None)
+            .ok_or_else(|| anyhow::anyhow!("Unable to parse the content as Markdown."))?;
+        let mut ranges =
+            tree_sitter_traversal2::traverse(tree.walk(), tree_sitter_traversal2::Order::Pre)
+                .filter(|node| NODE_KINDS.contains(&node.kind()))
+                .map(|node| {
+                    // Nodes include their trailing blank lines, which are not selected
+                    let range = node.byte_range();
+                    range.start..range.start + content[range].trim_end().len()
+                })
+                .filter(|range| !range.is_empty())
+                .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+        ranges.dedup();
+        Ok(Self {
+            ranges: ranges.into_iter().map(ByteRange::new).collect(),
+        })
+    }
+}
+
+impl IterBasedSelectionMode for Markdown {
+    fn iter<'a>(
+        &'a self,
+        _: &super::SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(self.ranges.iter().cloned()))
+    }
+}
+
+#[cfg(test)]
+mod test_markdown {
+    use crate::{buffer::Buffer, selection::Selection};
+
+    use super::*;
+
+    #[test]
+    fn sections_list_items_and_fenced_code_blocks() {
+        let content = "# A\n\n- x\n- y\n\n## B\n\n```rs\nz\n```\n";
+        let buffer = Buffer::new(None, content);
+        Markdown::new(&buffer).unwrap().assert_all_selections(
+            &buffer,
+            Selection::default(),
+            &[
+                (0..31, "# A\n\n- x\n- y\n\n## B\n\n```rs\nz\n```"),
+                (5..8, "- x"),
+                (9..12, "- y"),
+                (14..31, "## B\n\n```rs\nz\n```"),
+                (20..31, "```rs\nz\n```"),
+            ],
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{buffer::Buffer, git::merge_conflict::merge_conflicts};
+
+use super::{ByteRange, IterBasedSelectionMode};
+
+/// Regions between the `<<<<<<<` and `>>>>>>>` markers of unresolved merge conflicts.
+pub struct MergeConflict {
+    ranges: Vec<ByteRange>,
+}
+
+impl MergeConflict {
+    pub fn new(buffer: &Buffer) -> Self {
+        let content = buffer.content();
+        Self {
+            ranges: merge_conflicts(&content)
+                .into_iter()
+                .map(|conflict| {
+                    // The line break of the `>>>>>>>` line is not selected
+                    let range = conflict.range().clone();
+                    ByteRange::new(range.start..range.start + content[range].trim_end().len())
+                })
+                .collect(),
+        }
+    }
+}
+
+impl IterBasedSelectionMode for MergeConflict {
+    fn iter<'a>(
+        &'a self,
+        _: &super::SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(self.ranges.iter().cloned()))
+    }
+}
@@ -2,7 +2,9 @@ pub mod ast_grep;
 pub mod character;
 pub mod custom;
 pub mod diagnostic;
+pub mod fuzzy;
 pub mod git_hunk;
+pub mod indentation_block;
 pub mod mark;
 pub mod naming_convention_agnostic;
 pub mod syntax_token;
@@ -13,10 +15,15 @@ pub mod big_word;
 pub mod line_full;
 pub mod line_trimmed;
 pub mod local_quickfix;
+pub mod markdown;
+pub mod merge_conflict;
 pub mod paragraph;
+pub mod pattern;
 pub mod regex;
+pub mod sentence;
 pub mod subword;
 pub mod syntax_node;
+pub mod textobject;
 pub mod word;
 pub use self::regex::Regex;
 pub use ast_grep::AstGrep;
@@ -24,18 +31,25 @@ pub use big_word::BigWord;
 pub use character::Character;
 pub use custom::Custom;
 pub use diagnostic::Diagnostic;
+pub use fuzzy::Fuzzy;
 pub use git_hunk::GitHunk;
+pub use indentation_block::IndentationBlock;
 use itertools::Itertools;
 pub use line_full::LineFull;
 pub use line_trimmed::LineTrimmed;
 pub use local_quickfix::LocalQuickfix;
 pub use mark::Mark;
+pub use markdown::Markdown;
+pub use merge_conflict::MergeConflict;
 pub use naming_convention_agnostic::NamingConventionAgnostic;
 pub use paragraph::Paragraph;
+pub use pattern::{Pattern, PatternKind};
 use position_pair::ParsedChar;
+pub use sentence::Sentence;
 use std::ops::Range;
 pub use subword::Subword;
 pub use syntax_node::SyntaxNode;
+pub use textobject::{Textobject, TextobjectKind};
 pub use top_node::TopNode;
 pub use word::Word;
 
@@ -0,0 +1,164 @@
+use std::ops::Range;
+
+use crate::buffer::Buffer;
+
+use super::{ByteRange, IterBasedSelectionMode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PatternKind {
+    Url,
+    /// A file path, optionally followed by `:line` or `:line:column`,
+    /// as printed by compilers and linters.
+    FilePath,
+    /// Integer and floating point literals, including hexadecimal, binary and octal ones.
+    Number,
+}
+
+impl PatternKind {
+    pub fn display(&self) -> &'static str {
+        match self {
+            PatternKind::Url => "URL",
+            PatternKind::FilePath => "PATH",
+            PatternKind::Number => "NUM",
+        }
+    }
+}
+
+/// Tokens that are recognized by their shape rather than by the grammar of the language,
+/// so that they can be selected in any buffer, such as logs or the output of a compiler.
+pub struct Pattern {
+    ranges: Vec<ByteRange>,
+}
+
+impl Pattern {
+    pub fn new(buffer: &Buffer, kind: PatternKind) -> Self {
+        Self {
+            ranges: pattern_ranges(&buffer.content(), kind)
+                .into_iter()
+                .map(ByteRange::new)
+                .collect(),
+        }
+    }
+}
+
+impl IterBasedSelectionMode for Pattern {
+    fn iter<'a>(
+        &'a self,
+        _: &super::SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(self.ranges.iter().cloned()))
+    }
+}
+
+fn pattern_ranges(content: &str, kind: PatternKind) -> Vec<Range<usize>> {
+    let urls = || {
+        lazy_regex::regex!(r#"\b[a-zA-Z][a-zA-Z0-9+.-]*://[^\s<>"'`]+"#)
+            .find_iter(content)
+            .map(|found| trim_trailing_punctuation(content, found.range()))
+    };
+    match kind {
+        PatternKind::Url => urls().collect(),
+        PatternKind::FilePath => {
+            let urls = urls().collect::<Vec<_>>();
+            lazy_regex::regex!(
+                r"(?:(?:~|\.{1,2})?/|[\w.@+-]+/)(?:[\w.@+-]+/)*[\w.@+-]+(?::\d+){0,2}|\b[\w.@+-]+\.[a-zA-Z]\w*(?::\d+){1,2}"
+            )
+            .find_iter(content)
+            // Paths within URLs are not selected
+            .filter(|found| {
+                !urls
+                    .iter()
+                    .any(|url| url.start <= found.start() && found.start() < url.end)
+            })
+            .map(|found| trim_trailing_punctuation(content, found.range()))
+            .collect()
+        }
+        PatternKind::Number => lazy_regex::regex!(
+            r"\b(?:0[xX][0-9a-fA-F_]+|0[bB][01_]+|0[oO][0-7_]+|\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d+)?)"
+        )
+        .find_iter(content)
+        .map(|found| found.range())
+        .collect(),
+    }
+}
+
+/// Punctuation that ends a sentence, or closes an enclosure that is opened before the match,
+/// is usually not part of a URL or a path, such as in `(see https://example.com).`
+fn trim_trailing_punctuation(content: &str, range: Range<usize>) -> Range<usize> {
+    let text = &content[range.clone()];
+    let mut end = text.len();
+    loop {
+        let trimmed = &text[..end];
+        let Some(last) = trimmed.chars().next_back() else {
+            break;
+        };
+        let is_unbalanced = |open: char| {
+            trimmed.chars().filter(|c| *c == open).count()
+                < trimmed.chars().filter(|c| *c == last).count()
+        };
+        let trim = match last {
+            '.' | ',' | ';' | ':' | '!' | '?' => true,
+            ')' => is_unbalanced('('),
+            ']' => is_unbalanced('['),
+            '}' => is_unbalanced('{'),
+            _ => false,
+        };
+        if !trim {
+            break;
+        }
+        end -= last.len_utf8();
+    }
+    range.start..range.start + end
+}
+
+#[cfg(test)]
+mod test_pattern {
+    use super::*;
+
+    fn run(content: &str, kind: PatternKind) -> Vec<&str> {
+        pattern_ranges(content, kind)
+            .into_iter()
+            .map(|range| &content[range])
+            .collect()
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            run(
+                "See (https://en.wikipedia.org/wiki/Rust_(programming_language)). Or file:///tmp/a.",
+                PatternKind::Url
+            ),
+            [
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                "file:///tmp/a"
+            ]
+        );
+    }
+
+    #[test]
+    fn file_paths() {
+        assert_eq!(
+            run(
+                "error at src/main.rs:12:5, see ./docs/a.md or lib.rs:3. Ignore https://x.io/a/b",
+                PatternKind::FilePath
+            ),
+            ["src/main.rs:12:5", "./docs/a.md", "lib.rs:3"]
+        );
+        assert_eq!(
+            run("/usr/bin/env and ~/notes.txt", PatternKind::FilePath),
+            ["/usr/bin/env", "~/notes.txt"]
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            run(
+                "x1 = 42 + 0xFF * 3.14e-2 - 1_000 (10px)",
+                PatternKind::Number
+            ),
+            ["42", "0xFF", "3.14e-2", "1_000", "10"]
+        );
+    }
+}
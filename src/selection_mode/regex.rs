@@ -71,6 +71,7 @@ mod test_regex {
                 escaped: true,
                 case_sensitive: false,
                 match_whole_word: false,
+                multi_line: false,
             },
         )
         .unwrap()
@@ -87,6 +88,7 @@ mod test_regex {
                 escaped: false,
                 case_sensitive: false,
                 match_whole_word: false,
+                multi_line: false,
             },
         )
         .unwrap()
@@ -107,6 +109,7 @@ mod test_regex {
                 escaped: false,
                 case_sensitive: false,
                 match_whole_word: false,
+                multi_line: false,
             },
         )
         .unwrap()
@@ -127,6 +130,7 @@ mod test_regex {
                 escaped: false,
                 case_sensitive: false,
                 match_whole_word: true,
+                multi_line: false,
             },
         )
         .unwrap()
@@ -151,6 +155,7 @@ mod test_regex {
                 escaped: false,
                 case_sensitive: false,
                 match_whole_word: false,
+                multi_line: false,
             },
         )
         .unwrap()
@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use super::{ByteRange, IterBasedSelectionMode};
+
+/// Words that are commonly followed by a period without ending a sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "al", "approx", "cf", "co", "dr", "e.g", "eg", "etc", "fig", "i.e", "ie", "inc", "jr", "ltd",
+    "mr", "mrs", "ms", "prof", "sr", "st", "viz", "vs",
+];
+
+/// Sentences of prose, which may span multiple lines.
+///
+/// A blank line always ends a sentence, and the line comment prefix of the
+/// innermost language of each line is ignored, so that sentences in a comment block can be selected.
+pub struct Sentence {
+    ranges: Vec<ByteRange>,
+}
+
+impl Sentence {
+    pub fn new(buffer: &crate::buffer::Buffer) -> Self {
+        let line_comment_prefix = |byte: usize| {
+            buffer
+                .language_at(byte)
+                .and_then(|language| language.line_comment_prefix())
+        };
+        Self {
+            ranges: sentence_ranges(&buffer.content(), line_comment_prefix)
+                .into_iter()
+                .map(ByteRange::new)
+                .collect(),
+        }
+    }
+}
+
+impl IterBasedSelectionMode for Sentence {
+    fn iter<'a>(
+        &'a self,
+        _: &super::SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(self.ranges.iter().cloned()))
+    }
+}
+
+/// `line_comment_prefix` is given the byte offset of each line.
+fn sentence_ranges(
+    content: &str,
+    line_comment_prefix: impl Fn(usize) -> Option<String>,
+) -> Vec<Range<usize>> {
+    // The prose characters and their byte offsets,
+    // that is, without the indentation and comment prefix of each line
+    let chars = {
+        let mut line_start = 0;
+        let mut chars = Vec::new();
+        let mut was_comment = None;
+        for line in content.split_inclusive('\n') {
+            let trimmed = line.trim_start_matches([' ', '\t']);
+            let comment = line_comment_prefix(line_start)
+                .and_then(|prefix| trimmed.strip_prefix(prefix.as_str()));
+            // Entering or leaving a comment block is treated as a blank line
+            if was_comment.is_some_and(|was_comment| was_comment != comment.is_some()) {
+                chars.push((line_start, '\n'));
+            }
+            was_comment = Some(comment.is_some());
+            let prose = comment.unwrap_or(trimmed);
+            let offset = line_start + line.len() - prose.len();
+            chars.extend(prose.char_indices().map(|(index, c)| (offset + index, c)));
+            line_start += line.len();
+        }
+        chars
+    };
+    let next_non_blank = |index: usize| {
+        chars[index..]
+            .iter()
+            .map(|(_, c)| *c)
+            .find(|c| *c != ' ' && *c != '\t')
+    };
+    let is_abbreviation = |period_index: usize| {
+        let word: String = chars[..period_index]
+            .iter()
+            .rev()
+            .map(|(_, c)| *c)
+            .take_while(|c| c.is_alphabetic() || *c == '.')
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let mut letters = word.chars();
+        // A single capital letter is an initial, as in `J. R. R. Tolkien`
+        let is_initial =
+            matches!((letters.next(), letters.next()), (Some(c), None) if c.is_uppercase());
+        let word = word.to_lowercase();
+        // `no.` only abbreviates "number" when a number follows, as in `No. 5`
+        let is_number = word == "no"
+            && next_non_whitespace(&chars[period_index + 1..]).is_some_and(|c| c.is_ascii_digit());
+        is_initial || is_number || ABBREVIATIONS.contains(&word.as_str())
+    };
+
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        if chars[index].1.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        let end = loop {
+            let Some((_, c)) = chars.get(index) else {
+                break chars.len();
+            };
+            if *c == '\n' && next_non_blank(index + 1).is_none_or(|c| c == '\n') {
+                break index;
+            }
+            index += 1;
+            if !matches!(c, '.' | '!' | '?') {
+                continue;
+            }
+            let period_index = index - 1;
+            let is_single_period = *c == '.' && !matches!(chars.get(index), Some((_, '.')));
+            while matches!(chars.get(index), Some((_, '.' | '!' | '?'))) {
+                index += 1
+            }
+            while matches!(
+                chars.get(index),
+                Some((_, '"' | '\'' | ')' | ']' | '”' | '’'))
+            ) {
+                index += 1
+            }
+            let ends_sentence = match chars.get(index) {
+                None => true,
+                Some((_, c)) if c.is_whitespace() => {
+                    !(is_single_period && is_abbreviation(period_index))
+                        && !next_non_whitespace(&chars[index..]).is_some_and(char::is_lowercase)
+                }
+                Some(_) => false,
+            };
+            if ends_sentence {
+                break index;
+            }
+        };
+        let last = chars[start..end]
+            .iter()
+            .rposition(|(_, c)| !c.is_whitespace())
+            .map(|position| chars[start + position])
+            .unwrap_or(chars[start]);
+        ranges.push(chars[start].0..last.0 + last.1.len_utf8());
+        index = end;
+    }
+    ranges
+}
+
+fn next_non_whitespace(chars: &[(usize, char)]) -> Option<char> {
+    chars.iter().map(|(_, c)| *c).find(|c| !c.is_whitespace())
+}
+
+#[cfg(test)]
+mod test_sentence {
+    use super::sentence_ranges;
+
+    fn sentences<'a>(content: &'a str, line_comment_prefix: Option<&str>) -> Vec<&'a str> {
+        sentence_ranges(content, |_| line_comment_prefix.map(str::to_string))
+            .into_iter()
+            .map(|range| &content[range])
+            .collect()
+    }
+
+    #[test]
+    fn abbreviations_and_initials() {
+        assert_eq!(
+            sentences(
+                "Dr. Who met J. R. R. Tolkien, e.g. on Mars. Really?! Yes... \"Fine.\" Pi is 3.14",
+                None
+            ),
+            [
+                "Dr. Who met J. R. R. Tolkien, e.g. on Mars.",
+                "Really?!",
+                "Yes...",
+                "\"Fine.\"",
+                "Pi is 3.14"
+            ]
+        );
+    }
+
+    #[test]
+    fn number_abbreviation() {
+        assert_eq!(
+            sentences("See No. 5 for details. I said no. Then I left.", None),
+            ["See No. 5 for details.", "I said no.", "Then I left."]
+        );
+    }
+
+    #[test]
+    fn blank_lines_end_sentences() {
+        assert_eq!(
+            sentences("A heading\n\nThis spans\ntwo lines. Last", None),
+            ["A heading", "This spans\ntwo lines.", "Last"]
+        );
+    }
+
+    #[test]
+    fn comment_prefixes() {
+        assert_eq!(
+            sentences(
+                "    // First sentence\n    // continues here. Second.\n    //\n    // Third",
+                Some("//")
+            ),
+            ["First sentence\n    // continues here.", "Second.", "Third"]
+        );
+    }
+}
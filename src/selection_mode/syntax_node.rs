@@ -26,13 +26,7 @@ impl IterBasedSelectionMode for SyntaxNode {
         let Some(node) = node.parent() else {
             return Ok(Box::new(std::iter::empty()));
         };
-        let mut cursor = params
-            .buffer
-            .tree()
-            .ok_or(anyhow::anyhow!(
-                "SyntaxNode::iter.tree: Cannot find Treesitter language"
-            ))?
-            .walk();
+        let mut cursor = node.walk();
         let vector = if self.coarse {
             node.named_children(&mut cursor).collect_vec()
         } else {
@@ -11,7 +11,7 @@ impl IterBasedSelectionMode for SyntaxToken {
     ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
         let buffer = params.buffer;
         let tree = buffer
-            .tree()
+            .tree_at(buffer.char_to_byte(params.cursor_char_index())?)
             .ok_or(anyhow::anyhow!("Unable to find Treesitter language"))?;
         Ok(Box::new(
             tree_sitter_traversal2::traverse(tree.walk(), tree_sitter_traversal2::Order::Post)
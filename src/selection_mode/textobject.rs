@@ -0,0 +1,230 @@
+use std::cmp::Reverse;
+
+use tree_sitter::StreamingIterator;
+
+use crate::selection::CharIndex;
+
+use super::{ByteRange, IterBasedSelectionMode};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TextobjectKind {
+    Function,
+    Class,
+    Parameter,
+    Comment,
+    Test,
+}
+
+impl TextobjectKind {
+    /// The name of the capture in `textobjects.scm`, for example `function.inside`.
+    fn capture_name(&self, inside: bool) -> String {
+        let kind = match self {
+            TextobjectKind::Function => "function",
+            TextobjectKind::Class => "class",
+            TextobjectKind::Parameter => "parameter",
+            TextobjectKind::Comment => "comment",
+            TextobjectKind::Test => "test",
+        };
+        format!("{kind}.{}", if inside { "inside" } else { "around" })
+    }
+
+    /// Whether the `inside` capture of this kind is a body,
+    /// whose surrounding braces are excluded.
+    fn has_body(&self) -> bool {
+        matches!(
+            self,
+            TextobjectKind::Function | TextobjectKind::Class | TextobjectKind::Test
+        )
+    }
+
+    pub fn display(&self) -> &'static str {
+        match self {
+            TextobjectKind::Function => "FUNC",
+            TextobjectKind::Class => "CLASS",
+            TextobjectKind::Parameter => "PARAM",
+            TextobjectKind::Comment => "COMMENT",
+            TextobjectKind::Test => "TEST",
+        }
+    }
+}
+
+pub struct Textobject {
+    ranges: Vec<ByteRange>,
+}
+
+impl Textobject {
+    /// Only the textobjects of the innermost language at `cursor` are selected,
+    /// see `Buffer::language_at`.
+    pub fn new(
+        buffer: &crate::buffer::Buffer,
+        cursor: CharIndex,
+        kind: TextobjectKind,
+        inside: bool,
+    ) -> anyhow::Result<Self> {
+        let byte = buffer.char_to_byte(cursor)?;
+        let innermost_language = buffer.language_at(byte);
+        let Some(tree) = buffer.tree_at(byte).filter(|_| {
+            innermost_language
+                .as_ref()
+                .is_some_and(|language| language.tree_sitter_language().is_some())
+        }) else {
+            return Err(anyhow::anyhow!(
+                "Unable to select textobjects because no Tree-sitter language is found."
+            ));
+        };
+        let Some(query) = innermost_language
+            .map(|language| language.compiled_textobjects_query())
+            .transpose()?
+            .flatten()
+        else {
+            return Err(anyhow::anyhow!(
+                "Unable to select textobjects because no textobjects query is found for this language."
+            ));
+        };
+        let Some(capture_index) = query.capture_index_for_name(&kind.capture_name(inside)) else {
+            return Ok(Self { ranges: Vec::new() });
+        };
+        let source = buffer.rope().to_string();
+        let mut cursor = tree_sitter::QueryCursor::new();
+        let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        let mut ranges = Vec::new();
+        while let Some(query_match) = matches.next() {
+            let mut nodes = query_match.nodes_for_capture_index(capture_index);
+            let range = if inside && kind.has_body() {
+                nodes.next().and_then(|node| inside_braces(node, &source))
+            } else {
+                // A textobject can span multiple captured nodes,
+                // for example a parameter and its trailing comma
+                nodes
+                    .map(|node| node.byte_range())
+                    .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+            };
+            ranges.extend(range);
+        }
+        // Different patterns can capture the same textobject with or without
+        // its surroundings, in which case only the largest one is kept
+        ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+        ranges.dedup_by_key(|range| range.start);
+        Ok(Self {
+            ranges: ranges.into_iter().map(ByteRange::new).collect(),
+        })
+    }
+}
+
+/// Returns the range of `node` without its surrounding braces and the whitespaces inside them,
+/// or `None` if there is nothing inside the braces.
+/// Bodies without braces, such as those of Python, are returned as is.
+fn inside_braces(node: tree_sitter::Node, source: &str) -> Option<std::ops::Range<usize>> {
+    let count = node.child_count();
+    let braces = (count >= 2)
+        .then(|| Some((node.child(0)?, node.child(count - 1)?)))
+        .flatten()
+        .filter(|(open, close)| open.kind() == "{" && close.kind() == "}");
+    let Some((open, close)) = braces else {
+        return Some(node.byte_range());
+    };
+    let inside = &source[open.end_byte()..close.start_byte()];
+    let start = open.end_byte() + (inside.len() - inside.trim_start().len());
+    let end = close.start_byte() - (inside.len() - inside.trim_end().len());
+    (start < end).then_some(start..end)
+}
+
+impl IterBasedSelectionMode for Textobject {
+    fn iter<'a>(
+        &'a self,
+        _: &super::SelectionModeParams<'a>,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
+        Ok(Box::new(self.ranges.iter().cloned()))
+    }
+}
+
+#[cfg(test)]
+mod test_textobject {
+    use crate::{buffer::Buffer, selection::Selection};
+
+    use super::*;
+
+    fn rust_buffer(content: &str) -> Buffer {
+        let mut buffer = Buffer::new(Some(tree_sitter_rust::LANGUAGE.into()), content);
+        buffer
+            .set_language(crate::config::from_extension("rs").unwrap())
+            .unwrap();
+        buffer
+    }
+
+    #[test]
+    fn function() {
+        let buffer = rust_buffer("fn f(x: A) { g(|y| y) } fn h() {}");
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Function, false)
+            .unwrap()
+            .assert_all_selections(
+                &buffer,
+                Selection::default(),
+                &[
+                    (0..23, "fn f(x: A) { g(|y| y) }"),
+                    (15..20, "|y| y"),
+                    (24..33, "fn h() {}"),
+                ],
+            );
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Function, true)
+            .unwrap()
+            .assert_all_selections(
+                &buffer,
+                Selection::default(),
+                &[(13..21, "g(|y| y)"), (19..20, "y")],
+            );
+    }
+
+    #[test]
+    fn parameter() {
+        let buffer = rust_buffer("fn f(x: A, y: B) {}");
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Parameter, false)
+            .unwrap()
+            .assert_all_selections(
+                &buffer,
+                Selection::default(),
+                &[(5..10, "x: A,"), (11..15, "y: B")],
+            );
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Parameter, true)
+            .unwrap()
+            .assert_all_selections(
+                &buffer,
+                Selection::default(),
+                &[(5..9, "x: A"), (11..15, "y: B")],
+            );
+    }
+
+    #[test]
+    fn class_comment_and_test() {
+        let buffer = rust_buffer(
+            "
+/* x */
+struct A { x: X }
+#[test]
+fn t() {}
+fn u() {}
+#[cfg(test)]
+mod test_a { fn v() {} }"
+                .trim(),
+        );
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Class, true)
+            .unwrap()
+            .assert_all_selections(&buffer, Selection::default(), &[(19..23, "x: X")]);
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Comment, false)
+            .unwrap()
+            .assert_all_selections(&buffer, Selection::default(), &[(0..7, "/* x */")]);
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Test, false)
+            .unwrap()
+            .assert_all_selections(
+                &buffer,
+                Selection::default(),
+                &[
+                    (26..43, "#[test]\nfn t() {}"),
+                    (54..91, "#[cfg(test)]\nmod test_a { fn v() {} }"),
+                ],
+            );
+        Textobject::new(&buffer, CharIndex(0), TextobjectKind::Test, true)
+            .unwrap()
+            .assert_all_selections(&buffer, Selection::default(), &[(80..89, "fn v() {}")]);
+    }
+}
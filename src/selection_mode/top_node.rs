@@ -9,9 +9,11 @@ impl IterBasedSelectionMode for TopNode {
         params: &super::SelectionModeParams<'a>,
     ) -> anyhow::Result<Box<dyn Iterator<Item = ByteRange> + 'a>> {
         let buffer = params.buffer;
-        let tree = buffer.tree().ok_or(anyhow::anyhow!(
-            "TopNode::iter: cannot find Treesitter language"
-        ))?;
+        let tree = buffer
+            .tree_at(buffer.char_to_byte(params.cursor_char_index())?)
+            .ok_or(anyhow::anyhow!(
+                "TopNode::iter: cannot find Treesitter language"
+            ))?;
         let root_node_id = tree.root_node().id();
         Ok(Box::new(
             tree_sitter_traversal2::traverse(tree.walk(), tree_sitter_traversal2::Order::Pre)
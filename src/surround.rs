@@ -1,6 +1,8 @@
+use std::ops::Range;
+
 use itertools::Itertools;
 
-use crate::selection::CharIndex;
+use crate::{buffer::Buffer, char_index_range::CharIndexRange, selection::CharIndex};
 
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub enum EnclosureKind {
@@ -11,6 +13,9 @@ pub enum EnclosureKind {
     DoubleQuotes,
     SingleQuotes,
     Backticks,
+    /// Language-defined pairs of keywords, such as `begin`/`end` in Ruby,
+    /// see `Language::keyword_enclosures`.
+    Keywords,
 }
 
 impl std::fmt::Display for EnclosureKind {
@@ -23,6 +28,7 @@ impl std::fmt::Display for EnclosureKind {
             EnclosureKind::DoubleQuotes => write!(f, "DoubleQuotes"),
             EnclosureKind::SingleQuotes => write!(f, "SingleQuotes"),
             EnclosureKind::Backticks => write!(f, "Backticks"),
+            EnclosureKind::Keywords => write!(f, "Keywords"),
         }
     }
 }
@@ -39,7 +45,7 @@ pub fn get_surrounding_indices(
         return None;
     }
     let chars = content.chars().collect_vec();
-    let (open, close) = kind.open_close_symbols();
+    let (open, close) = kind.open_close_symbols()?;
     let open_index = {
         let index = if include_cursor_position {
             cursor_char_index + 1
@@ -86,28 +92,216 @@ pub fn get_surrounding_indices(
     Some((open_index, close_index))
 }
 
+/// Return the char ranges of the open and close symbols of the given `kind` that enclose the cursor.
+///
+/// If the buffer has a syntax tree, only symbols that delimit a syntax node are considered,
+/// so that symbols in strings and comments are ignored,
+/// unless the cursor is within a token that contains both symbols (such as a comment).
+/// Otherwise, the symbols are searched within the whole content.
+pub fn get_surrounding_ranges(
+    buffer: &Buffer,
+    kind: EnclosureKind,
+    cursor_char_index: CharIndex,
+    include_cursor_position: bool,
+) -> Option<(CharIndexRange, CharIndexRange)> {
+    let to_char_ranges =
+        |(open, close): (CharIndex, CharIndex)| -> (CharIndexRange, CharIndexRange) {
+            ((open..open + 1).into(), (close..close + 1).into())
+        };
+    let content = buffer.content();
+    let Some(tree) = buffer.tree() else {
+        return get_surrounding_indices(&content, kind, cursor_char_index, include_cursor_position)
+            .map(to_char_ranges);
+    };
+    let cursor_byte = buffer.char_to_byte(cursor_char_index).ok()?;
+    let cursor_byte_range = cursor_byte
+        ..buffer
+            .char_to_byte(cursor_char_index + 1)
+            .unwrap_or(cursor_byte);
+    let pairs = kind.pairs(buffer.language().as_ref());
+    if let Some((open, close)) = get_surrounding_node_ranges(
+        tree.root_node(),
+        &content,
+        &pairs,
+        cursor_byte_range.clone(),
+        include_cursor_position,
+    ) {
+        return Some((
+            buffer.byte_range_to_char_index_range(&open).ok()?,
+            buffer.byte_range_to_char_index_range(&close).ok()?,
+        ));
+    }
+    let token_range = tree
+        .root_node()
+        .descendant_for_byte_range(cursor_byte_range.start, cursor_byte_range.end)
+        .filter(|node| node.child_count() == 0 && node.byte_range().contains(&cursor_byte))
+        .and_then(|token| {
+            buffer
+                .byte_range_to_char_index_range(&token.byte_range())
+                .ok()
+        })?;
+    let (open, close) = get_surrounding_indices(
+        &buffer.slice(&token_range).ok()?.to_string(),
+        kind,
+        cursor_char_index - token_range.start.0,
+        include_cursor_position,
+    )?;
+    Some(to_char_ranges((
+        open + token_range.start.0,
+        close + token_range.start.0,
+    )))
+}
+
+/// Return the byte ranges of the first and last child of the innermost node
+/// that encloses the cursor and is delimited by one of the given `pairs`.
+fn get_surrounding_node_ranges(
+    root: tree_sitter::Node,
+    content: &str,
+    pairs: &[(String, String)],
+    cursor_byte_range: Range<usize>,
+    include_cursor_position: bool,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let mut node = root.descendant_for_byte_range(cursor_byte_range.start, cursor_byte_range.end);
+    while let Some(current) = node {
+        if let Some((open, close)) = get_delimiters(current, content, pairs) {
+            let cursor = cursor_byte_range.start;
+            let encloses = if include_cursor_position {
+                open.start <= cursor && cursor < close.start
+            } else {
+                open.end <= cursor && cursor < close.end
+            };
+            if encloses {
+                return Some((open, close));
+            }
+        }
+        node = current.parent();
+    }
+    None
+}
+
+/// Return the byte ranges of the first and last child of `node`
+/// if they are one of the given `pairs`.
+fn get_delimiters(
+    node: tree_sitter::Node,
+    content: &str,
+    pairs: &[(String, String)],
+) -> Option<(Range<usize>, Range<usize>)> {
+    let count = node.child_count();
+    if count < 2 || node.is_error() {
+        return None;
+    }
+    let open = node.child(0)?.byte_range();
+    let close = node.child(count - 1)?.byte_range();
+    let (open_text, close_text) = (content.get(open.clone())?, content.get(close.clone())?);
+    pairs
+        .iter()
+        .any(|(open, close)| open == open_text && close == close_text)
+        .then_some((open, close))
+}
+
+/// Return the char range of the token that pairs with the delimiter under the cursor,
+/// for example the closing bracket of an opening bracket, or the `end` of a `do`.
+///
+/// If the cursor is not on a delimiter, the closing delimiter of the innermost
+/// node that encloses the cursor is returned.
+pub fn get_matching_pair_range(
+    buffer: &Buffer,
+    cursor_char_index: CharIndex,
+) -> Option<CharIndexRange> {
+    let Some(tree) = buffer.tree() else {
+        let content = buffer.content();
+        let char = content.chars().nth(cursor_char_index.0)?;
+        let kind = [
+            EnclosureKind::Parentheses,
+            EnclosureKind::CurlyBraces,
+            EnclosureKind::SquareBrackets,
+            EnclosureKind::AngularBrackets,
+        ]
+        .into_iter()
+        .find(|kind| {
+            kind.open_close_symbols()
+                .is_some_and(|(open, close)| char == open || char == close)
+        })?;
+        let is_open = kind
+            .open_close_symbols()
+            .is_some_and(|(open, _)| char == open);
+        let (open, close) = get_surrounding_indices(&content, kind, cursor_char_index, is_open)?;
+        let index = if open == cursor_char_index {
+            close
+        } else {
+            open
+        };
+        return Some((index..index + 1).into());
+    };
+    let cursor_byte = buffer.char_to_byte(cursor_char_index).ok()?;
+    let content = buffer.content();
+    let pairs = [
+        EnclosureKind::Parentheses,
+        EnclosureKind::CurlyBraces,
+        EnclosureKind::SquareBrackets,
+        EnclosureKind::AngularBrackets,
+        EnclosureKind::DoubleQuotes,
+        EnclosureKind::SingleQuotes,
+        EnclosureKind::Backticks,
+        EnclosureKind::Keywords,
+    ]
+    .into_iter()
+    .flat_map(|kind| kind.pairs(buffer.language().as_ref()))
+    .collect_vec();
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(cursor_byte, cursor_byte + 1);
+    while let Some(current) = node {
+        if let Some((open, close)) = get_delimiters(current, &content, &pairs) {
+            let range = if open.contains(&cursor_byte) {
+                close
+            } else if close.contains(&cursor_byte) {
+                open
+            } else {
+                close
+            };
+            return buffer.byte_range_to_char_index_range(&range).ok();
+        }
+        node = current.parent();
+    }
+    None
+}
+
 impl EnclosureKind {
-    pub const fn open_close_symbols(&self) -> (char, char) {
+    pub const fn open_close_symbols(&self) -> Option<(char, char)> {
         match self {
-            EnclosureKind::Parentheses => ('(', ')'),
-            EnclosureKind::CurlyBraces => ('{', '}'),
-            EnclosureKind::AngularBrackets => ('<', '>'),
-            EnclosureKind::SquareBrackets => ('[', ']'),
-            EnclosureKind::DoubleQuotes => ('"', '"'),
-            EnclosureKind::SingleQuotes => ('\'', '\''),
-            EnclosureKind::Backticks => ('`', '`'),
+            EnclosureKind::Parentheses => Some(('(', ')')),
+            EnclosureKind::CurlyBraces => Some(('{', '}')),
+            EnclosureKind::AngularBrackets => Some(('<', '>')),
+            EnclosureKind::SquareBrackets => Some(('[', ']')),
+            EnclosureKind::DoubleQuotes => Some(('"', '"')),
+            EnclosureKind::SingleQuotes => Some(('\'', '\'')),
+            EnclosureKind::Backticks => Some(('`', '`')),
+            EnclosureKind::Keywords => None,
         }
     }
 
-    pub const fn open_close_symbols_str(&self) -> (&'static str, &'static str) {
+    /// Returns `None` for `Keywords`, whose symbols depend on the language.
+    pub const fn open_close_symbols_str(&self) -> Option<(&'static str, &'static str)> {
         match self {
-            EnclosureKind::Parentheses => ("(", ")"),
-            EnclosureKind::CurlyBraces => ("{", "}"),
-            EnclosureKind::AngularBrackets => ("<", ">"),
-            EnclosureKind::SquareBrackets => ("[", "]"),
-            EnclosureKind::DoubleQuotes => ("\"", "\""),
-            EnclosureKind::SingleQuotes => ("'", "'"),
-            EnclosureKind::Backticks => ("`", "`"),
+            EnclosureKind::Parentheses => Some(("(", ")")),
+            EnclosureKind::CurlyBraces => Some(("{", "}")),
+            EnclosureKind::AngularBrackets => Some(("<", ">")),
+            EnclosureKind::SquareBrackets => Some(("[", "]")),
+            EnclosureKind::DoubleQuotes => Some(("\"", "\"")),
+            EnclosureKind::SingleQuotes => Some(("'", "'")),
+            EnclosureKind::Backticks => Some(("`", "`")),
+            EnclosureKind::Keywords => None,
+        }
+    }
+
+    /// The pairs of symbols of this kind in the given language.
+    fn pairs(&self, language: Option<&shared::language::Language>) -> Vec<(String, String)> {
+        match self.open_close_symbols_str() {
+            Some((open, close)) => vec![(open.to_string(), close.to_string())],
+            None => language
+                .map(|language| language.keyword_enclosures().to_vec())
+                .unwrap_or_default(),
         }
     }
 
@@ -120,6 +314,7 @@ impl EnclosureKind {
             EnclosureKind::DoubleQuotes => "Double Quotes",
             EnclosureKind::SingleQuotes => "Single Quotes",
             EnclosureKind::Backticks => "Backticks",
+            EnclosureKind::Keywords => "Keywords",
         }
     }
 }
@@ -166,3 +361,74 @@ mod test_surround {
         run_test("'hello'", SingleQuotes, 2, Some((0, 6)));
     }
 }
+
+#[cfg(test)]
+mod test_syntax_aware_surround {
+    use super::*;
+
+    fn buffer(extension: &str, content: &str) -> Buffer {
+        let language = crate::config::from_extension(extension).unwrap();
+        let mut buffer = Buffer::new(language.tree_sitter_language(), content);
+        buffer.set_language(language).unwrap();
+        buffer
+    }
+
+    fn run_test(
+        buffer: &Buffer,
+        kind: EnclosureKind,
+        cursor_char_index: usize,
+        expected: Option<(std::ops::Range<usize>, std::ops::Range<usize>)>,
+    ) {
+        let actual = get_surrounding_ranges(buffer, kind, CharIndex(cursor_char_index), true);
+        assert_eq!(
+            actual,
+            expected.map(|(open, close)| (
+                (CharIndex(open.start)..CharIndex(open.end)).into(),
+                (CharIndex(close.start)..CharIndex(close.end)).into()
+            ))
+        );
+    }
+
+    #[test]
+    fn ignore_symbols_in_strings() {
+        let buffer = buffer("rs", r#"f("(", x)"#);
+        run_test(&buffer, EnclosureKind::Parentheses, 7, Some((1..2, 8..9)));
+    }
+
+    #[test]
+    fn ignore_unpaired_symbols_outside_of_syntax_nodes() {
+        let buffer = buffer("rs", r#"let a = "("; let b = ")";"#);
+        run_test(&buffer, EnclosureKind::Parentheses, 13, None);
+    }
+
+    #[test]
+    fn symbols_in_comments() {
+        let buffer = buffer("rs", "// (hello) x");
+        run_test(&buffer, EnclosureKind::Parentheses, 5, Some((3..4, 9..10)));
+    }
+
+    #[test]
+    fn keywords() {
+        let buffer = buffer("rb", "begin\n  x\nend");
+        run_test(&buffer, EnclosureKind::Keywords, 8, Some((0..5, 10..13)));
+        run_test(&buffer, EnclosureKind::Parentheses, 8, None);
+    }
+
+    #[test]
+    fn matching_pair() {
+        let matching_pair = |buffer: &Buffer, cursor_char_index: usize| {
+            get_matching_pair_range(buffer, CharIndex(cursor_char_index))
+                .map(|range| range.start.0..range.end.0)
+        };
+        let rust = buffer("rs", "fn f() { g(x) }");
+        assert_eq!(matching_pair(&rust, 7), Some(14..15));
+        assert_eq!(matching_pair(&rust, 14), Some(7..8));
+        assert_eq!(matching_pair(&rust, 12), Some(10..11));
+        // Not on a delimiter
+        assert_eq!(matching_pair(&rust, 9), Some(14..15));
+
+        let ruby = buffer("rb", "begin\n  x\nend");
+        assert_eq!(matching_pair(&ruby, 0), Some(10..13));
+        assert_eq!(matching_pair(&ruby, 11), Some(0..5));
+    }
+}
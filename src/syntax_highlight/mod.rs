@@ -38,11 +38,19 @@ impl GetHighlightConfig for Language {
         let Some(highlights_query) = &self.highlight_query() else {
             return Ok(None);
         };
+        // Injection queries of grammars built from source may use unsupported predicates,
+        // which should not prevent the highlighting of the host language
+        let injection_query = self
+            .compiled_injection_query()
+            .ok()
+            .flatten()
+            .and_then(|_| self.injection_query())
+            .unwrap_or_default();
         let mut config = HighlightConfiguration::new(
             tree_sitter_language,
             "highlight".to_string(),
             highlights_query,
-            self.injection_query().unwrap_or_default(),
+            &injection_query,
             self.locals_query().unwrap_or_default(),
         )?;
 
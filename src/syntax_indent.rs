@@ -0,0 +1,266 @@
+//! Syntax-aware indentation, driven by the `indents.scm` query of a language.
+//!
+//! The query uses the following captures:
+//! - `@indent`: lines inside the node are indented one level deeper than the line where the node starts.
+//! - `@extend`: the `@indent` node also applies to a line inserted right after its end,
+//!   which is necessary for languages whose blocks are not closed by a delimiter, like Python.
+//! - `@outdent`: a line starting with this token is aligned with the line where the node it closes starts,
+//!   for example `}` or `elif`.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use tree_sitter::StreamingIterator;
+
+use crate::buffer::Buffer;
+
+pub(crate) struct SyntaxIndent<'a> {
+    buffer: &'a Buffer,
+    tree: &'a tree_sitter::Tree,
+    indent: HashSet<usize>,
+    extend: HashSet<usize>,
+    outdent: HashSet<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SyntaxIndentation {
+    Inside(String),
+    /// The line starts with the token that closes its enclosing node,
+    /// where `inner` is the indentation of the content of that node.
+    Closing {
+        indentation: String,
+        inner: String,
+    },
+}
+
+impl SyntaxIndentation {
+    pub(crate) fn indentation(&self) -> &str {
+        match self {
+            SyntaxIndentation::Inside(indentation) => indentation,
+            SyntaxIndentation::Closing { indentation, .. } => indentation,
+        }
+    }
+}
+
+impl<'a> SyntaxIndent<'a> {
+    /// Returns `None` if the language of the buffer has no indents query.
+    ///
+    /// Only lines within `byte_range` can be indented with the returned value.
+    pub(crate) fn new(buffer: &'a Buffer, byte_range: Range<usize>) -> Option<Self> {
+        let tree = buffer.tree()?;
+        let query = buffer
+            .language()?
+            .compiled_indents_query()
+            .inspect_err(|error| log::error!("[SyntaxIndent::new] {error:?}"))
+            .ok()??;
+        let mut indent = HashSet::new();
+        let mut extend = HashSet::new();
+        let mut outdent = HashSet::new();
+        let rope = buffer.rope();
+        let mut cursor = tree_sitter::QueryCursor::new();
+        // The indentation of a line depends on previous lines of the same top-level node,
+        // and on nodes that end right before the range
+        let root = tree.root_node();
+        let byte_range = byte_range.start.saturating_sub(1)..byte_range.end + 1;
+        let top_level_node = root
+            .children(&mut root.walk())
+            .filter(|node| node.start_byte() < byte_range.end && byte_range.start < node.end_byte())
+            .map(|node| node.byte_range())
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end));
+        cursor.set_byte_range(match top_level_node {
+            Some(range) => range.start.min(byte_range.start)..range.end.max(byte_range.end),
+            None => byte_range,
+        });
+        let mut captures = cursor.captures(&query, tree.root_node(), |node: tree_sitter::Node| {
+            rope.byte_slice(node.byte_range())
+                .chunks()
+                .map(str::as_bytes)
+        });
+        while let Some((query_match, index)) = captures.next() {
+            let capture = query_match.captures[*index];
+            match query.capture_names()[capture.index as usize] {
+                "indent" => indent.insert(capture.node.id()),
+                "extend" => extend.insert(capture.node.id()),
+                "outdent" => outdent.insert(capture.node.id()),
+                _ => false,
+            };
+        }
+        Some(Self {
+            buffer,
+            tree,
+            indent,
+            extend,
+            outdent,
+        })
+    }
+
+    /// Returns the innermost `@indent` node that encloses a line whose content starts at `byte`,
+    /// and whether the line starts with the token that closes that node.
+    fn anchor(&self, byte: usize) -> Option<(tree_sitter::Node<'a>, bool)> {
+        let leaf = self
+            .tree
+            .root_node()
+            .descendant_for_byte_range(byte, byte)?;
+        let mut enclosing = None;
+        let mut node = Some(leaf);
+        while let Some(current) = node {
+            if current.start_byte() < byte {
+                enclosing.get_or_insert(current.id());
+                let applies = byte < current.end_byte()
+                    || (byte == current.end_byte() && self.extend.contains(&current.id()));
+                if applies && self.indent.contains(&current.id()) {
+                    let closes = leaf.start_byte() == byte
+                        && self.outdent.contains(&leaf.id())
+                        && enclosing == Some(current.id());
+                    return Some((current, closes));
+                }
+            }
+            node = current.parent();
+        }
+        None
+    }
+
+    /// Computes the indentation of a line whose content starts at `byte`.
+    ///
+    /// The content of a node is aligned with its previous lines if there are any,
+    /// otherwise it is indented by `unit` relative to the line where the node starts.
+    ///
+    /// `line_indentation` returns the indentation of a previous line,
+    /// which allows reindenting multiple lines before applying any edit.
+    ///
+    /// Returns `None` if the indentation cannot be determined,
+    /// which happens when no node encloses the line and the syntax tree contains errors.
+    pub(crate) fn indentation(
+        &self,
+        byte: usize,
+        unit: &str,
+        line_indentation: impl Fn(usize) -> String,
+    ) -> Option<SyntaxIndentation> {
+        let Some((anchor, closes)) = self.anchor(byte) else {
+            return (!self.tree.root_node().has_error())
+                .then(|| SyntaxIndentation::Inside(String::new()));
+        };
+        let anchor_line = anchor.start_position().row;
+        let indentation = line_indentation(anchor_line);
+        let line = self.buffer.byte_to_line(byte).ok()?;
+        let sibling_line = (anchor_line + 1..=line).rev().find(|&line_index| {
+            let Some(first_token_byte) = self.first_token_byte(line_index) else {
+                return false;
+            };
+            first_token_byte < byte
+                && matches!(
+                    self.anchor(first_token_byte),
+                    Some((node, false)) if node.id() == anchor.id()
+                )
+        });
+        let inner = match sibling_line {
+            Some(sibling_line) => line_indentation(sibling_line),
+            None => format!("{indentation}{unit}"),
+        };
+        Some(if closes {
+            SyntaxIndentation::Closing { indentation, inner }
+        } else {
+            SyntaxIndentation::Inside(inner)
+        })
+    }
+
+    /// Returns `None` if the given line is blank.
+    fn first_token_byte(&self, line_index: usize) -> Option<usize> {
+        let line = self.buffer.get_line_by_line_index(line_index).ok()?;
+        if line.chars().all(char::is_whitespace) {
+            return None;
+        }
+        Some(
+            self.buffer.line_to_byte(line_index).ok()? + self.leading_whitespaces(line_index).len(),
+        )
+    }
+
+    /// Computes the indentation of each line from top to bottom,
+    /// so that every line is indented relative to the new indentation of the lines above it.
+    ///
+    /// Lines that are blank are not indented.
+    pub(crate) fn reindent(&self, line_range: Range<usize>, unit: &str) -> Vec<(usize, String)> {
+        let mut result: Vec<(usize, String)> = Vec::new();
+        for line_index in line_range {
+            if self.buffer.get_line_by_line_index(line_index).is_err() {
+                break;
+            }
+            let indentation = match self.first_token_byte(line_index) {
+                None => String::new(),
+                Some(byte) => {
+                    let Some(indentation) = self.indentation(byte, unit, |line_index| {
+                        result
+                            .iter()
+                            .find(|(index, _)| index == &line_index)
+                            .map(|(_, indentation)| indentation.clone())
+                            .unwrap_or_else(|| self.leading_whitespaces(line_index))
+                    }) else {
+                        continue;
+                    };
+                    indentation.indentation().to_string()
+                }
+            };
+            result.push((line_index, indentation))
+        }
+        result
+    }
+
+    /// The leading whitespaces of the given line of the buffer.
+    pub(crate) fn leading_whitespaces(&self, line_index: usize) -> String {
+        self.buffer
+            .get_line_by_line_index(line_index)
+            .map(|line| {
+                line.chars()
+                    .take_while(|c| matches!(c, ' ' | '\t'))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test_syntax_indent {
+    use super::*;
+
+    fn reindent(extension: &str, content: &str) -> Vec<String> {
+        let language = crate::config::from_extension(extension).unwrap();
+        let mut buffer = Buffer::new(language.tree_sitter_language(), content);
+        buffer.set_language(language).unwrap();
+        SyntaxIndent::new(&buffer, 0..content.len())
+            .unwrap()
+            .reindent(0..buffer.len_lines(), "  ")
+            .into_iter()
+            .map(|(_, indentation)| indentation)
+            .collect()
+    }
+
+    #[test]
+    fn rust() {
+        assert_eq!(
+            reindent(
+                "rs",
+                "fn f() {
+let x = g(
+a,
+);
+
+}"
+            ),
+            ["", "  ", "    ", "  ", "", ""]
+        );
+    }
+
+    #[test]
+    fn python_outdent() {
+        assert_eq!(
+            reindent(
+                "py",
+                "if x:
+        y
+elif z:
+ w"
+            ),
+            ["", "  ", "", "  "]
+        );
+    }
+}
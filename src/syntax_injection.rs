@@ -0,0 +1,196 @@
+//! Regions of a buffer that are written in another language, such as a code fence in Markdown
+//! or a `<script>` element in HTML, as specified by the `injections.scm` query of a language.
+//!
+//! The query uses the following captures:
+//! - `@injection.content`: the node whose text is parsed with the injected language.
+//! - `@injection.language`: the node whose text is the name of the injected language,
+//!   which can also be given with `(#set! injection.language "javascript")`.
+
+use std::ops::Range;
+
+use shared::language::Language;
+use tree_sitter::{Parser, QueryCursor, StreamingIterator, Tree};
+
+/// Injections within injections are only followed up to this depth.
+const MAX_DEPTH: usize = 4;
+
+#[derive(Clone)]
+pub struct Injection {
+    language: Language,
+    /// Parsed from the whole content of the buffer, with only `byte_range` included,
+    /// so that the byte offsets of its nodes are those of the buffer.
+    tree: Tree,
+    byte_range: Range<usize>,
+    /// The text within `byte_range` when `tree` was parsed,
+    /// so that the tree can be reused if neither the text nor its position changes.
+    content: String,
+}
+
+impl Injection {
+    pub fn language(&self) -> &Language {
+        &self.language
+    }
+
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    pub fn byte_range(&self) -> &Range<usize> {
+        &self.byte_range
+    }
+}
+
+/// Returns the injections of `tree`, followed by the injections nested within each of them.
+///
+/// Only the injections that are not found in `previous` with the same language, range and text are parsed,
+/// so that an edit only reparses the injections it touches, or those after it.
+pub(crate) fn injections(
+    language: &Language,
+    tree: &Tree,
+    source: &str,
+    previous: &[Injection],
+) -> Vec<Injection> {
+    injections_at_depth(language, tree, source, previous, 0)
+}
+
+fn injections_at_depth(
+    language: &Language,
+    tree: &Tree,
+    source: &str,
+    previous: &[Injection],
+    depth: usize,
+) -> Vec<Injection> {
+    if depth >= MAX_DEPTH {
+        return Vec::new();
+    }
+    let query = match language.compiled_injection_query() {
+        Ok(Some(query)) => query,
+        Ok(None) => return Vec::new(),
+        Err(error) => {
+            log::error!("[syntax_injection::injections] {error:?}");
+            return Vec::new();
+        }
+    };
+    let Some(content_index) = query.capture_index_for_name("injection.content") else {
+        return Vec::new();
+    };
+    let language_index = query.capture_index_for_name("injection.language");
+
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+    let mut result = Vec::new();
+    while let Some(query_match) = matches.next() {
+        let name = query_match
+            .captures
+            .iter()
+            .find(|capture| Some(capture.index) == language_index)
+            .and_then(|capture| capture.node.utf8_text(source.as_bytes()).ok())
+            .or_else(|| {
+                query
+                    .property_settings(query_match.pattern_index)
+                    .iter()
+                    .find(|property| &*property.key == "injection.language")
+                    .and_then(|property| property.value.as_deref())
+            });
+        let Some(injected_language) = name.and_then(crate::config::from_injection_name) else {
+            continue;
+        };
+        let Some(injected_tree_sitter_language) = injected_language.tree_sitter_language() else {
+            continue;
+        };
+        for node in query_match.nodes_for_capture_index(content_index) {
+            let Some(content) = source.get(node.byte_range()) else {
+                continue;
+            };
+            let unchanged = previous.iter().find(|injection| {
+                injection.byte_range == node.byte_range()
+                    && injection.language == injected_language
+                    && injection.content == content
+            });
+            let injected_tree = match unchanged {
+                Some(injection) => Some(injection.tree.clone()),
+                None => {
+                    let mut parser = Parser::new();
+                    parser
+                        .set_language(&injected_tree_sitter_language)
+                        .ok()
+                        .and_then(|_| parser.set_included_ranges(&[node.range()]).ok())
+                        .and_then(|_| parser.parse(source, None))
+                }
+            };
+            let Some(injected_tree) = injected_tree else {
+                continue;
+            };
+            let nested = injections_at_depth(
+                &injected_language,
+                &injected_tree,
+                source,
+                previous,
+                depth + 1,
+            );
+            result.push(Injection {
+                language: injected_language.clone(),
+                tree: injected_tree,
+                byte_range: node.byte_range(),
+                content: content.to_string(),
+            });
+            result.extend(nested);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test_syntax_injection {
+    use super::Injection;
+    use crate::buffer::Buffer;
+
+    #[test]
+    fn nested_in_markdown_code_fence() {
+        let content = "# Title\n\n```rust\nfn f() {}\n```\n";
+        let language = crate::config::from_extension("md").unwrap();
+        let mut buffer = Buffer::new(language.tree_sitter_language(), content);
+        buffer.set_language(language).unwrap();
+        let byte = content.find("fn").unwrap();
+        let injection = buffer.injection_at(byte).unwrap();
+        assert_eq!(injection.tree().root_node().kind(), "source_file");
+        assert_eq!(&content[injection.byte_range().clone()], "fn f() {}\n");
+        assert!(buffer.injection_at(0).is_none());
+    }
+
+    #[test]
+    fn only_changed_injections_are_reparsed() {
+        let content = "```rust\nfn f() {}\n```\n\n```rust\nfn g() {}\n```\n";
+        let language = crate::config::from_extension("md").unwrap();
+        let tree = Buffer::new(language.tree_sitter_language(), content)
+            .tree()
+            .unwrap()
+            .clone();
+        let root_id = |injections: &[Injection], text: &str| {
+            injections
+                .iter()
+                .find(|injection| injection.content == text)
+                .unwrap()
+                .tree()
+                .root_node()
+                .id()
+        };
+        let previous = super::injections(&language, &tree, content, &[]);
+        let mut outdated = previous.clone();
+        outdated
+            .iter_mut()
+            .filter(|injection| injection.content == "fn g() {}\n")
+            .for_each(|injection| injection.content = String::new());
+
+        // The tree of an unchanged injection is reused, while the others are parsed again
+        let injections = super::injections(&language, &tree, content, &outdated);
+        assert_eq!(
+            root_id(&injections, "fn f() {}\n"),
+            root_id(&previous, "fn f() {}\n")
+        );
+        assert_ne!(
+            root_id(&injections, "fn g() {}\n"),
+            root_id(&previous, "fn g() {}\n")
+        );
+    }
+}
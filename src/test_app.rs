@@ -33,7 +33,7 @@ use crate::{
     components::{component::RenderTitleMode, editor_keymap::BUILTIN_KEYBOARD_LAYOUTS},
     lsp::process::ResponseContext,
     scripting::{ScriptInput, ScriptOutput},
-    selection_mode::GetGapMovement,
+    selection_mode::{GetGapMovement, PatternKind},
 };
 
 use shared::{absolute_path::AbsolutePath, language::LanguageId};
@@ -43,8 +43,8 @@ use crate::layout::BufferContentsMap;
 
 use crate::{
     app::{
-        App, Dimension, Dispatch, LocalSearchConfigUpdate, RequestParams, Scope,
-        StatusLineComponent,
+        App, Dimension, Dispatch, GlobalSearchScopeUpdate, LocalSearchConfigUpdate, RequestParams,
+        Scope, StatusLineComponent,
     },
     buffer::{Buffer, BufferOwner},
     char_index_range::CharIndexRange,
@@ -59,7 +59,7 @@ use crate::{
         prompt::PromptHistoryKey,
         suggestive_editor::{DispatchSuggestiveEditor, Info, SuggestiveEditorFilter},
     },
-    context::{Context, GlobalMode, LocalSearchConfigMode},
+    context::{Context, GlobalMode, GlobalSearchLimit, GlobalSearchScope, LocalSearchConfigMode},
     frontend::{mock::MockFrontend, MyWriter, NullWriter, StringWriter},
     git::DiffMode,
     grid::{IndexedHighlightGroup, StyleKey},
@@ -130,6 +130,7 @@ pub enum ExpectKind {
     EditorInfoContents(&'static [&'static str]),
     EditorInfoContentMatches(&'static lazy_regex::Lazy<regex::Regex>),
     GlobalInfoContents(&'static [&'static str]),
+    GlobalInfoContentMatches(&'static lazy_regex::Lazy<regex::Regex>),
     QuickfixListCurrentLine(&'static str),
     DropdownInfosCount(usize),
     QuickfixListContent(String),
@@ -159,6 +160,7 @@ pub enum ExpectKind {
     CurrentViewAlignment(Option<ViewAlignment>),
     ComponentsLength(usize),
     Quickfixes(Box<[QuickfixListItem]>),
+    QuickfixesCount(usize),
     AppGrid(String),
     AppGridContains(&'static str),
     EditorGrid(&'static str),
@@ -218,6 +220,8 @@ pub enum ExpectKind {
     AppCursorPosition(Position),
     CurrentMarks(Vec<(AbsolutePath, Vec<CharIndexRange>)>),
     CurrentTreeReparsedCount(usize),
+    CurrentBufferLargeFileMode(bool),
+    CurrentBufferReadOnly(bool),
 }
 fn log<T: std::fmt::Debug>(s: T) {
     if !is_ci::cached() {
@@ -281,6 +285,9 @@ impl ExpectKind {
                 contextualize(app.get_current_selected_texts(), to_vec(selected_texts))
             }
             ComponentsLength(length) => contextualize(app.components().len(), *length),
+            QuickfixesCount(expected) => {
+                contextualize(expected, &app.quickfix_list().items().len())
+            }
             Quickfixes(expected_quickfixes) => contextualize(
                 app.quickfix_list()
                     .items()
@@ -511,6 +518,10 @@ impl ExpectKind {
                 app.global_info_contents(),
                 expected.iter().map(|s| s.to_string()).collect(),
             ),
+            GlobalInfoContentMatches(regex) => {
+                let content = app.global_info().unwrap_or_default();
+                contextualize_regex_match(&content, regex)
+            }
             AppGridContains(substring) => {
                 let content = app.get_screen().unwrap().stringify();
                 println!("Actual=\n{}", content);
@@ -709,6 +720,22 @@ impl ExpectKind {
                     .buffer()
                     .tree_reparsed_count,
             ),
+            CurrentBufferLargeFileMode(expected) => contextualize(
+                expected,
+                &app.get_current_editor()
+                    .borrow()
+                    .editor()
+                    .buffer()
+                    .large_file_mode(),
+            ),
+            CurrentBufferReadOnly(expected) => contextualize(
+                expected,
+                &app.get_current_editor()
+                    .borrow()
+                    .editor()
+                    .buffer()
+                    .read_only(),
+            ),
         })
     }
 }
@@ -2081,6 +2108,7 @@ fn global_search_replace_regex() -> Result<(), anyhow::Error> {
             escaped: true,
             case_sensitive: false,
             match_whole_word: false,
+            multi_line: false,
         }),
         main_content: "main foo",
         foo_content: "foo foo",
@@ -2119,6 +2147,363 @@ fn global_search_replace_naming_convention_agnostic() -> Result<(), anyhow::Erro
     })
 }
 
+#[test]
+fn global_search_replace_multi_line_regex() -> Result<(), anyhow::Error> {
+    test_global_search_replace(TestGlobalSearchReplaceArgs {
+        mode: LocalSearchConfigMode::Regex(RegexConfig {
+            multi_line: true,
+            ..RegexConfig::regex()
+        }),
+        main_content: "foo(\n    bar)",
+        foo_content: "foo(\n  bar); foo(baz)",
+        search: r"foo\(\s*\n\s*bar",
+        replacement: "foo(bar",
+        main_replaced: "foo(bar)",
+        foo_replaced: "foo(bar); foo(baz)",
+    })
+}
+
+#[test]
+fn global_search_multi_line_regex() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        let new_dispatch = |update: LocalSearchConfigUpdate| -> Dispatch {
+            UpdateLocalSearchConfig {
+                update,
+                scope: Scope::Global,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: false,
+            }
+        };
+        Box::new([
+            App(OpenFile {
+                path: s.foo_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("foo(baz);\nfoo(\n    bar);".to_string())),
+            App(SaveAll),
+            App(new_dispatch(LocalSearchConfigUpdate::Mode(
+                LocalSearchConfigMode::Regex(RegexConfig {
+                    multi_line: true,
+                    ..RegexConfig::regex()
+                }),
+            ))),
+            App(UpdateLocalSearchConfig {
+                update: LocalSearchConfigUpdate::Search(r"foo\(\s*\n\s*bar".to_string()),
+                scope: Scope::Global,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: true,
+            }),
+            WaitForAppMessage(regex!("GlobalSearchFinished")),
+            Expect(Quickfixes(Box::new([QuickfixListItem::new(
+                Location {
+                    path: s.foo_rs(),
+                    range: (CharIndex(10)..CharIndex(22)).into(),
+                },
+                None,
+                // Only the first line of the match is shown
+                Some("foo(\n".to_string()),
+            )]))),
+            Expect(CurrentSelectedTexts(&["foo(\n    bar"])),
+        ])
+    })
+}
+
+#[test]
+fn global_search_scope() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        let search = || UpdateLocalSearchConfig {
+            update: LocalSearchConfigUpdate::Search("needle".to_string()),
+            scope: Scope::Global,
+            if_current_not_found: IfCurrentNotFound::LookForward,
+            run_search_after_config_updated: true,
+        };
+        let quickfix = |path: AbsolutePath| {
+            QuickfixListItem::new(
+                Location {
+                    path,
+                    range: (CharIndex(0)..CharIndex(6)).into(),
+                },
+                None,
+                Some("needle".to_string()),
+            )
+        };
+        Box::new([
+            App(OpenFile {
+                path: s.foo_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("needle".to_string())),
+            App(OpenFile {
+                path: s.hello_ts(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("needle".to_string())),
+            App(ToggleFileMark),
+            App(SaveAll),
+            App(UpdateGlobalSearchScope(GlobalSearchScopeUpdate::Scope(
+                GlobalSearchScope {
+                    file_types: vec!["rust".to_string()],
+                    ..Default::default()
+                },
+            ))),
+            App(search()),
+            WaitForAppMessage(regex!("GlobalSearchFinished")),
+            Expect(Quickfixes(Box::new([quickfix(s.foo_rs())]))),
+            App(UpdateGlobalSearchScope(GlobalSearchScopeUpdate::Scope(
+                GlobalSearchScope {
+                    limit: GlobalSearchLimit::MarkedFiles,
+                    ..Default::default()
+                },
+            ))),
+            App(search()),
+            WaitForAppMessage(regex!("GlobalSearchFinished")),
+            Expect(Quickfixes(Box::new([quickfix(s.hello_ts())]))),
+        ])
+    })
+}
+
+#[test]
+fn global_search_progress_and_cancellation() -> Result<(), anyhow::Error> {
+    execute_test_helper(
+        || Box::new(NullWriter),
+        false,
+        [StatusLine::new(
+            [StatusLineComponent::GlobalSearchProgress].to_vec(),
+        )]
+        .to_vec(),
+        |s| {
+            let directory = s.new_path("many");
+            std::fs::create_dir_all(&directory).unwrap();
+            for index in 0..1000 {
+                std::fs::write(directory.join(format!("{index}.txt")), "needle").unwrap();
+            }
+            let search = || UpdateLocalSearchConfig {
+                update: LocalSearchConfigUpdate::Search("needle".to_string()),
+                scope: Scope::Global,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: true,
+            };
+            Box::new([
+                App(OpenFile {
+                    path: s.foo_rs(),
+                    owner: BufferOwner::User,
+                    focus: true,
+                }),
+                Editor(SetContent("needle needle".to_string())),
+                App(ToggleFileMark),
+                App(SaveAll),
+                App(UpdateGlobalSearchScope(GlobalSearchScopeUpdate::Scope(
+                    GlobalSearchScope {
+                        limit: GlobalSearchLimit::MarkedFiles,
+                        ..Default::default()
+                    },
+                ))),
+                App(search()),
+                // The progress is shown until the search is finished,
+                // and the matches are counted before they are sent
+                WaitForAppMessage(regex!("AddQuickfixListEntries")),
+                Expect(AppGridContains("Searching: ")),
+                Expect(AppGridContains("2 matches")),
+                Editor(HandleEsc),
+                Expect(Not(Box::new(AppGridContains("Searching: ")))),
+                // Cancelling a running search stops reporting its matches
+                App(UpdateGlobalSearchScope(GlobalSearchScopeUpdate::Scope(
+                    Default::default(),
+                ))),
+                App(search()),
+                Expect(AppGridContains("Searching: ")),
+                Editor(HandleEsc),
+                Expect(Not(Box::new(AppGridContains("Searching: ")))),
+                WaitForAppMessage(regex!("GlobalSearchFinished")),
+                Expect(Not(Box::new(QuickfixesCount(1002)))),
+            ])
+        },
+        true,
+        RunTestOptions {
+            enable_lsp: false,
+            enable_syntax_highlighting: false,
+            enable_file_watcher: false,
+        },
+    )?;
+    Ok(())
+}
+
+#[test]
+fn global_search_and_replace_use_unsaved_buffer_contents() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        let new_dispatch = |update: LocalSearchConfigUpdate| -> Dispatch {
+            UpdateLocalSearchConfig {
+                update,
+                scope: Scope::Global,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: false,
+            }
+        };
+        let foo_rs = s.foo_rs();
+        Box::new([
+            App(OpenFile {
+                path: s.foo_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("foo".to_string())),
+            App(SaveAll),
+            // This change is not saved
+            Editor(SetContent("bar foo".to_string())),
+            App(new_dispatch(LocalSearchConfigUpdate::Replacement(
+                "spam".to_string(),
+            ))),
+            App(UpdateLocalSearchConfig {
+                update: LocalSearchConfigUpdate::Search("foo".to_string()),
+                scope: Scope::Global,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: true,
+            }),
+            WaitForAppMessage(regex!("GlobalSearchFinished")),
+            Expect(Quickfixes(Box::new([QuickfixListItem::new(
+                Location {
+                    path: s.foo_rs(),
+                    range: (CharIndex(4)..CharIndex(7)).into(),
+                },
+                None,
+                Some("bar foo".to_string()),
+            )]))),
+            App(Dispatch::Replace {
+                scope: Scope::Global,
+            }),
+            Expect(FileContent(s.foo_rs(), "bar spam".to_string())),
+            // The buffer is not saved, because it has unsaved changes
+            Expect(EditorIsDirty()),
+            ExpectCustom(Box::new(move || {
+                assert_eq!(foo_rs.read().unwrap(), "foo");
+            })),
+        ])
+    })
+}
+
+#[test]
+fn preview_exclude_and_apply_global_replace() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        let new_dispatch = |update: LocalSearchConfigUpdate| -> Dispatch {
+            UpdateLocalSearchConfig {
+                update,
+                scope: Scope::Global,
+                if_current_not_found: IfCurrentNotFound::LookForward,
+                run_search_after_config_updated: false,
+            }
+        };
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("foo".to_string())),
+            App(OpenFile {
+                path: s.foo_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("foo\nbar\nfoo\n".to_string())),
+            App(SaveAll),
+            App(new_dispatch(LocalSearchConfigUpdate::Search(
+                "foo".to_string(),
+            ))),
+            App(new_dispatch(LocalSearchConfigUpdate::Replacement(
+                "spam".to_string(),
+            ))),
+            App(PreviewGlobalReplace),
+            // Nothing is changed by the preview
+            Expect(FileContent(s.foo_rs(), "foo\nbar\nfoo\n".to_string())),
+            Expect(CurrentComponentPath(Some(s.foo_rs()))),
+            Expect(CurrentSelectedTexts(&["foo"])),
+            App(ExcludeFromReplacePreview { whole_file: false }),
+            App(ApplyReplacePreview),
+            Expect(FileContent(s.foo_rs(), "foo\nbar\nspam\n".to_string())),
+            Expect(FileContent(s.main_rs(), "spam".to_string())),
+            // The replacement of each file is undone at once
+            App(OpenFile {
+                path: s.foo_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(CoarseUndo),
+            Expect(FileContent(s.foo_rs(), "foo\nbar\nfoo\n".to_string())),
+        ])
+    })
+}
+
+#[test]
+fn ast_grep_rules() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        std::fs::write(s.new_path("sgconfig.yml"), "ruleDirs:\n  - rules\n").unwrap();
+        std::fs::create_dir_all(s.new_path("rules")).unwrap();
+        std::fs::write(
+            s.new_path("rules/no-unwrap.yml"),
+            "id: no-unwrap
+language: rust
+severity: warning
+message: Avoid unwrap
+rule:
+  pattern: $A.unwrap()
+fix: $A.expect(\"TODO\")
+",
+        )
+        .unwrap();
+        std::fs::write(s.main_rs().to_path_buf(), "fn main() { x.unwrap(); }").unwrap();
+        Box::new([
+            // Saving a YAML file reloads the rules
+            App(OpenFile {
+                path: s.temp_dir().join("sgconfig.yml").unwrap(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(Save),
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Expect(DiagnosticsRanges(vec![
+                (CharIndex(12)..CharIndex(22)).into()
+            ])),
+            App(PreviewAstGrepFixes),
+            Expect(FileContent(
+                s.main_rs(),
+                "fn main() { x.unwrap(); }".to_string(),
+            )),
+            App(ApplyReplacePreview),
+            Expect(FileContent(
+                s.main_rs(),
+                "fn main() { x.expect(\"TODO\"); }".to_string(),
+            )),
+        ])
+    })
+}
+
+#[test]
+fn invalid_ast_grep_rules_do_not_fail_the_save() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        std::fs::write(s.new_path("sgconfig.yml"), "ruleDirs:\n  - rules\n").unwrap();
+        std::fs::create_dir_all(s.new_path("rules")).unwrap();
+        std::fs::write(s.new_path("rules/invalid.yml"), "id: [").unwrap();
+        Box::new([
+            App(OpenFile {
+                path: s.temp_dir().join("rules/invalid.yml").unwrap(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(Save),
+            Expect(GlobalInfoContentMatches(regex!(
+                r"Failed to load the ast-grep rules of .*invalid\.yml"
+            ))),
+        ])
+    })
+}
+
 #[test]
 fn quickfix_list_basic() -> Result<(), anyhow::Error> {
     execute_test(|s| {
@@ -4117,6 +4502,70 @@ fn go_to_file_with_multiple_selections() -> Result<(), anyhow::Error> {
     })
 }
 
+#[test]
+fn go_to_file_path_with_line_and_column() -> Result<(), anyhow::Error> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("warning: src/foo.rs:2:5: unused".to_string())),
+            Editor(SetSelectionMode(
+                IfCurrentNotFound::LookForward,
+                SelectionMode::Pattern(PatternKind::FilePath),
+            )),
+            Expect(CurrentSelectedTexts(&["src/foo.rs:2:5"])),
+            Editor(GoToFile),
+            Expect(CurrentComponentPath(Some(s.foo_rs()))),
+            Expect(CurrentSelectedTexts(&["a"])),
+            // The position is clamped to the content of the opened buffer
+            Editor(SetContent("fn f() {}\nsrc/main.rs:99:99".to_string())),
+            Editor(MatchLiteral("src".to_string())),
+            Editor(SetSelectionMode(
+                IfCurrentNotFound::LookForward,
+                SelectionMode::Pattern(PatternKind::FilePath),
+            )),
+            Editor(GoToFile),
+            Expect(CurrentComponentPath(Some(s.main_rs()))),
+            Expect(CurrentComponentContent("warning: src/foo.rs:2:5: unused")),
+            Expect(EditorCursorPosition(Position::new(0, 31))),
+        ])
+    })
+}
+
+#[test]
+fn go_to_file_path_relative_to_home() -> Result<(), anyhow::Error> {
+    // The file has to exist in the actual home directory for the path to be resolved
+    let file = tempfile::Builder::new()
+        .suffix(".rs")
+        .tempfile_in(AbsolutePath::home_dir()?)?;
+    let path: AbsolutePath = file.path().try_into()?;
+    let file_name = file
+        .path()
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent(format!("~/{file_name}"))),
+            Editor(SetSelectionMode(
+                IfCurrentNotFound::LookForward,
+                SelectionMode::Line,
+            )),
+            Editor(GoToFile),
+            Expect(CurrentComponentPath(Some(path.clone()))),
+        ])
+    })
+}
+
 #[test]
 fn closing_all_buffers_should_land_on_scratch_buffer() -> Result<(), anyhow::Error> {
     execute_test(|s| {
@@ -4610,3 +5059,290 @@ fn ctrl_z_should_trigger_suspend_regardless_of_current_layout() -> anyhow::Resul
         ])
     })
 }
+
+#[test]
+fn toggling_large_file_mode() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Expect(CurrentBufferLargeFileMode(false)),
+            App(ToggleLargeFileMode),
+            Expect(CurrentBufferLargeFileMode(true)),
+            // Editing should still work in large-file mode
+            Editor(SetContent("hello".to_string())),
+            Expect(CurrentComponentContent("hello")),
+            App(ToggleLargeFileMode),
+            Expect(CurrentBufferLargeFileMode(false)),
+        ])
+    })
+}
+
+#[test]
+fn large_file_mode_closes_lsp_document() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            App(ToggleLargeFileMode),
+            Expect(ExpectKind::LspRequestSent(
+                FromEditor::TextDocumentDidClose {
+                    file_path: s.main_rs(),
+                },
+            )),
+        ])
+    })
+}
+
+#[test]
+fn opening_file_with_large_file_mode_override() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFileWithLargeFileMode {
+                path: s.main_rs(),
+                large_file_mode: true,
+            }),
+            Expect(CurrentBufferLargeFileMode(true)),
+            App(OpenFileWithLargeFileMode {
+                path: s.main_rs(),
+                large_file_mode: false,
+            }),
+            Expect(CurrentBufferLargeFileMode(false)),
+        ])
+    })
+}
+
+#[test]
+fn auto_save_is_disabled_by_default() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn main(){}".to_string())),
+            App(HandleEvent(event::event::Event::FocusLost)),
+            Expect(EditorIsDirty()),
+        ])
+    })
+}
+
+#[test]
+fn auto_save_on_focus_lost_without_formatter() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(SetAutoSaveConfig(crate::config::AutoSaveConfig {
+                enabled: true,
+                idle_delay_ms: 1000,
+                run_formatter: false,
+            })),
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn main(){}".to_string())),
+            App(HandleEvent(event::event::Event::FocusLost)),
+            Expect(Not(Box::new(EditorIsDirty()))),
+            Expect(FileContent(s.main_rs(), "fn main(){}".to_string())),
+            // Auto-save should not interrupt the user
+            Expect(CurrentMode(Mode::Normal)),
+            Expect(CurrentComponentPath(Some(s.main_rs()))),
+        ])
+    })
+}
+
+#[test]
+fn auto_save_when_cycling_marked_file() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(SetAutoSaveConfig(crate::config::AutoSaveConfig {
+                enabled: true,
+                idle_delay_ms: 1000,
+                run_formatter: false,
+            })),
+            App(OpenFile {
+                path: s.foo_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            App(ToggleFileMark),
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            App(ToggleFileMark),
+            Editor(SetContent("fn main(){}".to_string())),
+            App(CycleMarkedFile(Movement::Left)),
+            Expect(CurrentComponentPath(Some(s.foo_rs()))),
+            Expect(FileContent(s.main_rs(), "fn main(){}".to_string())),
+        ])
+    })
+}
+
+#[test]
+fn auto_save_shows_save_conflict_prompt_once() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(SetAutoSaveConfig(crate::config::AutoSaveConfig {
+                enabled: true,
+                idle_delay_ms: 1000,
+                run_formatter: false,
+            })),
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("ours".to_string())),
+            App(SetFileContent(s.main_rs(), "theirs".to_string())),
+            App(HandleEvent(event::event::Event::FocusLost)),
+            Expect(CurrentComponentTitle(
+                "Failed to save src/main.rs: The content of the file is newer.".to_string(),
+            )),
+            Expect(FileContent(s.main_rs(), "theirs".to_string())),
+            App(CloseCurrentWindow),
+            Expect(CurrentComponentPath(Some(s.main_rs()))),
+            App(HandleEvent(event::event::Event::FocusLost)),
+            Expect(CurrentComponentPath(Some(s.main_rs()))),
+            Expect(EditorIsDirty()),
+        ])
+    })
+}
+
+#[test]
+fn read_only_buffer_refuses_edits() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            App(ToggleReadOnly),
+            Expect(CurrentBufferReadOnly(true)),
+            App(HandleKeyEvents(keys!("h").to_vec())),
+            Expect(CurrentMode(Mode::Normal)),
+            Expect(GlobalInfo(
+                "This buffer is read-only, use \"Toggle Read-only\" to make it editable."
+                    .to_string(),
+            )),
+            App(ToggleReadOnly),
+            Expect(CurrentBufferReadOnly(false)),
+            Editor(EnterInsertMode(Direction::Start)),
+            Expect(CurrentMode(Mode::Insert)),
+        ])
+    })
+}
+
+#[test]
+fn file_at_revision_is_read_only() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn main() {}".to_string())),
+            App(OpenFileAtRevision(DiffMode::UnstagedAgainstCurrentBranch)),
+            Expect(CurrentComponentContent(
+                "mod foo;
+
+fn main() {
+    foo::foo();
+    println!(\"Hello, world!\");
+}
+",
+            )),
+            Expect(CurrentComponentPath(None)),
+            Expect(CurrentBufferReadOnly(true)),
+        ])
+    })
+}
+
+#[test]
+fn auto_save_skips_read_only_buffer() -> anyhow::Result<()> {
+    execute_test(|s| {
+        Box::new([
+            App(SetAutoSaveConfig(crate::config::AutoSaveConfig {
+                enabled: true,
+                idle_delay_ms: 1000,
+                run_formatter: false,
+            })),
+            App(OpenFile {
+                path: s.main_rs(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("fn main(){}".to_string())),
+            App(ToggleReadOnly),
+            App(HandleEvent(event::event::Event::FocusLost)),
+            Expect(EditorIsDirty()),
+        ])
+    })
+}
+
+#[test]
+fn editorconfig_is_honoured_by_indent_and_save() -> anyhow::Result<()> {
+    execute_test(|s| {
+        let notes = AbsolutePath::try_from(s.new_path("notes.txt")).unwrap();
+        Box::new([
+            App(SetFileContent(
+                AbsolutePath::try_from(s.new_path(".editorconfig")).unwrap(),
+                "root = true
+
+[*.txt]
+indent_style = tab
+trim_trailing_whitespace = true
+insert_final_newline = true
+"
+                .to_string(),
+            )),
+            App(SetFileContent(notes.clone(), "".to_string())),
+            App(OpenFile {
+                path: notes.clone(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("hello  ".to_string())),
+            Editor(Indent),
+            Expect(CurrentComponentContent("\thello  ")),
+            Editor(Save),
+            Expect(CurrentComponentContent("\thello\n")),
+            Expect(FileContent(notes.clone(), "\thello\n".to_string())),
+        ])
+    })
+}
+
+#[test]
+fn indentation_is_detected_and_overridable() -> anyhow::Result<()> {
+    execute_test(|s| {
+        let notes = AbsolutePath::try_from(s.new_path("notes.txt")).unwrap();
+        Box::new([
+            App(SetFileContent(
+                notes.clone(),
+                "a:\n  b:\n    c: 1\n".to_string(),
+            )),
+            App(OpenFile {
+                path: notes.clone(),
+                owner: BufferOwner::User,
+                focus: true,
+            }),
+            Editor(SetContent("x".to_string())),
+            Editor(Indent),
+            Expect(CurrentComponentContent("  x")),
+            Editor(SetIndentation(Some(('\t', 1)))),
+            Editor(Indent),
+            Expect(CurrentComponentContent("\t  x")),
+        ])
+    })
+}
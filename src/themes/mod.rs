@@ -120,6 +120,9 @@ impl Theme {
             StyleKey::HunkNewEmphasized => {
                 Style::new().background_color(self.hunk.new_emphasized_background)
             }
+            StyleKey::MergeConflictMarker => {
+                Style::new().background_color(self.hunk.old_emphasized_background)
+            }
             StyleKey::Syntax(highlight_group) => highlight_group
                 .to_highlight_name()
                 .and_then(|name| self.syntax.get_style(&name))
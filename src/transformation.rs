@@ -1,19 +1,72 @@
+use std::{cmp::Ordering, collections::hash_map::RandomState, hash::BuildHasher};
+
 use convert_case::Casing;
 use itertools::Itertools;
 use shared::process_command::ProcessCommand;
 
-use crate::{clipboard::Texts, selection_mode::NamingConventionAgnostic, soft_wrap::soft_wrap};
+use crate::{
+    arithmetic, clipboard::Texts, conversion::Conversion, increment::increment,
+    selection_mode::NamingConventionAgnostic, soft_wrap::soft_wrap,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Transformation {
     Case(convert_case::Case),
     Unwrap,
     Wrap,
+    HardWrap(HardWrap),
     PipeToShell { command: String },
     ReplaceWithCopiedText { copied_texts: Texts },
     RegexReplace { regex: MyRegex, replacement: String },
     NamingConventionAgnosticReplace { search: String, replacement: String },
     ToggleBlockComment { open: String, close: String },
+    Increment(Increment),
+    // Reorders the lines of each selection.
+    // With several selections, the editor reorders the selections instead.
+    Reorder(Reorder),
+    Convert(Conversion),
+    // Replaces the arithmetic expression of each selection with its value,
+    // or with `expression = value` if `keep_expression` is true.
+    Evaluate { keep_expression: bool },
+}
+
+/// Rewraps each paragraph to `width` columns, keeping the indentation
+/// and the line comment prefix of its first line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HardWrap {
+    pub width: usize,
+    pub line_comment_prefix: Option<String>,
+}
+
+/// Increments the first number, date, time or word ring member of each selection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Increment {
+    pub amount: i64,
+    /// If true, the nth selection is incremented by `n * amount`.
+    pub progressive: bool,
+    pub word_rings: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reorder {
+    Sort(SortKey),
+    Reverse,
+    /// Removes the duplicates of preceding items.
+    Unique,
+    Shuffle,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    Lexicographic,
+    CaseInsensitive,
+    /// Compares the runs of digits by their values, so that `item2` comes before `item10`.
+    Natural,
+    Length,
+    /// Compares the first capture group of the regex naturally,
+    /// or its whole match if it has no capture group.
+    /// Items that do not match come first.
+    Regex(MyRegex),
 }
 
 impl std::fmt::Display for Transformation {
@@ -26,6 +79,7 @@ impl std::fmt::Display for Transformation {
             ),
             Transformation::Unwrap => write!(f, "Unwrap",),
             Transformation::Wrap => write!(f, "Wrap",),
+            Transformation::HardWrap(HardWrap { width, .. }) => write!(f, "Hard Wrap to {width}"),
             Transformation::PipeToShell { command } => write!(f, "Pipe To Shell `{command}`",),
             Transformation::ReplaceWithCopiedText { .. } => {
                 write!(f, "Replace With Copied Text",)
@@ -47,10 +101,129 @@ impl std::fmt::Display for Transformation {
             Transformation::ToggleBlockComment { open, close } => {
                 write!(f, "Toggle Block Comment `{open} {close}`")
             }
+            Transformation::Increment(Increment {
+                amount,
+                progressive,
+                ..
+            }) => write!(
+                f,
+                "{} by {}{}",
+                if *amount < 0 {
+                    "Decrement"
+                } else {
+                    "Increment"
+                },
+                amount.unsigned_abs(),
+                if *progressive { " progressively" } else { "" }
+            ),
+            Transformation::Reorder(reorder) => write!(f, "{reorder}"),
+            Transformation::Convert(conversion) => write!(f, "{conversion}"),
+            Transformation::Evaluate { keep_expression } => write!(
+                f,
+                "Evaluate{}",
+                if *keep_expression {
+                    " (keep expression)"
+                } else {
+                    ""
+                }
+            ),
         }
     }
 }
 
+impl std::fmt::Display for Reorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reorder::Sort(SortKey::Lexicographic) => write!(f, "Sort"),
+            Reorder::Sort(SortKey::CaseInsensitive) => write!(f, "Sort (case-insensitive)"),
+            Reorder::Sort(SortKey::Natural) => write!(f, "Sort (natural)"),
+            Reorder::Sort(SortKey::Length) => write!(f, "Sort by Length"),
+            Reorder::Sort(SortKey::Regex(regex)) => write!(f, "Sort by /{}/", regex.0.as_str()),
+            Reorder::Reverse => write!(f, "Reverse"),
+            Reorder::Unique => write!(f, "Unique"),
+            Reorder::Shuffle => write!(f, "Shuffle"),
+        }
+    }
+}
+
+impl Reorder {
+    /// The result has fewer items than `items` if `self` is `Unique` and there are duplicates.
+    pub fn apply(&self, items: Vec<String>) -> Vec<String> {
+        match self {
+            // `sorted_by` is stable, so equal items keep their order
+            Reorder::Sort(key) => items
+                .into_iter()
+                .sorted_by(|a, b| key.compare(a, b))
+                .collect(),
+            Reorder::Reverse => items.into_iter().rev().collect(),
+            Reorder::Unique => items.into_iter().unique().collect(),
+            Reorder::Shuffle => {
+                // A random permutation without depending on a random number generator,
+                // because every `RandomState` hashes with different random keys
+                let state = RandomState::new();
+                items
+                    .into_iter()
+                    .enumerate()
+                    .sorted_by_cached_key(|(index, _)| state.hash_one(index))
+                    .map(|(_, item)| item)
+                    .collect()
+            }
+        }
+    }
+}
+
+impl SortKey {
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            SortKey::Lexicographic => a.cmp(b),
+            SortKey::CaseInsensitive => a.to_lowercase().cmp(&b.to_lowercase()),
+            SortKey::Natural => natural_cmp(a, b),
+            SortKey::Length => a.chars().count().cmp(&b.chars().count()),
+            SortKey::Regex(regex) => {
+                let key = |item: &'_ str| -> Option<String> {
+                    let captures = regex.0.captures(item).ok()??;
+                    Some(captures.get(1).or(captures.get(0))?.as_str().to_string())
+                };
+                match (key(a), key(b)) {
+                    (Some(a), Some(b)) => natural_cmp(&a, &b),
+                    (a, b) => a.cmp(&b),
+                }
+            }
+        }
+    }
+}
+
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    fn chunks(string: &str) -> impl Iterator<Item = &str> {
+        lazy_regex::regex!(r"\d+|\D+")
+            .find_iter(string)
+            .map(|m| m.as_str())
+    }
+    chunks(a)
+        .zip_longest(chunks(b))
+        .map(|pair| match pair {
+            itertools::EitherOrBoth::Both(a, b) => {
+                if a.starts_with(|c: char| c.is_ascii_digit())
+                    && b.starts_with(|c: char| c.is_ascii_digit())
+                {
+                    let (a_value, b_value) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+                    a_value
+                        .len()
+                        .cmp(&b_value.len())
+                        .then_with(|| a_value.cmp(b_value))
+                        // `01` comes after `1`
+                        .then_with(|| a.len().cmp(&b.len()))
+                } else {
+                    a.cmp(b)
+                }
+            }
+            itertools::EitherOrBoth::Left(_) => Ordering::Greater,
+            itertools::EitherOrBoth::Right(_) => Ordering::Less,
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
 #[derive(Debug, Clone)]
 pub struct MyRegex(pub fancy_regex::Regex);
 
@@ -85,6 +258,10 @@ impl Transformation {
 
                 result
             }),
+            Transformation::HardWrap(HardWrap {
+                width,
+                line_comment_prefix,
+            }) => Ok(hard_wrap(&string, *width, line_comment_prefix.as_deref())),
             Transformation::PipeToShell { command } => {
                 ProcessCommand::new("bash", ["-c".to_string(), command.to_string()].as_ref())
                     .run_with_input(&string)
@@ -110,13 +287,128 @@ impl Transformation {
                     format!("{open} {string} {close}")
                 })
             }
+            Transformation::Increment(Increment {
+                amount,
+                progressive,
+                word_rings,
+            }) => {
+                let amount = if *progressive {
+                    amount * (selection_index as i64 + 1)
+                } else {
+                    *amount
+                };
+                Ok(increment(&string, amount, word_rings).unwrap_or(string))
+            }
+            Transformation::Reorder(reorder) => {
+                // The line endings stay in place, so that CRLF and the final newline are kept
+                let (lines, endings): (Vec<_>, Vec<_>) = string
+                    .split_inclusive('\n')
+                    .map(|line| {
+                        let content = line
+                            .strip_suffix('\n')
+                            .map_or(line, |line| line.strip_suffix('\r').unwrap_or(line));
+                        (content.to_string(), &line[content.len()..])
+                    })
+                    .unzip();
+                let lines = reorder.apply(lines);
+                let count = lines.len();
+                Ok(lines
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, line)| {
+                        // `Unique` can drop lines, in which case the last line takes the last ending
+                        let ending = if index + 1 == count {
+                            endings.last().copied().unwrap_or_default()
+                        } else {
+                            endings[index]
+                        };
+                        format!("{line}{ending}")
+                    })
+                    .collect())
+            }
+            Transformation::Convert(conversion) => conversion.apply(&string),
+            Transformation::Evaluate { keep_expression } => {
+                // The surrounding whitespace is kept, such as the newline of a selected line
+                let trimmed = string.trim();
+                let leading = &string[..string.len() - string.trim_start().len()];
+                let trailing = &string[string.trim_end().len()..];
+                let result = if *keep_expression {
+                    // The previous value is dropped, so that an edited expression can be re-evaluated
+                    let expression = trimmed
+                        .split_once('=')
+                        .map_or(trimmed, |(expression, _)| expression)
+                        .trim();
+                    format!("{expression} = {}", arithmetic::evaluate(expression)?)
+                } else {
+                    arithmetic::evaluate(trimmed)?.to_string()
+                };
+                Ok(format!("{leading}{result}{trailing}"))
+            }
+        }
+    }
+}
+
+fn hard_wrap(string: &str, width: usize, line_comment_prefix: Option<&str>) -> String {
+    // The length of the indentation and comment prefix of a line
+    let leader_len = |line: &str| {
+        let indentation = line.len() - line.trim_start().len();
+        let prefix = line_comment_prefix
+            .filter(|prefix| line[indentation..].starts_with(prefix))
+            .map(|prefix| {
+                let rest = &line[indentation + prefix.len()..];
+                prefix.len() + rest.len() - rest.trim_start().len()
+            })
+            .unwrap_or(0);
+        indentation + prefix
+    };
+    let is_blank = |line: &str| line[leader_len(line)..].trim().is_empty();
+    // The indentation and comment prefix of a commented line
+    let comment_leader = |line: &str| {
+        let indentation = line.len() - line.trim_start().len();
+        line_comment_prefix
+            .filter(|prefix| line[indentation..].starts_with(prefix))
+            .map(|prefix| &line[..indentation + prefix.len()])
+    };
+    let lines = string.lines().collect_vec();
+    let mut result = Vec::new();
+    // A paragraph also ends where the comment prefix changes or stops,
+    // so that code next to a comment is never joined with it
+    for ((blank, _), paragraph) in &lines
+        .into_iter()
+        .chunk_by(|line| (is_blank(line), comment_leader(line)))
+    {
+        if blank {
+            result.extend(paragraph.map(|line| line.trim_end().to_string()));
+            continue;
+        }
+        let paragraph = paragraph.collect_vec();
+        let leader = &paragraph[0][..leader_len(paragraph[0])];
+        let leader_width = leader.chars().count();
+        let mut current = String::new();
+        for word in paragraph
+            .iter()
+            .flat_map(|line| line[leader_len(line)..].split_whitespace())
+        {
+            if !current.is_empty()
+                && leader_width + current.chars().count() + 1 + word.chars().count() > width
+            {
+                result.push(format!("{leader}{current}"));
+                current.clear();
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
         }
+        result.push(format!("{leader}{current}"));
     }
+    let trailing_newline = if string.ends_with('\n') { "\n" } else { "" };
+    format!("{}{trailing_newline}", result.join("\n"))
 }
 
 #[cfg(test)]
 mod test_transformation {
-    use super::Transformation;
+    use super::{HardWrap, Increment, MyRegex, Reorder, SortKey, Transformation};
 
     #[test]
     fn unwrap() {
@@ -163,4 +455,111 @@ who lives in a pineapple under the sea? Spongebob Squarepants! absorbent and yel
             "hello"
         );
     }
+
+    #[test]
+    fn progressive_increment() {
+        let transformation = Transformation::Increment(Increment {
+            amount: 1,
+            progressive: true,
+            word_rings: Vec::new(),
+        });
+        assert_eq!(
+            (0..3)
+                .map(|index| transformation.apply(index, "item0".to_string()).unwrap())
+                .collect::<Vec<_>>(),
+            ["item1", "item2", "item3"]
+        );
+    }
+
+    #[test]
+    fn hard_wrap() {
+        let transformation = Transformation::HardWrap(HardWrap {
+            width: 22,
+            line_comment_prefix: Some("//".to_string()),
+        });
+        assert_eq!(
+            transformation
+                .apply(
+                    0,
+                    "    // who lives in a pineapple\n    // under the sea?\n    //\n    // Spongebob\n"
+                        .to_string()
+                )
+                .unwrap(),
+            "    // who lives in a\n    // pineapple under\n    // the sea?\n    //\n    // Spongebob\n"
+        );
+        assert_eq!(
+            transformation
+                .apply(
+                    0,
+                    "// who lives in a\nlet pineapple = under_the_sea;\n".to_string()
+                )
+                .unwrap(),
+            "// who lives in a\nlet pineapple =\nunder_the_sea;\n"
+        );
+    }
+
+    #[test]
+    fn reorder_lines() {
+        let run_test = |reorder: Reorder, input: &str, expected: &str| {
+            assert_eq!(
+                Transformation::Reorder(reorder)
+                    .apply(0, input.to_string())
+                    .unwrap(),
+                expected
+            );
+        };
+        run_test(
+            Reorder::Sort(SortKey::Lexicographic),
+            "b\nB\na10\na9\n",
+            "B\na10\na9\nb\n",
+        );
+        run_test(
+            Reorder::Sort(SortKey::CaseInsensitive),
+            "b\nA\na\n",
+            "A\na\nb\n",
+        );
+        run_test(
+            Reorder::Sort(SortKey::Natural),
+            "a10\na9\na09\nb",
+            "a9\na09\na10\nb",
+        );
+        run_test(Reorder::Sort(SortKey::Length), "ccc\na\nbb", "a\nbb\nccc");
+        run_test(
+            Reorder::Sort(SortKey::Regex(MyRegex(
+                fancy_regex::Regex::new(r"id=(\d+)").unwrap(),
+            ))),
+            "x id=10\ny id=2\nz",
+            "z\ny id=2\nx id=10",
+        );
+        run_test(Reorder::Reverse, "a\nb\nc", "c\nb\na");
+        run_test(Reorder::Unique, "a\nb\na\nb\nc", "a\nb\nc");
+        run_test(Reorder::Unique, "a\nb\na\nb\nc\n", "a\nb\nc\n");
+        run_test(
+            Reorder::Sort(SortKey::Lexicographic),
+            "b\r\nc\r\na\r\n",
+            "a\r\nb\r\nc\r\n",
+        );
+        run_test(Reorder::Reverse, "a\r\nb", "b\r\na");
+    }
+
+    #[test]
+    fn evaluate() {
+        let evaluate = |keep_expression: bool, input: &str| {
+            Transformation::Evaluate { keep_expression }
+                .apply(0, input.to_string())
+                .unwrap()
+        };
+        assert_eq!(evaluate(false, " 0x10 * 2 "), " 32 ");
+        assert_eq!(evaluate(false, "0x10 * 2\n"), "32\n");
+        assert_eq!(evaluate(true, "1 + 2"), "1 + 2 = 3");
+        assert_eq!(evaluate(true, "  1 + 3 = 3\r\n"), "  1 + 3 = 4\r\n");
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation() {
+        let items = (0..100).map(|index| index.to_string()).collect::<Vec<_>>();
+        let mut shuffled = Reorder::Shuffle.apply(items.clone());
+        shuffled.sort_by(|a, b| super::natural_cmp(a, b));
+        assert_eq!(shuffled, items);
+    }
 }